@@ -1,6 +1,20 @@
 // Common library for celestia-recursion workspace
+#![no_std]
 
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub mod mmr;
+
+/// Trusting period the program verifies each hop under: two weeks.
+pub const TRUSTING_PERIOD_SECS: u64 = 14 * 24 * 60 * 60;
+
+/// Trust threshold the program verifies each hop under, as numerator and denominator. This is the
+/// light client default, `TrustThreshold::ONE_THIRD`.
+pub const TRUST_THRESHOLD: (u64, u64) = (1, 3);
 
 #[derive(Serialize, Deserialize)]
 #[repr(u8)]
@@ -9,7 +23,7 @@ pub enum ProofType {
     Groth16,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Groth16VkeyCheckpoint {
     // We remove block height from the checkpoint, since RPCs usually have mappings of block hash to block height.
     // if this becomes annoying we can add it back in an upgrade
@@ -17,4 +31,140 @@ pub struct Groth16VkeyCheckpoint {
     pub block_hash: [u8; 32],
     pub groth16_vk: Option<Vec<u8>>,
     pub program_vk_hash: [u32; 8],
-}
\ No newline at end of file
+}
+
+impl Groth16VkeyCheckpoint {
+    /// Extends the hash of the checkpoints before this one by this one.
+    pub fn extend_history(&self, previous: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(previous);
+        hasher.update(self.block_hash);
+        for word in self.program_vk_hash {
+            hasher.update(word.to_le_bytes());
+        }
+        match &self.groth16_vk {
+            Some(vk) => {
+                hasher.update([1]);
+                hasher.update((vk.len() as u64).to_le_bytes());
+                hasher.update(vk);
+            }
+            None => hasher.update([0]),
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// The running hash of a checkpoint history, which the recursion program commits in place of the
+/// history itself. An empty history hashes to zero.
+pub fn checkpoint_history_hash(checkpoints: &[Groth16VkeyCheckpoint]) -> [u8; 32] {
+    checkpoints
+        .iter()
+        .fold([0; 32], |hash, checkpoint| checkpoint.extend_history(&hash))
+}
+
+/// The public values committed by the recursion program, in commit order.
+///
+/// bincode encodes a struct as its fields back to back, so this decodes the public values of a
+/// proof in one go.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct RecursionPublicValues {
+    /// [`checkpoint_history_hash`] of the upgrade history. The history itself is published
+    /// alongside the proofs.
+    pub checkpoints_hash: [u8; 32],
+    pub genesis_hash: Vec<u8>,
+    pub h2_hash: Vec<u8>,
+    pub vk_digest: [u32; 8],
+    /// Root of the [`mmr::PathAccumulator`] of every head verified along the way, this proof's
+    /// `h2` last.
+    pub path_root: [u8; 32],
+}
+
+/// The public values of recursion proofs from before [`checkpoint_history_hash`] and the path
+/// root: the whole upgrade history, and no path. Upgrades can still build on them.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct LegacyRecursionPublicValues {
+    pub checkpoints: Vec<Groth16VkeyCheckpoint>,
+    pub genesis_hash: Vec<u8>,
+    pub h2_hash: Vec<u8>,
+    pub vk_digest: [u32; 8],
+}
+
+impl LegacyRecursionPublicValues {
+    /// The same values in the current layout. The path accumulator starts over from the upgrade,
+    /// so the path root is that of an empty path.
+    pub fn upgrade(self) -> RecursionPublicValues {
+        RecursionPublicValues {
+            checkpoints_hash: checkpoint_history_hash(&self.checkpoints),
+            genesis_hash: self.genesis_hash,
+            h2_hash: self.h2_hash,
+            vk_digest: self.vk_digest,
+            path_root: mmr::PathAccumulator::default().root(),
+        }
+    }
+}
+
+/// The public values committed by the blob inclusion program.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct BlobInclusionPublicValues {
+    /// Namespace of the blob: the version byte followed by the 28 byte id.
+    pub namespace: [u8; 29],
+    /// Share commitment of the blob, as found in its `MsgPayForBlobs`.
+    pub commitment: [u8; 32],
+    /// Height of the block the blob was posted in.
+    pub height: u64,
+    /// Hash of the head proven by the recursion proof, which the blob's block is linked to.
+    pub head_hash: [u8; 32],
+    /// The recursion proof's genesis hash and program vk digest, which a verifier must trust.
+    pub genesis_hash: [u8; 32],
+    pub recursion_vk_digest: [u32; 8],
+}
+
+/// The public values committed by the namespace completeness program.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct NamespaceCompletenessPublicValues {
+    /// The namespace: the version byte followed by the 28 byte id.
+    pub namespace: [u8; 29],
+    /// Share commitments of every blob of the namespace in the block, in block order. Empty when
+    /// the namespace has no data in the block.
+    pub commitments: Vec<[u8; 32]>,
+    /// Height of the block.
+    pub height: u64,
+    /// Hash of the head proven by the recursion proof, which the block is linked to.
+    pub head_hash: [u8; 32],
+    /// The recursion proof's genesis hash and program vk digest, which a verifier must trust.
+    pub genesis_hash: [u8; 32],
+    pub recursion_vk_digest: [u32; 8],
+}
+
+/// The public values committed by the state proof program.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct StatePublicValues {
+    /// Name of the store the key is in, e.g. `bank`.
+    pub store: String,
+    pub key: Vec<u8>,
+    /// Value of the key, or `None` if the key is proven absent.
+    pub value: Option<Vec<u8>>,
+    /// Height of the state: as left by executing this block, which the next block's `app_hash`
+    /// commits to.
+    pub height: u64,
+    /// Hash of the head proven by the recursion proof, which the block whose `app_hash` the state
+    /// is proven against is linked to.
+    pub head_hash: [u8; 32],
+    /// The recursion proof's genesis hash and program vk digest, which a verifier must trust.
+    pub genesis_hash: [u8; 32],
+    pub recursion_vk_digest: [u32; 8],
+}
+
+/// The public values committed by the ancestor header program.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct AncestorHeaderPublicValues {
+    /// Hash, height and data root (`data_hash`) of the target header.
+    pub hash: [u8; 32],
+    pub height: u64,
+    pub data_root: [u8; 32],
+    /// Hash of the head proven by the recursion proof, which the target is an ancestor of.
+    pub head_hash: [u8; 32],
+    /// The recursion proof's genesis hash and program vk digest, which a verifier must trust.
+    pub genesis_hash: [u8; 32],
+    pub recursion_vk_digest: [u32; 8],
+}
@@ -0,0 +1,253 @@
+//! A Merkle mountain range over the heads the recursion verified, one leaf per hop, so that a
+//! proof can commit to its whole path with one root.
+//!
+//! Leaves, nodes and the root are SHA-256 hashes with distinct one-byte prefixes. The root covers
+//! the number of leaves and the peaks, highest first.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+const ROOT_PREFIX: u8 = 2;
+
+/// A header verified by one hop of the recursion: its `h2`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PathLeaf {
+    pub hash: [u8; 32],
+    pub height: u64,
+    /// Header time, in nanoseconds since the Unix epoch.
+    pub time_nanos: i128,
+}
+
+impl PathLeaf {
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(self.hash);
+        hasher.update(self.height.to_be_bytes());
+        hasher.update(self.time_nanos.to_be_bytes());
+        hasher.finalize().into()
+    }
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn bag_peaks(leaves: u64, peaks: &[[u8; 32]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ROOT_PREFIX]);
+    hasher.update(leaves.to_be_bytes());
+    for peak in peaks {
+        hasher.update(peak);
+    }
+    hasher.finalize().into()
+}
+
+/// The state needed to extend the range: its leaf count and the roots of its perfect subtrees.
+///
+/// Peak `i` is the root of a subtree of `2^b` leaves, for the `i`th highest bit `b` set in
+/// `leaves`.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct PathAccumulator {
+    pub leaves: u64,
+    pub peaks: Vec<[u8; 32]>,
+}
+
+impl PathAccumulator {
+    pub fn from_leaves(leaves: &[[u8; 32]]) -> Self {
+        let mut accumulator = PathAccumulator::default();
+        for leaf in leaves {
+            accumulator.push(*leaf);
+        }
+        accumulator
+    }
+
+    /// Appends a leaf digest, merging the peaks of equal height it completes.
+    pub fn push(&mut self, leaf: [u8; 32]) {
+        let mut node = leaf;
+        let mut height = 0;
+        while (self.leaves >> height) & 1 == 1 {
+            let left = self.peaks.pop().expect("fewer peaks than leaf count bits");
+            node = hash_node(&left, &node);
+            height += 1;
+        }
+        self.peaks.push(node);
+        self.leaves += 1;
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        bag_peaks(self.leaves, &self.peaks)
+    }
+}
+
+/// Proves that a leaf is in a range: the path from the leaf up to its peak, and every peak.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PathProof {
+    /// Position of the leaf, counting from the first hop.
+    pub index: u64,
+    pub leaves: u64,
+    /// Siblings from the leaf up to its peak.
+    pub siblings: Vec<[u8; 32]>,
+    pub peaks: Vec<[u8; 32]>,
+}
+
+impl PathProof {
+    /// Builds the proof of leaf `index` of `leaves`, or `None` if it is out of range.
+    pub fn new(leaves: &[[u8; 32]], index: u64) -> Option<Self> {
+        let count = leaves.len() as u64;
+        let (start, size, _) = peak_of(count, index)?;
+
+        let mut level = leaves[start as usize..(start + size) as usize].to_vec();
+        let mut position = (index - start) as usize;
+        let mut siblings = Vec::new();
+        while level.len() > 1 {
+            siblings.push(level[position ^ 1]);
+            level = level
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            position /= 2;
+        }
+
+        Some(PathProof {
+            index,
+            leaves: count,
+            siblings,
+            peaks: PathAccumulator::from_leaves(leaves).peaks,
+        })
+    }
+
+    /// Checks that `leaf` (a leaf digest) is at `index` in the range with the given root.
+    pub fn verify(&self, leaf: &[u8; 32], root: &[u8; 32]) -> bool {
+        if self.peaks.len() != self.leaves.count_ones() as usize {
+            return false;
+        }
+        let Some((start, size, peak)) = peak_of(self.leaves, self.index) else {
+            return false;
+        };
+        // The peak is a perfect tree of `size` leaves, so its height is that power of two.
+        if self.siblings.len() != size.trailing_zeros() as usize {
+            return false;
+        }
+
+        let mut node = *leaf;
+        let mut position = self.index - start;
+        for sibling in &self.siblings {
+            node = if position & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+            position >>= 1;
+        }
+        node == self.peaks[peak] && bag_peaks(self.leaves, &self.peaks) == *root
+    }
+}
+
+/// The first leaf, size and position of the peak that leaf `index` falls under.
+fn peak_of(leaves: u64, index: u64) -> Option<(u64, u64, usize)> {
+    let mut start = 0;
+    let mut peak = 0;
+    for height in (0..u64::BITS).rev() {
+        let size = 1u64 << height;
+        if leaves & size == 0 {
+            continue;
+        }
+        if index < start + size {
+            return Some((start, size, peak));
+        }
+        start += size;
+        peak += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn leaves(count: u64) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|height| {
+                PathLeaf {
+                    hash: [height as u8; 32],
+                    height,
+                    time_nanos: height as i128 * 6_000_000_000,
+                }
+                .digest()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_leaf_is_proven() {
+        for count in 1..20 {
+            let leaves = leaves(count);
+            let root = PathAccumulator::from_leaves(&leaves).root();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = PathProof::new(&leaves, index as u64).unwrap();
+                assert!(proof.verify(leaf, &root), "leaf {} of {}", index, count);
+            }
+            assert!(PathProof::new(&leaves, count).is_none());
+        }
+    }
+
+    #[test]
+    fn hostile_proof_is_rejected() {
+        let leaves = leaves(5);
+        let root = PathAccumulator::from_leaves(&leaves).root();
+        let proof = PathProof::new(&leaves, 2).unwrap();
+
+        // As many siblings as a shift of a u64 can take, and more.
+        for count in [63, 64, 65, 200] {
+            let mut hostile = proof.clone();
+            hostile.siblings = vec![[0; 32]; count];
+            assert!(!hostile.verify(&leaves[2], &root), "{} siblings", count);
+        }
+        // The largest range a u64 can count, with a single peak.
+        let hostile = PathProof {
+            index: u64::MAX - 1,
+            leaves: 1 << 63,
+            siblings: vec![[0; 32]; 64],
+            peaks: vec![[0; 32]],
+        };
+        assert!(!hostile.verify(&leaves[2], &root));
+        let hostile = PathProof {
+            index: u64::MAX - 1,
+            leaves: u64::MAX,
+            siblings: vec![[0; 32]; 64],
+            peaks: vec![[0; 32]; 64],
+        };
+        assert!(!hostile.verify(&leaves[2], &root));
+    }
+
+    #[test]
+    fn proof_does_not_verify_another_leaf() {
+        let leaves = leaves(11);
+        let root = PathAccumulator::from_leaves(&leaves).root();
+        let proof = PathProof::new(&leaves, 4).unwrap();
+        assert!(!proof.verify(&leaves[5], &root));
+
+        let mut moved = proof.clone();
+        moved.index = 5;
+        assert!(!moved.verify(&leaves[4], &root));
+    }
+
+    #[test]
+    fn root_commits_to_the_leaf_count() {
+        let leaves = leaves(4);
+        let accumulator = PathAccumulator::from_leaves(&leaves);
+        let mut recounted = accumulator.clone();
+        recounted.leaves = 5;
+        assert_eq!(accumulator.peaks, recounted.peaks);
+        assert_ne!(accumulator.root(), recounted.root());
+    }
+}
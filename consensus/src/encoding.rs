@@ -0,0 +1,538 @@
+//! A compact binary encoding of the light blocks the recursion program verifies, in place of CBOR.
+//!
+//! It carries only what verification reads: the signed header, and the keys and powers of both
+//! validator sets. Integers are little-endian and fixed width, hashes and keys raw bytes, and
+//! every variant a one-byte tag, so decoding reads fields straight out of the input without
+//! parsing strings.
+//!
+//! Every value has exactly one encoding, and the decoder rejects anything else: unknown tags,
+//! out of range values, validators out of order and trailing bytes. Validator addresses, names,
+//! proposer priorities and the proposer are not encoded. Addresses are derived from the keys, and
+//! none of the others are hashed or read by verification.
+
+use core::fmt;
+use tendermint::{
+    account,
+    block::{self, parts, CommitSig, Height, Round},
+    chain, node,
+    public_key::PublicKey,
+    validator, vote, AppHash, Hash, Signature, Time,
+};
+use tendermint_light_client_verifier::types::{LightBlock, SignedHeader, ValidatorSet};
+
+const HASH_NONE: u8 = 0;
+const HASH_SHA256: u8 = 1;
+
+const OPTION_NONE: u8 = 0;
+const OPTION_SOME: u8 = 1;
+
+// The CometBFT block ID flags.
+const FLAG_ABSENT: u8 = 1;
+const FLAG_COMMIT: u8 = 2;
+const FLAG_NIL: u8 = 3;
+
+const ED25519_KEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The light block has something the encoding cannot carry.
+    Unsupported(&'static str),
+    /// The bytes are not the encoding of any light block.
+    Invalid(&'static str),
+    UnexpectedEnd,
+    TrailingBytes,
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::Unsupported(what) => write!(f, "unsupported {}", what),
+            EncodingError::Invalid(what) => write!(f, "invalid {}", what),
+            EncodingError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            EncodingError::TrailingBytes => write!(f, "trailing bytes after light block"),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+pub fn encode_light_block(block: &LightBlock) -> Result<Vec<u8>, EncodingError> {
+    let mut out = Vec::new();
+    write_header(&mut out, &block.signed_header.header)?;
+    write_commit(&mut out, &block.signed_header.commit)?;
+    write_validators(&mut out, &block.validators)?;
+    write_validators(&mut out, &block.next_validators)?;
+    Ok(out)
+}
+
+/// Decodes a light block from [`encode_light_block`]. Its provider is a placeholder.
+pub fn decode_light_block(bytes: &[u8]) -> Result<LightBlock, EncodingError> {
+    let mut reader = Reader { bytes };
+    let header = reader.header()?;
+    let commit = reader.commit()?;
+    let validators = reader.validators()?;
+    let next_validators = reader.validators()?;
+    if !reader.bytes.is_empty() {
+        return Err(EncodingError::TrailingBytes);
+    }
+
+    let signed_header =
+        SignedHeader::new(header, commit).map_err(|_| EncodingError::Invalid("commit height"))?;
+    Ok(LightBlock::new(
+        signed_header,
+        validators,
+        next_validators,
+        node::Id::new([0; 20]),
+    ))
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) -> Result<(), EncodingError> {
+    let len = u32::try_from(len).map_err(|_| EncodingError::Unsupported("length"))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn write_time(out: &mut Vec<u8>, time: Time) {
+    let nanos = time.unix_timestamp_nanos();
+    let secs = nanos.div_euclid(NANOS_PER_SECOND) as i64;
+    out.extend_from_slice(&secs.to_le_bytes());
+    out.extend_from_slice(&(nanos.rem_euclid(NANOS_PER_SECOND) as u32).to_le_bytes());
+}
+
+fn write_hash(out: &mut Vec<u8>, hash: &Hash) {
+    match hash {
+        Hash::None => out.push(HASH_NONE),
+        Hash::Sha256(bytes) => {
+            out.push(HASH_SHA256);
+            out.extend_from_slice(bytes);
+        }
+    }
+}
+
+fn write_option_hash(out: &mut Vec<u8>, hash: &Option<Hash>) {
+    match hash {
+        None => out.push(OPTION_NONE),
+        Some(hash) => {
+            out.push(OPTION_SOME);
+            write_hash(out, hash);
+        }
+    }
+}
+
+fn write_block_id(out: &mut Vec<u8>, id: &block::Id) {
+    write_hash(out, &id.hash);
+    out.extend_from_slice(&id.part_set_header.total.to_le_bytes());
+    write_hash(out, &id.part_set_header.hash);
+}
+
+fn write_header(out: &mut Vec<u8>, header: &block::Header) -> Result<(), EncodingError> {
+    out.extend_from_slice(&header.version.block.to_le_bytes());
+    out.extend_from_slice(&header.version.app.to_le_bytes());
+    let chain_id = header.chain_id.as_str().as_bytes();
+    write_len(out, chain_id.len())?;
+    out.extend_from_slice(chain_id);
+    out.extend_from_slice(&header.height.value().to_le_bytes());
+    write_time(out, header.time);
+    match &header.last_block_id {
+        None => out.push(OPTION_NONE),
+        Some(id) => {
+            out.push(OPTION_SOME);
+            write_block_id(out, id);
+        }
+    }
+    write_option_hash(out, &header.last_commit_hash);
+    write_option_hash(out, &header.data_hash);
+    write_hash(out, &header.validators_hash);
+    write_hash(out, &header.next_validators_hash);
+    write_hash(out, &header.consensus_hash);
+    write_len(out, header.app_hash.as_bytes().len())?;
+    out.extend_from_slice(header.app_hash.as_bytes());
+    write_option_hash(out, &header.last_results_hash);
+    write_option_hash(out, &header.evidence_hash);
+    out.extend_from_slice(header.proposer_address.as_bytes());
+    Ok(())
+}
+
+fn write_commit(out: &mut Vec<u8>, commit: &block::Commit) -> Result<(), EncodingError> {
+    out.extend_from_slice(&commit.height.value().to_le_bytes());
+    out.extend_from_slice(&commit.round.value().to_le_bytes());
+    write_block_id(out, &commit.block_id);
+    write_len(out, commit.signatures.len())?;
+    for signature in &commit.signatures {
+        let (flag, validator_address, timestamp, signature) = match signature {
+            CommitSig::BlockIdFlagAbsent => {
+                out.push(FLAG_ABSENT);
+                continue;
+            }
+            CommitSig::BlockIdFlagCommit {
+                validator_address,
+                timestamp,
+                signature,
+            } => (FLAG_COMMIT, validator_address, timestamp, signature),
+            CommitSig::BlockIdFlagNil {
+                validator_address,
+                timestamp,
+                signature,
+            } => (FLAG_NIL, validator_address, timestamp, signature),
+        };
+        out.push(flag);
+        out.extend_from_slice(validator_address.as_bytes());
+        write_time(out, *timestamp);
+        match signature {
+            None => out.push(OPTION_NONE),
+            Some(signature) if signature.as_bytes().len() == SIGNATURE_SIZE => {
+                out.push(OPTION_SOME);
+                out.extend_from_slice(signature.as_bytes());
+            }
+            Some(_) => return Err(EncodingError::Unsupported("signature size")),
+        }
+    }
+    Ok(())
+}
+
+fn write_validators(out: &mut Vec<u8>, validators: &ValidatorSet) -> Result<(), EncodingError> {
+    write_len(out, validators.validators().len())?;
+    for validator in validators.validators() {
+        let key = validator
+            .pub_key
+            .ed25519()
+            .ok_or(EncodingError::Unsupported("validator key type"))?;
+        if validator.address != account::Id::from(validator.pub_key) {
+            return Err(EncodingError::Unsupported(
+                "validator address not derived from its key",
+            ));
+        }
+        out.extend_from_slice(key.as_bytes());
+        out.extend_from_slice(&validator.power().to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Reads fields from the front of the input, borrowing rather than copying.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], EncodingError> {
+        if self.bytes.len() < len {
+            return Err(EncodingError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<&'a [u8; N], EncodingError> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, EncodingError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EncodingError> {
+        Ok(u32::from_le_bytes(*self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, EncodingError> {
+        Ok(u64::from_le_bytes(*self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize, EncodingError> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn option(&mut self) -> Result<bool, EncodingError> {
+        match self.u8()? {
+            OPTION_NONE => Ok(false),
+            OPTION_SOME => Ok(true),
+            _ => Err(EncodingError::Invalid("option tag")),
+        }
+    }
+
+    fn time(&mut self) -> Result<Time, EncodingError> {
+        let secs = i64::from_le_bytes(*self.array()?);
+        let nanos = self.u32()?;
+        Time::from_unix_timestamp(secs, nanos).map_err(|_| EncodingError::Invalid("time"))
+    }
+
+    fn height(&mut self) -> Result<Height, EncodingError> {
+        Height::try_from(self.u64()?).map_err(|_| EncodingError::Invalid("height"))
+    }
+
+    fn hash(&mut self) -> Result<Hash, EncodingError> {
+        match self.u8()? {
+            HASH_NONE => Ok(Hash::None),
+            HASH_SHA256 => Ok(Hash::Sha256(*self.array()?)),
+            _ => Err(EncodingError::Invalid("hash tag")),
+        }
+    }
+
+    fn option_hash(&mut self) -> Result<Option<Hash>, EncodingError> {
+        Ok(if self.option()? {
+            Some(self.hash()?)
+        } else {
+            None
+        })
+    }
+
+    fn address(&mut self) -> Result<account::Id, EncodingError> {
+        Ok(account::Id::new(*self.array()?))
+    }
+
+    fn block_id(&mut self) -> Result<block::Id, EncodingError> {
+        let hash = self.hash()?;
+        let total = self.u32()?;
+        let part_set_header = parts::Header::new(total, self.hash()?)
+            .map_err(|_| EncodingError::Invalid("part set header"))?;
+        Ok(block::Id {
+            hash,
+            part_set_header,
+        })
+    }
+
+    fn header(&mut self) -> Result<block::Header, EncodingError> {
+        let version = block::header::Version {
+            block: self.u64()?,
+            app: self.u64()?,
+        };
+        let len = self.len()?;
+        let chain_id = core::str::from_utf8(self.take(len)?)
+            .ok()
+            .and_then(|id| chain::Id::try_from(id).ok())
+            .ok_or(EncodingError::Invalid("chain id"))?;
+        let height = self.height()?;
+        let time = self.time()?;
+        let last_block_id = if self.option()? {
+            Some(self.block_id()?)
+        } else {
+            None
+        };
+        let last_commit_hash = self.option_hash()?;
+        let data_hash = self.option_hash()?;
+        let validators_hash = self.hash()?;
+        let next_validators_hash = self.hash()?;
+        let consensus_hash = self.hash()?;
+        let len = self.len()?;
+        let app_hash = AppHash::try_from(self.take(len)?.to_vec())
+            .map_err(|_| EncodingError::Invalid("app hash"))?;
+        let last_results_hash = self.option_hash()?;
+        let evidence_hash = self.option_hash()?;
+        let proposer_address = self.address()?;
+        Ok(block::Header {
+            version,
+            chain_id,
+            height,
+            time,
+            last_block_id,
+            last_commit_hash,
+            data_hash,
+            validators_hash,
+            next_validators_hash,
+            consensus_hash,
+            app_hash,
+            last_results_hash,
+            evidence_hash,
+            proposer_address,
+        })
+    }
+
+    fn commit(&mut self) -> Result<block::Commit, EncodingError> {
+        let height = self.height()?;
+        let round = Round::try_from(self.u32()?).map_err(|_| EncodingError::Invalid("round"))?;
+        let block_id = self.block_id()?;
+        let count = self.len()?;
+        // Every signature takes at least its flag byte, so a bad count cannot over-allocate.
+        let mut signatures = Vec::with_capacity(count.min(self.bytes.len()));
+        for _ in 0..count {
+            let flag = self.u8()?;
+            if flag == FLAG_ABSENT {
+                signatures.push(CommitSig::BlockIdFlagAbsent);
+                continue;
+            }
+            let validator_address = self.address()?;
+            let timestamp = self.time()?;
+            let signature = if self.option()? {
+                let bytes: &[u8; SIGNATURE_SIZE] = self.array()?;
+                Some(Signature::try_from(bytes.as_slice()).expect("signature has 64 bytes"))
+            } else {
+                None
+            };
+            signatures.push(match flag {
+                FLAG_COMMIT => CommitSig::BlockIdFlagCommit {
+                    validator_address,
+                    timestamp,
+                    signature,
+                },
+                FLAG_NIL => CommitSig::BlockIdFlagNil {
+                    validator_address,
+                    timestamp,
+                    signature,
+                },
+                _ => return Err(EncodingError::Invalid("block id flag")),
+            });
+        }
+        Ok(block::Commit {
+            height,
+            round,
+            block_id,
+            signatures,
+        })
+    }
+
+    fn validators(&mut self) -> Result<ValidatorSet, EncodingError> {
+        let count = self.len()?;
+        let mut validators: Vec<validator::Info> =
+            Vec::with_capacity(count.min(self.bytes.len() / (ED25519_KEY_SIZE + 8)));
+        let mut total: u64 = 0;
+        for _ in 0..count {
+            let key: &[u8; ED25519_KEY_SIZE] = self.array()?;
+            let pub_key =
+                PublicKey::from_raw_ed25519(key).ok_or(EncodingError::Invalid("validator key"))?;
+            let power = vote::Power::try_from(self.u64()?)
+                .map_err(|_| EncodingError::Invalid("voting power"))?;
+            let validator = validator::Info::new(pub_key, power);
+
+            // The set sorts by power, highest first, then by address. Only that order is valid,
+            // so that the same set has one encoding.
+            if let Some(previous) = validators.last() {
+                if (core::cmp::Reverse(previous.power), previous.address)
+                    >= (core::cmp::Reverse(validator.power), validator.address)
+                {
+                    return Err(EncodingError::Invalid("validator order"));
+                }
+            }
+            total = total
+                .checked_add(validator.power())
+                .filter(|total| *total <= ValidatorSet::MAX_TOTAL_VOTING_POWER)
+                .ok_or(EncodingError::Invalid("total voting power"))?;
+            validators.push(validator);
+        }
+        Ok(ValidatorSet::without_proposer(validators))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tendermint_testgen::{
+        Generator, Header as TestgenHeader, LightBlock as TestgenLightBlock, Validator,
+    };
+
+    fn light_block() -> LightBlock {
+        let vals = [
+            Validator::new("a").voting_power(40),
+            Validator::new("b").voting_power(30),
+            Validator::new("c").voting_power(30),
+        ];
+        let next_vals = [Validator::new("d").voting_power(10)];
+        let header = TestgenHeader::new(&vals)
+            .height(7)
+            .chain_id("mocha-4")
+            .next_validators(&next_vals)
+            .time(Time::from_unix_timestamp(1_700_000_000, 123_456_789).unwrap());
+        let block = TestgenLightBlock::new_default_with_header(header)
+            .next_validators(&next_vals)
+            .generate()
+            .unwrap();
+        let mut block = LightBlock::new(
+            block.signed_header,
+            block.validators,
+            block.next_validators,
+            block.provider,
+        );
+        block.signed_header.commit.signatures[1] = CommitSig::BlockIdFlagAbsent;
+        block
+    }
+
+    #[test]
+    fn round_trip_keeps_what_verification_reads() {
+        let mut block = light_block();
+        let header = &mut block.signed_header.header;
+        header.last_block_id = Some(block.signed_header.commit.block_id);
+        header.evidence_hash = Some(Hash::None);
+        let decoded = decode_light_block(&encode_light_block(&block).unwrap()).unwrap();
+        assert_eq!(decoded.signed_header, block.signed_header);
+        assert_eq!(
+            decoded.signed_header.header.hash(),
+            block.signed_header.header.hash()
+        );
+        assert_eq!(
+            decoded.validators.validators(),
+            block.validators.validators()
+        );
+        assert_eq!(decoded.validators.hash(), block.validators.hash());
+        assert_eq!(decoded.next_validators.hash(), block.next_validators.hash());
+        assert_eq!(
+            encode_light_block(&decoded).unwrap(),
+            encode_light_block(&block).unwrap()
+        );
+    }
+
+    #[test]
+    fn truncated_or_extended_input_is_rejected() {
+        let bytes = encode_light_block(&light_block()).unwrap();
+        for len in 0..bytes.len() {
+            assert!(
+                decode_light_block(&bytes[..len]).is_err(),
+                "prefix of {}",
+                len
+            );
+        }
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(
+            decode_light_block(&extended).unwrap_err(),
+            EncodingError::TrailingBytes
+        );
+    }
+
+    #[test]
+    fn non_canonical_input_is_rejected() {
+        let block = light_block();
+        let mut prefix = Vec::new();
+        write_header(&mut prefix, &block.signed_header.header).unwrap();
+        write_commit(&mut prefix, &block.signed_header.commit).unwrap();
+        let mut next_validators = Vec::new();
+        write_validators(&mut next_validators, &block.next_validators).unwrap();
+
+        // The first two validators swapped.
+        let mut validators = Vec::new();
+        write_validators(&mut validators, &block.validators).unwrap();
+        let entry = ED25519_KEY_SIZE + 8;
+        let (first, second) = validators[4..4 + 2 * entry].split_at(entry);
+        let swapped = [
+            &validators[..4],
+            second,
+            first,
+            &validators[4 + 2 * entry..],
+        ]
+        .concat();
+        assert_eq!(
+            decode_light_block(&[prefix.as_slice(), &swapped, &next_validators].concat())
+                .unwrap_err(),
+            EncodingError::Invalid("validator order")
+        );
+
+        // The header's time with a whole second of nanoseconds.
+        let mut bytes = encode_light_block(&block).unwrap();
+        let chain_id_len = "mocha-4".len();
+        let nanos = 8 + 8 + 4 + chain_id_len + 8 + 8;
+        bytes[nanos..nanos + 4].copy_from_slice(&1_000_000_000u32.to_le_bytes());
+        assert_eq!(
+            decode_light_block(&bytes).unwrap_err(),
+            EncodingError::Invalid("time")
+        );
+
+        // An unknown option tag for last_block_id.
+        let mut bytes = encode_light_block(&block).unwrap();
+        bytes[nanos + 4] = 2;
+        assert_eq!(
+            decode_light_block(&bytes).unwrap_err(),
+            EncodingError::Invalid("option tag")
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! What every program that builds on a recursion proof checks first: the proof, the head it
+//! proves, and how the header the program is about is linked to that head.
+
+use crate::recursion::{decode_public_values, ProofVerifier};
+use common::mmr::{PathLeaf, PathProof};
+use common::RecursionPublicValues;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use tendermint::block::Header;
+
+/// A head proven by a verified recursion proof.
+#[derive(Debug)]
+pub struct ProvenHead {
+    pub values: RecursionPublicValues,
+    pub header: Header,
+    pub hash: [u8; 32],
+    pub genesis_hash: [u8; 32],
+    /// The vk the recursion proof was verified with, which it commits too.
+    pub recursion_vk_digest: [u32; 8],
+}
+
+/// How the header a program proves something about is linked to the proven head.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum HeaderLink {
+    /// It is the head.
+    Head,
+    /// It is this many blocks below the head. The headers in between follow, newest first, each
+    /// the block named by the `last_block_id` of the one before, and the header itself last.
+    Ancestors(u64),
+    /// It was verified by a hop on the way to the head. The header follows, and this proves its
+    /// leaf against the committed path root.
+    Path(PathProof),
+    /// It is `count` blocks below a header verified by a hop on the way to the head. That header
+    /// follows, proven as for [`HeaderLink::Path`], then the headers below it as for
+    /// [`HeaderLink::Ancestors`].
+    PathAncestors { proof: PathProof, count: u64 },
+}
+
+impl HeaderLink {
+    /// How many headers follow the link.
+    pub fn header_count(&self) -> u64 {
+        match self {
+            HeaderLink::Head => 0,
+            HeaderLink::Ancestors(count) => *count,
+            HeaderLink::Path(_) => 1,
+            HeaderLink::PathAncestors { count, .. } => count + 1,
+        }
+    }
+}
+
+/// Why a recursion proof or a header linked to its head does not verify. The messages are the
+/// programs' panic messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeadError {
+    Proof(String),
+    MalformedPublicValues,
+    /// The proof does not commit the vk it was verified with.
+    Vk,
+    /// The header is not the proven head.
+    Head,
+    /// The headers after a [`HeaderLink`] are not as many as it says.
+    LinkedHeaders,
+    Ancestor(String),
+    PathProof,
+}
+
+impl fmt::Display for HeadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeadError::Proof(e) => write!(f, "Failed to verify recursion proof: {}", e),
+            HeadError::MalformedPublicValues => {
+                write!(f, "Recursion proof's public values are malformed")
+            }
+            HeadError::Vk => write!(f, "Recursion proof must commit the vk it was verified with"),
+            HeadError::Head => write!(f, "Header must be the head proven by the recursion proof"),
+            HeadError::LinkedHeaders => {
+                write!(f, "Linked headers must be as many as the link says")
+            }
+            HeadError::Ancestor(e) => write!(f, "Ancestor does not chain to the head: {}", e),
+            HeadError::PathProof => {
+                write!(
+                    f,
+                    "Header must be on the path proven by the recursion proof"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeadError {}
+
+fn header_hash(header: &Header) -> [u8; 32] {
+    header
+        .hash()
+        .as_bytes()
+        .try_into()
+        .expect("header hash is 32 bytes")
+}
+
+/// Verifies a recursion proof of the program with `vk_digest`, given its public values, and that
+/// `head` is the head it proves.
+///
+/// This does not make the proof one of the recursion program: any program can commit the same
+/// layout. Only a verifier that pins `recursion_vk_digest`, as committed by the program building
+/// on it, rules that out.
+pub fn verify_recursion_head(
+    vk_digest: &[u32; 8],
+    public_values: &[u8],
+    head: Header,
+    verifier: &impl ProofVerifier,
+) -> Result<ProvenHead, HeadError> {
+    let pv_digest: [u8; 32] = Sha256::digest(public_values).into();
+    verifier
+        .verify_sp1_proof(vk_digest, &pv_digest)
+        .map_err(HeadError::Proof)?;
+
+    let values =
+        decode_public_values(public_values, false).map_err(|_| HeadError::MalformedPublicValues)?;
+    if values.vk_digest != *vk_digest {
+        return Err(HeadError::Vk);
+    }
+    let genesis_hash = values
+        .genesis_hash
+        .as_slice()
+        .try_into()
+        .map_err(|_| HeadError::MalformedPublicValues)?;
+
+    let hash = header_hash(&head);
+    if hash.as_slice() != values.h2_hash {
+        return Err(HeadError::Head);
+    }
+    Ok(ProvenHead {
+        values,
+        header: head,
+        hash,
+        genesis_hash,
+        recursion_vk_digest: *vk_digest,
+    })
+}
+
+/// Checks that `ancestors` are the headers below `head`, newest first, each one the block named
+/// by the `last_block_id` of the one before. Returns the last one, or the head if there are none.
+pub fn walk_ancestors<'a>(
+    head: &'a Header,
+    ancestors: &'a [Header],
+) -> Result<&'a Header, HeadError> {
+    let mut child = head;
+    for parent in ancestors {
+        let last_block_id = child.last_block_id.ok_or_else(|| {
+            HeadError::Ancestor(format!("header {} has no last block id", child.height))
+        })?;
+        if last_block_id.hash != parent.hash() {
+            return Err(HeadError::Ancestor(format!(
+                "header {} is not the last block of header {}",
+                parent.height, child.height
+            )));
+        }
+        if parent.height.increment() != child.height {
+            return Err(HeadError::Ancestor(format!(
+                "header {} does not precede header {}",
+                parent.height, child.height
+            )));
+        }
+        child = parent;
+    }
+    Ok(child)
+}
+
+/// Checks that `header` was verified on the way to the head, by proving its leaf against the
+/// committed path root.
+fn check_path_header(
+    header: &Header,
+    proof: &PathProof,
+    path_root: &[u8; 32],
+) -> Result<(), HeadError> {
+    let leaf = PathLeaf {
+        hash: header_hash(header),
+        height: header.height.value(),
+        time_nanos: header.time.unix_timestamp_nanos(),
+    };
+    if !proof.verify(&leaf.digest(), path_root) {
+        return Err(HeadError::PathProof);
+    }
+    Ok(())
+}
+
+/// The header `link` names, given the headers that follow it and the proven `head` and path root.
+pub fn linked_header<'a>(
+    head: &'a Header,
+    path_root: &[u8; 32],
+    link: &HeaderLink,
+    headers: &'a [Header],
+) -> Result<&'a Header, HeadError> {
+    if headers.len() as u64 != link.header_count() {
+        return Err(HeadError::LinkedHeaders);
+    }
+    match link {
+        HeaderLink::Head => Ok(head),
+        HeaderLink::Ancestors(_) => walk_ancestors(head, headers),
+        HeaderLink::Path(proof) => {
+            check_path_header(&headers[0], proof, path_root)?;
+            Ok(&headers[0])
+        }
+        HeaderLink::PathAncestors { proof, .. } => {
+            let (start, ancestors) = headers.split_first().expect("one header per link at least");
+            check_path_header(start, proof, path_root)?;
+            walk_ancestors(start, ancestors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::mmr::PathAccumulator;
+    use std::cell::RefCell;
+    use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+
+    const VK: [u32; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    /// Headers at heights 1 to `count`, each linked to the one before, newest first.
+    fn chain(count: u64) -> Vec<Header> {
+        let mut block = TestgenLightBlock::new_default(1);
+        let mut headers = Vec::new();
+        for _ in 0..count {
+            headers.push(block.generate().unwrap().signed_header.header);
+            block = block.next();
+        }
+        headers.reverse();
+        headers
+    }
+
+    fn leaf(header: &Header) -> [u8; 32] {
+        PathLeaf {
+            hash: header_hash(header),
+            height: header.height.value(),
+            time_nanos: header.time.unix_timestamp_nanos(),
+        }
+        .digest()
+    }
+
+    /// The leaves of the path from the genesis at height 1 to the head, oldest first.
+    fn path(headers: &[Header]) -> Vec<[u8; 32]> {
+        headers.iter().rev().skip(1).map(leaf).collect()
+    }
+
+    fn public_values(headers: &[Header]) -> Vec<u8> {
+        bincode::serialize(&RecursionPublicValues {
+            checkpoints_hash: [0; 32],
+            genesis_hash: header_hash(headers.last().unwrap()).to_vec(),
+            h2_hash: header_hash(&headers[0]).to_vec(),
+            vk_digest: VK,
+            path_root: PathAccumulator::from_leaves(&path(headers)).root(),
+        })
+        .unwrap()
+    }
+
+    /// Accepts every proof, or none, and records what it was asked to verify.
+    #[derive(Default)]
+    struct MockVerifier {
+        reject: bool,
+        sp1: RefCell<Vec<([u32; 8], [u8; 32])>>,
+    }
+
+    impl ProofVerifier for MockVerifier {
+        fn verify_sp1_proof(
+            &self,
+            vk_digest: &[u32; 8],
+            pv_digest: &[u8; 32],
+        ) -> Result<(), String> {
+            self.sp1.borrow_mut().push((*vk_digest, *pv_digest));
+            if self.reject {
+                return Err("invalid proof".to_string());
+            }
+            Ok(())
+        }
+
+        fn verify_groth16_proof(
+            &self,
+            _proof: &[u8],
+            _public_values: &[u8],
+            _program_vk_hash: &[u32; 8],
+            _groth16_vk: &[u8],
+        ) -> Result<(), String> {
+            Err("no groth16 proofs here".to_string())
+        }
+    }
+
+    fn proven_head(headers: &[Header]) -> ProvenHead {
+        verify_recursion_head(
+            &VK,
+            &public_values(headers),
+            headers[0].clone(),
+            &MockVerifier::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn head_is_proven() {
+        let headers = chain(4);
+        let public_values = public_values(&headers);
+        let verifier = MockVerifier::default();
+
+        let head =
+            verify_recursion_head(&VK, &public_values, headers[0].clone(), &verifier).unwrap();
+        assert_eq!(head.hash, header_hash(&headers[0]));
+        assert_eq!(head.genesis_hash, header_hash(&headers[3]));
+        assert_eq!(head.recursion_vk_digest, VK);
+        let pv_digest: [u8; 32] = Sha256::digest(&public_values).into();
+        assert_eq!(verifier.sp1.into_inner(), vec![(VK, pv_digest)]);
+    }
+
+    #[test]
+    fn rejected_proof_is_rejected() {
+        let headers = chain(2);
+        let verifier = MockVerifier {
+            reject: true,
+            ..Default::default()
+        };
+        let e = verify_recursion_head(&VK, &public_values(&headers), headers[0].clone(), &verifier)
+            .unwrap_err();
+        assert_eq!(e, HeadError::Proof("invalid proof".to_string()));
+    }
+
+    #[test]
+    fn proof_of_another_vk_is_rejected() {
+        let headers = chain(2);
+        let verifier = MockVerifier::default();
+        let other = [9; 8];
+        let e = verify_recursion_head(
+            &other,
+            &public_values(&headers),
+            headers[0].clone(),
+            &verifier,
+        )
+        .unwrap_err();
+        assert_eq!(e, HeadError::Vk);
+    }
+
+    #[test]
+    fn malformed_public_values_are_rejected() {
+        let headers = chain(2);
+        let verifier = MockVerifier::default();
+        let e = verify_recursion_head(&VK, &[1, 2, 3], headers[0].clone(), &verifier).unwrap_err();
+        assert_eq!(e, HeadError::MalformedPublicValues);
+    }
+
+    #[test]
+    fn other_head_is_rejected() {
+        let headers = chain(3);
+        let verifier = MockVerifier::default();
+        let e = verify_recursion_head(&VK, &public_values(&headers), headers[1].clone(), &verifier)
+            .unwrap_err();
+        assert_eq!(e, HeadError::Head);
+    }
+
+    #[test]
+    fn head_links_to_itself() {
+        let headers = chain(3);
+        let head = proven_head(&headers);
+        let linked = linked_header(&head.header, &head.values.path_root, &HeaderLink::Head, &[]);
+        assert_eq!(linked.unwrap(), &headers[0]);
+
+        let e = linked_header(
+            &head.header,
+            &head.values.path_root,
+            &HeaderLink::Head,
+            &headers[1..2],
+        )
+        .unwrap_err();
+        assert_eq!(e, HeadError::LinkedHeaders);
+    }
+
+    #[test]
+    fn ancestors_link_to_the_head() {
+        let headers = chain(5);
+        let head = proven_head(&headers);
+        let root = head.values.path_root;
+
+        let linked = linked_header(
+            &head.header,
+            &root,
+            &HeaderLink::Ancestors(3),
+            &headers[1..4],
+        );
+        assert_eq!(linked.unwrap(), &headers[3]);
+
+        let e = linked_header(
+            &head.header,
+            &root,
+            &HeaderLink::Ancestors(4),
+            &headers[1..4],
+        )
+        .unwrap_err();
+        assert_eq!(e, HeadError::LinkedHeaders);
+
+        let skipped = [headers[1].clone(), headers[3].clone()];
+        let e =
+            linked_header(&head.header, &root, &HeaderLink::Ancestors(2), &skipped).unwrap_err();
+        assert_eq!(
+            e,
+            HeadError::Ancestor("header 2 is not the last block of header 4".to_string())
+        );
+    }
+
+    #[test]
+    fn path_header_links_to_the_head() {
+        let headers = chain(5);
+        let head = proven_head(&headers);
+        let root = head.values.path_root;
+        // Height 3 is the second leaf of the path from genesis.
+        let proof = PathProof::new(&path(&headers), 1).unwrap();
+        let link = HeaderLink::Path(proof);
+
+        let linked = linked_header(&head.header, &root, &link, &headers[2..3]);
+        assert_eq!(linked.unwrap(), &headers[2]);
+
+        let e = linked_header(&head.header, &root, &link, &headers[1..2]).unwrap_err();
+        assert_eq!(e, HeadError::PathProof);
+        let e = linked_header(&head.header, &root, &link, &headers[1..3]).unwrap_err();
+        assert_eq!(e, HeadError::LinkedHeaders);
+        // The genesis is not on the path: it was trusted, not verified.
+        let e = linked_header(&head.header, &root, &link, &headers[4..]).unwrap_err();
+        assert_eq!(e, HeadError::PathProof);
+    }
+
+    #[test]
+    fn ancestors_of_a_path_header_link_to_the_head() {
+        let headers = chain(6);
+        let head = proven_head(&headers);
+        let root = head.values.path_root;
+        // Height 4 is the third leaf of the path from genesis.
+        let proof = PathProof::new(&path(&headers), 2).unwrap();
+        let link = HeaderLink::PathAncestors { proof, count: 2 };
+
+        let linked = linked_header(&head.header, &root, &link, &headers[2..5]);
+        assert_eq!(linked.unwrap(), &headers[4]);
+
+        let e = linked_header(&head.header, &root, &link, &headers[2..4]).unwrap_err();
+        assert_eq!(e, HeadError::LinkedHeaders);
+        // The walk must start from the proven header.
+        let e = linked_header(&head.header, &root, &link, &headers[1..4]).unwrap_err();
+        assert_eq!(e, HeadError::PathProof);
+        let skipped = [headers[2].clone(), headers[3].clone(), headers[5].clone()];
+        let e = linked_header(&head.header, &root, &link, &skipped).unwrap_err();
+        assert_eq!(
+            e,
+            HeadError::Ancestor("header 1 is not the last block of header 3".to_string())
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! Verifies one hop of the recursion: an untrusted light block against a trusted one. Shared by
+//! the recursion program and by native tests that compare it with the light client's
+//! `ProdVerifier`.
+
+pub mod encoding;
+pub mod head;
+pub mod recursion;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "zkvm")]
+pub mod zkvm;
+
+use std::collections::BTreeSet;
+use tendermint::{
+    block::CommitSig,
+    crypto::default::signature::Verifier as SignatureVerifier,
+    trust_threshold::TrustThreshold as _,
+    validator,
+    vote::{SignedVote, ValidatorIndex, Vote},
+};
+use tendermint_light_client_verifier::{
+    errors::VerificationError,
+    operations::VotingPowerTally,
+    options::Options,
+    types::{Commit, Time, TrustThreshold, TrustedBlockState, UntrustedBlockState, ValidatorSet},
+    ProdVerifier, Verdict, Verifier,
+};
+
+/// `ProdVerifier` with both voting power checks done in one pass over the commit.
+///
+/// `ProdVerifier` tallies the commit twice on a skipping hop: once against the trusted next
+/// validators for the trust threshold, then against the untrusted validators for 2/3. Each pass
+/// verifies the signatures of its set until its threshold is met, so a validator in both sets has
+/// its signature verified twice. This verifier goes through the commit once, verifying each
+/// signature at most once, and stops as soon as both thresholds are met. Commit signatures follow
+/// the validator set order, highest power first, so that is usually early, and even more so for a
+/// commit pruned by the host.
+///
+/// It returns the same verdict as `ProdVerifier` for every input, errors included.
+#[derive(Default)]
+pub struct HopVerifier {
+    inner: ProdVerifier,
+}
+
+impl Verifier for HopVerifier {
+    fn verify_update_header(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> Verdict {
+        for verdict in [
+            self.inner.verify_validator_sets(&untrusted),
+            self.inner
+                .validate_against_trusted(&untrusted, &trusted, options, now),
+            self.inner
+                .check_header_is_from_past(&untrusted, options, now),
+        ] {
+            if verdict != Verdict::Success {
+                return verdict;
+            }
+        }
+
+        let trusted_validators = if untrusted.height() == trusted.height.increment() {
+            None
+        } else {
+            Some(trusted.next_validators)
+        };
+        tally_commit(&untrusted, trusted_validators, options.trust_threshold).into()
+    }
+
+    fn verify_misbehaviour_header(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> Verdict {
+        self.inner
+            .verify_misbehaviour_header(untrusted, trusted, options, now)
+    }
+}
+
+/// One of `ProdVerifier`'s passes over the commit, for a validator set and threshold.
+struct Pass<'a> {
+    validators: &'a ValidatorSet,
+    threshold: TrustThreshold,
+    total: u64,
+    tallied: u64,
+    running: bool,
+    /// An error this pass stopped on, reported once the passes before it have succeeded.
+    error: Option<VerificationError>,
+}
+
+impl<'a> Pass<'a> {
+    fn new(validators: &'a ValidatorSet, threshold: TrustThreshold) -> Self {
+        Pass {
+            validators,
+            threshold,
+            total: validators
+                .validators()
+                .iter()
+                .fold(0u64, |total, info| total + info.power.value()),
+            tallied: 0,
+            running: true,
+            error: None,
+        }
+    }
+
+    fn validator(&self, vote: &Vote) -> Option<validator::Info> {
+        if self.running {
+            self.validators.validator(vote.validator_address)
+        } else {
+            None
+        }
+    }
+
+    fn stop(&mut self, error: VerificationError) {
+        self.running = false;
+        self.error = Some(error);
+    }
+
+    fn tally(&self) -> VotingPowerTally {
+        VotingPowerTally {
+            total: self.total,
+            tallied: self.tallied,
+            trust_threshold: self.threshold,
+        }
+    }
+
+    fn is_enough(&self) -> bool {
+        self.threshold.is_enough_power(self.tallied, self.total)
+    }
+}
+
+/// Checks the trust threshold of `trusted_validators`, if given, and then 2/3 of the untrusted
+/// validators, in a single pass. Errors are those the first failing `ProdVerifier` pass returns.
+#[allow(clippy::result_large_err)]
+fn tally_commit(
+    untrusted: &UntrustedBlockState<'_>,
+    trusted_validators: Option<&ValidatorSet>,
+    trust_threshold: TrustThreshold,
+) -> Result<(), VerificationError> {
+    let signed_header = untrusted.signed_header;
+    let commit = &signed_header.commit;
+    let mut trusted = trusted_validators.map(|validators| Pass::new(validators, trust_threshold));
+    let mut signers = Pass::new(untrusted.validators, TrustThreshold::TWO_THIRDS);
+    let mut seen = BTreeSet::new();
+
+    for (index, signature) in commit.signatures.iter().enumerate() {
+        let trusted_running = trusted.as_ref().is_some_and(|pass| pass.running);
+        if !trusted_running && !signers.running {
+            break;
+        }
+        let Some(vote) = non_absent_vote(signature, index, commit) else {
+            continue;
+        };
+
+        if !seen.insert(vote.validator_address) {
+            let error = VerificationError::duplicate_validator(vote.validator_address);
+            if trusted_running {
+                return Err(error);
+            }
+            signers.stop(error);
+            break;
+        }
+
+        let trusted_validator = trusted.as_ref().and_then(|pass| pass.validator(&vote));
+        let signer = signers.validator(&vote);
+        if trusted_validator.is_none() && signer.is_none() {
+            continue;
+        }
+
+        let Some(signed_vote) = SignedVote::from_vote(vote, signed_header.header.chain_id.clone())
+        else {
+            if trusted_validator.is_some() {
+                return Err(VerificationError::missing_signature());
+            }
+            signers.stop(VerificationError::missing_signature());
+            continue;
+        };
+        let sign_bytes = signed_vote.sign_bytes();
+        let verify = |validator: &validator::Info| {
+            validator
+                .verify_signature::<SignatureVerifier>(&sign_bytes, signed_vote.signature())
+                .map_err(|_| {
+                    VerificationError::invalid_signature(
+                        signed_vote.signature().as_bytes().to_vec(),
+                        Box::new(validator.clone()),
+                        sign_bytes.clone(),
+                    )
+                })
+        };
+
+        if let Some(validator) = &trusted_validator {
+            verify(validator)?;
+        }
+        let signer = signer.filter(|validator| {
+            // Addresses are derived from keys, so a validator in both sets is verified once.
+            let verified = trusted_validator
+                .as_ref()
+                .is_some_and(|trusted| trusted.pub_key == validator.pub_key);
+            match if verified { Ok(()) } else { verify(validator) } {
+                Ok(()) => true,
+                Err(error) => {
+                    signers.stop(error);
+                    false
+                }
+            }
+        });
+
+        if signature.is_commit() {
+            if let (Some(pass), Some(validator)) = (trusted.as_mut(), &trusted_validator) {
+                pass.tallied += validator.power();
+                pass.running = !pass.is_enough();
+            }
+            if let Some(validator) = &signer {
+                signers.tallied += validator.power();
+                signers.running = !signers.is_enough();
+            }
+        }
+    }
+
+    if let Some(pass) = &trusted {
+        if !pass.is_enough() {
+            return Err(VerificationError::not_enough_trust(pass.tally()));
+        }
+    }
+    if let Some(error) = signers.error {
+        return Err(error);
+    }
+    if !signers.is_enough() {
+        return Err(VerificationError::insufficient_signers_overlap(
+            signers.tally(),
+        ));
+    }
+    Ok(())
+}
+
+/// The vote a commit signature is for, unless it is absent. As the light client builds it.
+fn non_absent_vote(signature: &CommitSig, index: usize, commit: &Commit) -> Option<Vote> {
+    let (validator_address, timestamp, signature, block_id) = match signature {
+        CommitSig::BlockIdFlagAbsent => return None,
+        CommitSig::BlockIdFlagCommit {
+            validator_address,
+            timestamp,
+            signature,
+        } => (
+            *validator_address,
+            *timestamp,
+            signature,
+            Some(commit.block_id),
+        ),
+        CommitSig::BlockIdFlagNil {
+            validator_address,
+            timestamp,
+            signature,
+        } => (*validator_address, *timestamp, signature, None),
+    };
+
+    Some(Vote {
+        vote_type: tendermint::vote::Type::Precommit,
+        height: commit.height,
+        round: commit.round,
+        block_id,
+        timestamp: Some(timestamp),
+        validator_address,
+        validator_index: ValidatorIndex::try_from(index).unwrap(),
+        signature: signature.clone(),
+        extension: Default::default(),
+        extension_signature: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tendermint::{block::Height, Signature};
+    use tendermint_light_client_verifier::types::LightBlock;
+    use tendermint_testgen::{
+        Commit as TestgenCommit, Generator, Header, LightBlock as TestgenLightBlock, Validator,
+        Vote as TestgenVote,
+    };
+
+    fn validators(powers: &[u64]) -> Vec<Validator> {
+        powers
+            .iter()
+            .enumerate()
+            .map(|(i, power)| Validator::new(&format!("v{}", i)).voting_power(*power))
+            .collect()
+    }
+
+    fn header(height: u64, vals: &[Validator], next_vals: &[Validator]) -> Header {
+        Header::new(vals)
+            .height(height)
+            .chain_id("test-chain")
+            .next_validators(next_vals)
+            .time(tendermint::Time::from_unix_timestamp(height as i64 * 10, 0).unwrap())
+    }
+
+    fn light_block(block: TestgenLightBlock) -> LightBlock {
+        let block = block.generate().unwrap();
+        LightBlock::new(
+            block.signed_header,
+            block.validators,
+            block.next_validators,
+            block.provider,
+        )
+    }
+
+    fn block(height: u64, vals: &[Validator], next_vals: &[Validator]) -> LightBlock {
+        light_block(
+            TestgenLightBlock::new_default_with_header(header(height, vals, next_vals))
+                .next_validators(next_vals),
+        )
+    }
+
+    /// Checks that both verifiers agree on the hop, and returns the verdict.
+    fn verify(untrusted: &LightBlock, trusted: &LightBlock) -> Verdict {
+        let options = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(1000),
+            clock_drift: Default::default(),
+        };
+        let now = (untrusted.time() + Duration::from_secs(20)).unwrap();
+        let expected = ProdVerifier::default().verify_update_header(
+            untrusted.as_untrusted_state(),
+            trusted.as_trusted_state(),
+            &options,
+            now,
+        );
+        let verdict = HopVerifier::default().verify_update_header(
+            untrusted.as_untrusted_state(),
+            trusted.as_trusted_state(),
+            &options,
+            now,
+        );
+        assert_eq!(verdict, expected);
+        verdict
+    }
+
+    fn tamper(signature: &mut CommitSig) {
+        if let CommitSig::BlockIdFlagCommit {
+            signature: Some(signature),
+            ..
+        } = signature
+        {
+            let mut bytes = signature.as_bytes().to_vec();
+            bytes[0] ^= 1;
+            *signature = Signature::try_from(bytes.as_slice()).unwrap();
+        }
+    }
+
+    const POWERS: [&[u64]; 4] = [
+        &[50, 30, 15, 5],
+        &[1, 1, 1, 1, 1, 1],
+        &[40, 20, 20, 10, 10],
+        &[100],
+    ];
+
+    #[test]
+    fn agrees_on_every_absent_subset() {
+        for powers in POWERS {
+            let vals = validators(powers);
+            let trusted = block(1, &vals, &vals);
+            for height in [2, 5] {
+                let full = block(height, &vals, &vals);
+                for mask in 0..1u32 << vals.len() {
+                    let mut untrusted = full.clone();
+                    for (i, signature) in untrusted
+                        .signed_header
+                        .commit
+                        .signatures
+                        .iter_mut()
+                        .enumerate()
+                    {
+                        if mask & (1 << i) != 0 {
+                            *signature = CommitSig::BlockIdFlagAbsent;
+                        }
+                    }
+                    verify(&untrusted, &trusted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_on_every_tampered_subset() {
+        for powers in POWERS {
+            let vals = validators(powers);
+            let trusted = block(1, &vals, &vals);
+            for height in [2, 5] {
+                let full = block(height, &vals, &vals);
+                for mask in 1..1u32 << vals.len() {
+                    let mut untrusted = full.clone();
+                    for (i, signature) in untrusted
+                        .signed_header
+                        .commit
+                        .signatures
+                        .iter_mut()
+                        .enumerate()
+                    {
+                        if mask & (1 << i) != 0 {
+                            tamper(signature);
+                        }
+                    }
+                    verify(&untrusted, &trusted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_across_validator_set_changes() {
+        let vals = validators(&[40, 20, 20, 10, 10]);
+        let trusted_sets = [
+            validators(&[40, 20, 20, 10, 10]),
+            validators(&[10, 10, 10, 10, 10, 10, 10, 10]),
+            vec![vals[3].clone(), vals[4].clone()],
+            vec![vals[0].clone(), Validator::new("other").voting_power(100)],
+            vec![Validator::new("other").voting_power(100)],
+        ];
+        for trusted_vals in &trusted_sets {
+            let trusted = block(1, trusted_vals, trusted_vals);
+            let full = block(5, &vals, &vals);
+            verify(&full, &trusted);
+            for i in 0..vals.len() {
+                let mut untrusted = full.clone();
+                tamper(&mut untrusted.signed_header.commit.signatures[i]);
+                verify(&untrusted, &trusted);
+
+                let mut untrusted = full.clone();
+                untrusted.signed_header.commit.signatures[i] = CommitSig::BlockIdFlagAbsent;
+                verify(&untrusted, &trusted);
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_on_malformed_commits() {
+        let vals = validators(&[40, 20, 20, 10, 10]);
+        let trusted = block(1, &vals, &vals);
+        for height in [2, 5] {
+            let full = block(height, &vals, &vals);
+
+            let mut duplicate = full.clone();
+            let signatures = &mut duplicate.signed_header.commit.signatures;
+            signatures[3] = signatures[0].clone();
+            verify(&duplicate, &trusted);
+
+            let mut unsigned = full.clone();
+            if let CommitSig::BlockIdFlagCommit { signature, .. } =
+                &mut unsigned.signed_header.commit.signatures[1]
+            {
+                *signature = None;
+            }
+            verify(&unsigned, &trusted);
+
+            let mut wrong_height = full.clone();
+            wrong_height.signed_header.commit.height = Height::try_from(height + 1).unwrap();
+            verify(&wrong_height, &trusted);
+        }
+    }
+
+    #[test]
+    fn agrees_with_nil_votes() {
+        let vals = validators(&[40, 20, 20, 10, 10]);
+        let trusted = block(1, &vals, &vals);
+        for height in [2, 5] {
+            let header = header(height, &vals, &vals);
+            for nil in 0..vals.len() {
+                let votes = vals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, val)| TestgenVote::new(val.clone(), header.clone()).nil(i <= nil))
+                    .collect();
+                let commit = TestgenCommit::new_with_votes(header.clone(), 1, votes);
+                let untrusted = light_block(
+                    TestgenLightBlock::new(header.clone(), commit)
+                        .validators(&vals)
+                        .next_validators(&vals),
+                );
+                verify(&untrusted, &trusted);
+            }
+        }
+    }
+
+    #[test]
+    fn returns_each_kind_of_verdict() {
+        let vals = validators(&[40, 20, 20, 10, 10]);
+        let trusted = block(1, &vals, &vals);
+        assert_eq!(verify(&block(5, &vals, &vals), &trusted), Verdict::Success);
+
+        let others = [Validator::new("x"), Validator::new("y")];
+        let stranger = block(1, &others, &others);
+        assert!(matches!(
+            verify(&block(5, &vals, &vals), &stranger),
+            Verdict::NotEnoughTrust(_)
+        ));
+
+        let mut untrusted = block(2, &vals, &vals);
+        tamper(&mut untrusted.signed_header.commit.signatures[0]);
+        assert!(matches!(verify(&untrusted, &trusted), Verdict::Invalid(_)));
+
+        let expired = block(1000, &vals, &vals);
+        assert!(matches!(verify(&expired, &trusted), Verdict::Invalid(_)));
+    }
+}
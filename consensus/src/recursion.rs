@@ -0,0 +1,771 @@
+//! The rules of one hop of the recursion program, as pure functions of its inputs. The program
+//! only reads the inputs, runs [`verify_consensus`] and [`verify_recursion`] with proof
+//! verification backed by the zkVM, and commits the result, so everything else runs and is tested
+//! natively.
+
+use crate::HopVerifier;
+use bincode::Options as _;
+use common::mmr::{PathAccumulator, PathLeaf};
+use common::{
+    checkpoint_history_hash, Groth16VkeyCheckpoint, LegacyRecursionPublicValues,
+    RecursionPublicValues,
+};
+use sha2::{Digest, Sha256};
+use sp1_verifier::Groth16Verifier;
+use std::fmt;
+use std::time::Duration;
+use tendermint_light_client_verifier::{options::Options, types::LightBlock, Verdict, Verifier};
+use verifier::program_vk_hash_hex;
+
+/// Verifies the proofs a hop recurses on.
+pub trait ProofVerifier {
+    /// Verifies a compressed proof of the program with `vk_digest`, given the digest of its
+    /// public values.
+    fn verify_sp1_proof(&self, vk_digest: &[u32; 8], pv_digest: &[u8; 32]) -> Result<(), String>;
+
+    /// Verifies a Groth16 proof of the program with `program_vk_hash` against `groth16_vk`, given
+    /// its public values themselves: a Groth16 proof commits to their hash, not their digest.
+    fn verify_groth16_proof(
+        &self,
+        proof: &[u8],
+        public_values: &[u8],
+        program_vk_hash: &[u32; 8],
+        groth16_vk: &[u8],
+    ) -> Result<(), String>;
+}
+
+/// Verifies a Groth16 proof of the program with `program_vk_hash`, given its public values, the
+/// way the program does.
+pub fn verify_groth16(
+    proof: &[u8],
+    public_values: &[u8],
+    program_vk_hash: &[u32; 8],
+    groth16_vk: &[u8],
+) -> Result<(), String> {
+    Groth16Verifier::verify(
+        proof,
+        public_values,
+        &program_vk_hash_hex(program_vk_hash),
+        groth16_vk,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// The proof of the hops up to h1.
+#[derive(Clone, Debug)]
+pub struct PreviousProof {
+    /// The Groth16 proof on upgrades from a Groth16 checkpoint, and empty otherwise.
+    pub groth16_proof: Vec<u8>,
+    pub public_values: Vec<u8>,
+}
+
+/// Everything the program reads for a hop.
+#[derive(Clone, Debug)]
+pub struct HopInputs {
+    pub is_upgrade: bool,
+    /// The whole upgrade history, including the incoming checkpoint on upgrades.
+    pub checkpoints: Vec<Groth16VkeyCheckpoint>,
+    pub genesis_hash: Vec<u8>,
+    pub h1: LightBlock,
+    pub h2: LightBlock,
+    /// Digest of the program's own vk.
+    pub vk_digest: [u32; 8],
+    /// The path accumulator before this hop.
+    pub previous_path: PathAccumulator,
+    /// The previous proof, unless h1 is genesis.
+    pub previous: Option<PreviousProof>,
+}
+
+/// Why a hop does not verify. The messages are the program's panic messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HopError {
+    /// h2 does not verify from h1.
+    Consensus(String),
+    /// h1 is genesis, but the path accumulator is not empty.
+    GenesisPath,
+    /// h1 is genesis, but there is a previous proof.
+    UnexpectedPreviousProof,
+    /// h1 is not genesis, and there is no previous proof.
+    MissingPreviousProof,
+    MalformedPublicValues,
+    PathRoot,
+    Genesis,
+    Vk,
+    Checkpoints,
+    UpgradeCheckpoints,
+    UpgradeVk,
+    UpgradeBlock,
+    Proof(String),
+}
+
+impl fmt::Display for HopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HopError::Consensus(verdict) => write!(f, "Verification failed: {}", verdict),
+            HopError::GenesisPath => write!(f, "Path accumulator must start empty at genesis"),
+            HopError::UnexpectedPreviousProof => {
+                write!(f, "There is no previous proof when h1 is genesis")
+            }
+            HopError::MissingPreviousProof => {
+                write!(f, "Previous proof is required when h1 is not genesis")
+            }
+            HopError::MalformedPublicValues => {
+                write!(f, "Previous proof's public values are malformed")
+            }
+            HopError::PathRoot => {
+                write!(f, "Path accumulator must match previous proof's path root")
+            }
+            HopError::Genesis => write!(f, "Genesis hash must match previous proof's genesis hash"),
+            HopError::Vk => write!(
+                f,
+                "Vkey must match previous proof's vkey, except for upgrades"
+            ),
+            HopError::Checkpoints => write!(
+                f,
+                "Checkpoints must match previous proof's checkpoints, except for upgrades"
+            ),
+            HopError::UpgradeCheckpoints => write!(
+                f,
+                "During upgrade, the checkpoints must be the previous proof's plus one"
+            ),
+            HopError::UpgradeVk => write!(
+                f,
+                "Program vkey hash must match previous proof's program vkey hash"
+            ),
+            HopError::UpgradeBlock => {
+                write!(f, "Incoming checkpoint block hash must match h1 block hash")
+            }
+            HopError::Proof(e) => write!(f, "Failed to verify previous proof: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HopError {}
+
+/// Decodes a recursion proof's public values, which must be exactly one layout. With `legacy`,
+/// proofs from before the checkpoints hash and the path root are read too, as
+/// [`LegacyRecursionPublicValues::upgrade`] converts them.
+pub fn decode_public_values(
+    public_values: &[u8],
+    legacy: bool,
+) -> bincode::Result<RecursionPublicValues> {
+    // The current layout wins, and reading every byte keeps one layout from passing for the other
+    // by accident.
+    let options = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes();
+    options.deserialize(public_values).or_else(|e| {
+        if !legacy {
+            return Err(e);
+        }
+        options
+            .deserialize::<LegacyRecursionPublicValues>(public_values)
+            .map(LegacyRecursionPublicValues::upgrade)
+            .map_err(|_| e)
+    })
+}
+
+/// Whether a hop starts at genesis, and so has no previous proof.
+pub fn is_genesis(h1: &LightBlock, genesis_hash: &[u8]) -> bool {
+    h1.signed_header.header().hash().as_bytes() == genesis_hash
+}
+
+/// Verifies h2 from h1 as the program does: with [`HopVerifier`], the default trust threshold and
+/// the two week trusting period, at 20 seconds after h2.
+pub fn verify_consensus(h1: &LightBlock, h2: &LightBlock) -> Result<(), HopError> {
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(common::TRUSTING_PERIOD_SECS),
+        clock_drift: Default::default(),
+    };
+    let verify_time =
+        (h2.time() + Duration::from_secs(20)).map_err(|e| HopError::Consensus(e.to_string()))?;
+    match HopVerifier::default().verify_update_header(
+        h2.as_untrusted_state(),
+        h1.as_trusted_state(),
+        &options,
+        verify_time,
+    ) {
+        Verdict::Success => Ok(()),
+        Verdict::NotEnoughTrust(tally) => {
+            Err(HopError::Consensus(format!("not enough trust: {}", tally)))
+        }
+        Verdict::Invalid(e) => Err(HopError::Consensus(e.to_string())),
+    }
+}
+
+/// Verifies a hop and returns the public values the program commits for it.
+pub fn verify_hop(
+    inputs: &HopInputs,
+    verifier: &impl ProofVerifier,
+) -> Result<RecursionPublicValues, HopError> {
+    verify_consensus(&inputs.h1, &inputs.h2)?;
+    verify_recursion(inputs, verifier)
+}
+
+/// Everything [`verify_hop`] checks but consensus: that the hop continues the previous proof, or
+/// starts at genesis. The program verifies consensus first, and then this.
+pub fn verify_recursion(
+    inputs: &HopInputs,
+    verifier: &impl ProofVerifier,
+) -> Result<RecursionPublicValues, HopError> {
+    let checkpoints_hash = checkpoint_history_hash(&inputs.checkpoints);
+    let h2_hash = inputs.h2.signed_header.header().hash();
+
+    let mut path = inputs.previous_path.clone();
+    path.push(
+        PathLeaf {
+            hash: h2_hash
+                .as_bytes()
+                .try_into()
+                .expect("header hash is 32 bytes"),
+            height: inputs.h2.height().value(),
+            time_nanos: inputs.h2.time().unix_timestamp_nanos(),
+        }
+        .digest(),
+    );
+    let values = RecursionPublicValues {
+        checkpoints_hash,
+        genesis_hash: inputs.genesis_hash.clone(),
+        h2_hash: h2_hash.as_bytes().to_vec(),
+        vk_digest: inputs.vk_digest,
+        path_root: path.root(),
+    };
+
+    // At genesis there is no previous proof to check.
+    if is_genesis(&inputs.h1, &inputs.genesis_hash) {
+        if inputs.previous_path != PathAccumulator::default() {
+            return Err(HopError::GenesisPath);
+        }
+        if inputs.previous.is_some() {
+            return Err(HopError::UnexpectedPreviousProof);
+        }
+        return Ok(values);
+    }
+    let previous = inputs
+        .previous
+        .as_ref()
+        .ok_or(HopError::MissingPreviousProof)?;
+
+    // Only upgrades build on proofs of the legacy layout, which commit no path root: the path
+    // accumulator starts over with them.
+    let previous_values = decode_public_values(&previous.public_values, inputs.is_upgrade)
+        .map_err(|_| HopError::MalformedPublicValues)?;
+    let pv_digest: [u8; 32] = Sha256::digest(&previous.public_values).into();
+
+    if inputs.previous_path.root() != previous_values.path_root {
+        return Err(HopError::PathRoot);
+    }
+    if previous_values.genesis_hash != inputs.genesis_hash {
+        return Err(HopError::Genesis);
+    }
+
+    if !inputs.is_upgrade {
+        if previous_values.vk_digest != inputs.vk_digest {
+            return Err(HopError::Vk);
+        }
+        if previous_values.checkpoints_hash != checkpoints_hash {
+            return Err(HopError::Checkpoints);
+        }
+        verifier
+            .verify_sp1_proof(&inputs.vk_digest, &pv_digest)
+            .map_err(HopError::Proof)?;
+    } else {
+        let (incoming, previous_checkpoints) = inputs
+            .checkpoints
+            .split_last()
+            .ok_or(HopError::UpgradeCheckpoints)?;
+        if checkpoint_history_hash(previous_checkpoints) != previous_values.checkpoints_hash {
+            return Err(HopError::UpgradeCheckpoints);
+        }
+        if incoming.program_vk_hash != previous_values.vk_digest {
+            return Err(HopError::UpgradeVk);
+        }
+        if incoming.block_hash.as_slice() != inputs.h1.signed_header.header().hash().as_bytes() {
+            return Err(HopError::UpgradeBlock);
+        }
+        match &incoming.groth16_vk {
+            Some(groth16_vk) => verifier.verify_groth16_proof(
+                &previous.groth16_proof,
+                &previous.public_values,
+                &incoming.program_vk_hash,
+                groth16_vk,
+            ),
+            // The previous proof is one of the old program, not this one.
+            None => verifier.verify_sp1_proof(&incoming.program_vk_hash, &pv_digest),
+        }
+        .map_err(HopError::Proof)?;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::block;
+    use proptest::prelude::*;
+    use std::cell::RefCell;
+
+    const VK: [u32; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    /// Accepts every proof, or none, and records what it was asked to verify.
+    #[derive(Default)]
+    struct MockVerifier {
+        reject: bool,
+        sp1: RefCell<Vec<([u32; 8], [u8; 32])>>,
+        groth16: RefCell<Vec<([u32; 8], Vec<u8>)>>,
+    }
+
+    impl ProofVerifier for MockVerifier {
+        fn verify_sp1_proof(
+            &self,
+            vk_digest: &[u32; 8],
+            pv_digest: &[u8; 32],
+        ) -> Result<(), String> {
+            self.sp1.borrow_mut().push((*vk_digest, *pv_digest));
+            if self.reject {
+                return Err("invalid proof".to_string());
+            }
+            Ok(())
+        }
+
+        fn verify_groth16_proof(
+            &self,
+            _proof: &[u8],
+            public_values: &[u8],
+            program_vk_hash: &[u32; 8],
+            _groth16_vk: &[u8],
+        ) -> Result<(), String> {
+            self.groth16
+                .borrow_mut()
+                .push((*program_vk_hash, public_values.to_vec()));
+            if self.reject {
+                return Err("invalid proof".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    fn path_digest(block: &LightBlock) -> [u8; 32] {
+        PathLeaf {
+            hash: block
+                .signed_header
+                .header()
+                .hash()
+                .as_bytes()
+                .try_into()
+                .unwrap(),
+            height: block.height().value(),
+            time_nanos: block.time().unix_timestamp_nanos(),
+        }
+        .digest()
+    }
+
+    fn genesis_hop(genesis: &LightBlock, h2: &LightBlock) -> HopInputs {
+        HopInputs {
+            is_upgrade: false,
+            checkpoints: Vec::new(),
+            genesis_hash: genesis.signed_header.header().hash().as_bytes().to_vec(),
+            h1: genesis.clone(),
+            h2: h2.clone(),
+            vk_digest: VK,
+            previous_path: PathAccumulator::default(),
+            previous: None,
+        }
+    }
+
+    /// The hop from `previous`'s h2 to `h2`, recursing on a proof of `previous`.
+    fn next_hop(previous: &HopInputs, h2: &LightBlock) -> HopInputs {
+        let values = verify_hop(previous, &MockVerifier::default()).unwrap();
+        let mut previous_path = previous.previous_path.clone();
+        previous_path.push(path_digest(&previous.h2));
+        HopInputs {
+            h1: previous.h2.clone(),
+            h2: h2.clone(),
+            previous_path,
+            previous: Some(PreviousProof {
+                groth16_proof: Vec::new(),
+                public_values: bincode::serialize(&values).unwrap(),
+            }),
+            ..previous.clone()
+        }
+    }
+
+    /// Genesis at height 1, then a hop to 3 and one to 6.
+    fn chain() -> (HopInputs, HopInputs) {
+        let genesis_hop = genesis_hop(&block(1, 10), &block(3, 30));
+        let second = next_hop(&genesis_hop, &block(6, 60));
+        (genesis_hop, second)
+    }
+
+    fn checkpoint(block: &LightBlock, groth16_vk: Option<Vec<u8>>) -> Groth16VkeyCheckpoint {
+        Groth16VkeyCheckpoint {
+            block_hash: block
+                .signed_header
+                .header()
+                .hash()
+                .as_bytes()
+                .try_into()
+                .unwrap(),
+            groth16_vk,
+            program_vk_hash: VK,
+        }
+    }
+
+    /// The second hop of [`chain`] as an upgrade to a new program vk.
+    fn upgrade(groth16_vk: Option<Vec<u8>>) -> HopInputs {
+        let (_, second) = chain();
+        HopInputs {
+            is_upgrade: true,
+            checkpoints: vec![checkpoint(&second.h1, groth16_vk)],
+            vk_digest: [9; 8],
+            ..second
+        }
+    }
+
+    fn pv_digest(inputs: &HopInputs) -> [u8; 32] {
+        Sha256::digest(&inputs.previous.as_ref().unwrap().public_values).into()
+    }
+
+    #[test]
+    fn genesis_hop_commits_its_head() {
+        let (genesis_hop, _) = chain();
+        let verifier = MockVerifier::default();
+        let values = verify_hop(&genesis_hop, &verifier).unwrap();
+
+        assert_eq!(values.checkpoints_hash, [0; 32]);
+        assert_eq!(values.genesis_hash, genesis_hop.genesis_hash);
+        assert_eq!(
+            values.h2_hash,
+            genesis_hop.h2.signed_header.header().hash().as_bytes()
+        );
+        assert_eq!(values.vk_digest, VK);
+        assert_eq!(
+            values.path_root,
+            PathAccumulator::from_leaves(&[path_digest(&genesis_hop.h2)]).root()
+        );
+        assert!(verifier.sp1.borrow().is_empty());
+    }
+
+    #[test]
+    fn next_hop_verifies_the_previous_proof() {
+        let (genesis_hop, second) = chain();
+        let verifier = MockVerifier::default();
+        let values = verify_hop(&second, &verifier).unwrap();
+
+        assert_eq!(*verifier.sp1.borrow(), vec![(VK, pv_digest(&second))]);
+        assert_eq!(
+            values.path_root,
+            PathAccumulator::from_leaves(&[path_digest(&genesis_hop.h2), path_digest(&second.h2)])
+                .root()
+        );
+    }
+
+    #[test]
+    fn genesis_hop_takes_no_previous_state() {
+        let (genesis_hop, second) = chain();
+
+        let with_path = HopInputs {
+            previous_path: second.previous_path.clone(),
+            ..genesis_hop.clone()
+        };
+        assert_eq!(
+            verify_hop(&with_path, &MockVerifier::default()),
+            Err(HopError::GenesisPath)
+        );
+
+        let with_proof = HopInputs {
+            previous: second.previous.clone(),
+            ..genesis_hop
+        };
+        assert_eq!(
+            verify_hop(&with_proof, &MockVerifier::default()),
+            Err(HopError::UnexpectedPreviousProof)
+        );
+    }
+
+    #[test]
+    fn later_hops_need_a_matching_previous_proof() {
+        let (_, second) = chain();
+        let check = |inputs: HopInputs| verify_hop(&inputs, &MockVerifier::default());
+
+        assert_eq!(
+            check(HopInputs {
+                previous: None,
+                ..second.clone()
+            }),
+            Err(HopError::MissingPreviousProof)
+        );
+        assert_eq!(
+            check(HopInputs {
+                previous: Some(PreviousProof {
+                    groth16_proof: Vec::new(),
+                    public_values: vec![1, 2, 3],
+                }),
+                ..second.clone()
+            }),
+            Err(HopError::MalformedPublicValues)
+        );
+        assert_eq!(
+            check(HopInputs {
+                previous_path: PathAccumulator::default(),
+                ..second.clone()
+            }),
+            Err(HopError::PathRoot)
+        );
+        assert_eq!(
+            check(HopInputs {
+                genesis_hash: vec![0; 32],
+                ..second.clone()
+            }),
+            Err(HopError::Genesis)
+        );
+        assert_eq!(
+            check(HopInputs {
+                vk_digest: [9; 8],
+                ..second.clone()
+            }),
+            Err(HopError::Vk)
+        );
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: vec![checkpoint(&second.h1, None)],
+                ..second
+            }),
+            Err(HopError::Checkpoints)
+        );
+    }
+
+    #[test]
+    fn invalid_previous_proof_is_rejected() {
+        let (_, second) = chain();
+        let verifier = MockVerifier {
+            reject: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            verify_hop(&second, &verifier),
+            Err(HopError::Proof("invalid proof".to_string()))
+        );
+    }
+
+    #[test]
+    fn hop_past_the_trusting_period_is_rejected() {
+        let (_, second) = chain();
+        let late = block(6, 60 + common::TRUSTING_PERIOD_SECS as i64);
+        let inputs = HopInputs { h2: late, ..second };
+        assert!(matches!(
+            verify_hop(&inputs, &MockVerifier::default()),
+            Err(HopError::Consensus(_))
+        ));
+    }
+
+    #[test]
+    fn upgrade_verifies_the_groth16_proof_of_the_old_program() {
+        let inputs = upgrade(Some(vec![7; 4]));
+        let verifier = MockVerifier::default();
+        let values = verify_hop(&inputs, &verifier).unwrap();
+
+        let public_values = inputs.previous.as_ref().unwrap().public_values.clone();
+        assert_eq!(*verifier.groth16.borrow(), vec![(VK, public_values)]);
+        assert!(verifier.sp1.borrow().is_empty());
+        assert_eq!(values.vk_digest, [9; 8]);
+        assert_eq!(
+            values.checkpoints_hash,
+            checkpoint_history_hash(&inputs.checkpoints)
+        );
+    }
+
+    #[test]
+    fn upgrade_without_groth16_vk_verifies_a_compressed_proof() {
+        let inputs = upgrade(None);
+        let verifier = MockVerifier::default();
+        verify_hop(&inputs, &verifier).unwrap();
+        // Against the old program's vk, which the checkpoint names, not the new one.
+        assert_eq!(*verifier.sp1.borrow(), vec![(VK, pv_digest(&inputs))]);
+    }
+
+    /// [`upgrade`] from a proof of the baseline program, which committed the checkpoint list before
+    /// the upgrade instead of its hash, and no path root.
+    fn upgrade_from_baseline(old_checkpoints: Vec<Groth16VkeyCheckpoint>) -> HopInputs {
+        let inputs = upgrade(None);
+        let h1_hash = inputs.h1.signed_header.header().hash().as_bytes().to_vec();
+        // The baseline program committed each value in turn.
+        let mut public_values = bincode::serialize(&old_checkpoints).unwrap();
+        public_values.extend(bincode::serialize(&inputs.genesis_hash).unwrap());
+        public_values.extend(bincode::serialize(&h1_hash).unwrap());
+        public_values.extend(bincode::serialize(&VK).unwrap());
+
+        let mut checkpoints = old_checkpoints;
+        checkpoints.extend(inputs.checkpoints.clone());
+        HopInputs {
+            checkpoints,
+            previous_path: PathAccumulator::default(),
+            previous: Some(PreviousProof {
+                groth16_proof: Vec::new(),
+                public_values,
+            }),
+            ..inputs
+        }
+    }
+
+    #[test]
+    fn upgrade_builds_on_a_baseline_proof() {
+        let old_checkpoints = vec![checkpoint(&block(1, 10), Some(vec![5; 4]))];
+        let inputs = upgrade_from_baseline(old_checkpoints.clone());
+        let verifier = MockVerifier::default();
+        let values = verify_hop(&inputs, &verifier).unwrap();
+
+        assert_eq!(*verifier.sp1.borrow(), vec![(VK, pv_digest(&inputs))]);
+        assert_eq!(
+            values.checkpoints_hash,
+            checkpoint_history_hash(&inputs.checkpoints)
+        );
+        // The path starts over from the upgrade.
+        assert_eq!(
+            values.path_root,
+            PathAccumulator::from_leaves(&[path_digest(&inputs.h2)]).root()
+        );
+
+        let check = |inputs: HopInputs| verify_hop(&inputs, &MockVerifier::default());
+        let (_, second) = chain();
+        assert_eq!(
+            check(HopInputs {
+                previous_path: second.previous_path,
+                ..inputs.clone()
+            }),
+            Err(HopError::PathRoot)
+        );
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: inputs.checkpoints[1..].to_vec(),
+                ..inputs.clone()
+            }),
+            Err(HopError::UpgradeCheckpoints)
+        );
+        // Only upgrades read the baseline layout.
+        assert_eq!(
+            check(HopInputs {
+                is_upgrade: false,
+                checkpoints: old_checkpoints,
+                vk_digest: VK,
+                ..inputs
+            }),
+            Err(HopError::MalformedPublicValues)
+        );
+    }
+
+    /// The verifier's Groth16 fixtures: a real SP1 proof, and a proof of recursion public values
+    /// under a test Groth16 vk.
+    const GROTH16_FIXTURES: [&str; 2] = [
+        include_str!("../../verifier/tests/fixtures/fibonacci_groth16.json"),
+        include_str!("../../verifier/tests/fixtures/recursion_groth16.json"),
+    ];
+
+    #[test]
+    fn groth16_proofs_verify_against_their_public_values() {
+        for fixture in GROTH16_FIXTURES {
+            let fixture: serde_json::Value = serde_json::from_str(fixture).unwrap();
+            let bytes = |field: &str| hex::decode(fixture[field].as_str().unwrap()).unwrap();
+            let (proof, public_values, groth16_vk) =
+                (bytes("proof"), bytes("public_values"), bytes("groth16_vk"));
+            let program_vk: [u32; 8] =
+                serde_json::from_value(fixture["program_vk"].clone()).unwrap();
+
+            verify_groth16(&proof, &public_values, &program_vk, &groth16_vk).unwrap();
+            // Not against their digest, which is what compressed proofs are verified against.
+            let pv_digest: [u8; 32] = Sha256::digest(&public_values).into();
+            assert!(verify_groth16(&proof, &pv_digest, &program_vk, &groth16_vk).is_err());
+            let mut other_vk = program_vk;
+            other_vk[0] += 1;
+            assert!(verify_groth16(&proof, &public_values, &other_vk, &groth16_vk).is_err());
+        }
+    }
+
+    #[test]
+    fn upgrade_checkpoint_must_follow_the_previous_proof() {
+        let check = |inputs: HopInputs| verify_hop(&inputs, &MockVerifier::default());
+        let inputs = upgrade(None);
+
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: Vec::new(),
+                ..inputs.clone()
+            }),
+            Err(HopError::UpgradeCheckpoints)
+        );
+        let mut twice = inputs.checkpoints.clone();
+        twice.extend(inputs.checkpoints.clone());
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: twice,
+                ..inputs.clone()
+            }),
+            Err(HopError::UpgradeCheckpoints)
+        );
+
+        let mut other_vk = inputs.checkpoints.clone();
+        other_vk[0].program_vk_hash = [9; 8];
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: other_vk,
+                ..inputs.clone()
+            }),
+            Err(HopError::UpgradeVk)
+        );
+
+        let mut other_block = inputs.checkpoints.clone();
+        other_block[0].block_hash = [0; 32];
+        assert_eq!(
+            check(HopInputs {
+                checkpoints: other_block,
+                ..inputs
+            }),
+            Err(HopError::UpgradeBlock)
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn any_path_from_genesis_verifies(gaps in prop::collection::vec(1u64..20, 1..5)) {
+            let mut height = 1;
+            let mut blocks = vec![block(height, 10)];
+            for gap in gaps {
+                height += gap;
+                blocks.push(block(height, height as i64 * 10));
+            }
+
+            let mut inputs = genesis_hop(&blocks[0], &blocks[1]);
+            for h2 in &blocks[2..] {
+                inputs = next_hop(&inputs, h2);
+            }
+            let values = verify_hop(&inputs, &MockVerifier::default()).unwrap();
+
+            let leaves: Vec<[u8; 32]> = blocks[1..].iter().map(path_digest).collect();
+            prop_assert_eq!(values.path_root, PathAccumulator::from_leaves(&leaves).root());
+            prop_assert_eq!(values.genesis_hash, inputs.genesis_hash);
+        }
+
+        #[test]
+        fn tampered_public_values_never_pass_as_the_proven_ones(
+            index in any::<prop::sample::Index>(),
+            flip in 1u8..,
+        ) {
+            let (_, mut second) = chain();
+            let proven = pv_digest(&second);
+            let public_values = &mut second.previous.as_mut().unwrap().public_values;
+            let index = index.index(public_values.len());
+            public_values[index] ^= flip;
+
+            // Either the hop is rejected, or the proof it asks for is of other public values,
+            // which the proof of the previous hop is not.
+            let verifier = MockVerifier::default();
+            if verify_hop(&second, &verifier).is_ok() {
+                prop_assert_eq!(verifier.sp1.borrow().len(), 1);
+                prop_assert_ne!(verifier.sp1.borrow()[0].1, proven);
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Fixtures for tests of the recursion rules, here and in the crates that build on them.
+
+use crate::recursion::{verify_groth16, ProofVerifier};
+use tendermint::Time;
+use tendermint_light_client_verifier::types::LightBlock;
+use tendermint_testgen::{Generator, Header, LightBlock as TestgenLightBlock, Validator};
+
+/// A block of "test-chain" at `height` and `time` (in seconds), signed by three validators with
+/// 40, 30 and 30 of the voting power.
+pub fn block(height: u64, time: i64) -> LightBlock {
+    let vals = [
+        Validator::new("a").voting_power(40),
+        Validator::new("b").voting_power(30),
+        Validator::new("c").voting_power(30),
+    ];
+    let header = Header::new(&vals)
+        .height(height)
+        .chain_id("test-chain")
+        .time(Time::from_unix_timestamp(time, 0).unwrap());
+    let block = TestgenLightBlock::new_default_with_header(header)
+        .generate()
+        .unwrap();
+    LightBlock::new(
+        block.signed_header,
+        block.validators,
+        block.next_validators,
+        block.provider,
+    )
+}
+
+/// Accepts every compressed proof, as the mock prover does, and verifies Groth16 proofs as the
+/// program does, so that everything but the proofs the zkVM verifies is checked.
+pub struct AcceptCompressed;
+
+impl ProofVerifier for AcceptCompressed {
+    fn verify_sp1_proof(&self, _: &[u32; 8], _: &[u8; 32]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn verify_groth16_proof(
+        &self,
+        proof: &[u8],
+        public_values: &[u8],
+        program_vk_hash: &[u32; 8],
+        groth16_vk: &[u8],
+    ) -> Result<(), String> {
+        verify_groth16(proof, public_values, program_vk_hash, groth16_vk)
+    }
+}
@@ -0,0 +1,83 @@
+//! Reading the inputs of [`crate::head`] and verifying proofs inside the zkVM, for the programs.
+
+use crate::head::{linked_header, verify_recursion_head, HeaderLink, ProvenHead};
+use crate::recursion::{verify_groth16, ProofVerifier};
+use tendermint::block::Header;
+
+/// Verifies compressed proofs as deferred proofs of the zkVM, and Groth16 proofs in the program.
+pub struct ZkvmProofVerifier;
+
+impl ProofVerifier for ZkvmProofVerifier {
+    fn verify_sp1_proof(&self, vk_digest: &[u32; 8], pv_digest: &[u8; 32]) -> Result<(), String> {
+        // Halts the program if the proof does not verify.
+        sp1_zkvm::lib::verify::verify_sp1_proof(vk_digest, pv_digest);
+        Ok(())
+    }
+
+    fn verify_groth16_proof(
+        &self,
+        proof: &[u8],
+        public_values: &[u8],
+        program_vk_hash: &[u32; 8],
+        groth16_vk: &[u8],
+    ) -> Result<(), String> {
+        verify_groth16(proof, public_values, program_vk_hash, groth16_vk)
+    }
+}
+
+fn read_header() -> Header {
+    serde_cbor::from_slice(&sp1_zkvm::io::read_vec()).expect("couldn't deserialize header")
+}
+
+/// Reads a recursion proof's vk digest and public values and the head it proves, and verifies
+/// them with [`verify_recursion_head`].
+pub fn read_recursion_head() -> ProvenHead {
+    println!("cycle-tracker-report-start: verify recursion head");
+    let vk_digest: [u32; 8] = sp1_zkvm::io::read();
+    let public_values = sp1_zkvm::io::read_vec();
+    let head = read_header();
+    let head = verify_recursion_head(&vk_digest, &public_values, head, &ZkvmProofVerifier)
+        .unwrap_or_else(|e| panic!("{}", e));
+    println!("cycle-tracker-report-end: verify recursion head");
+    head
+}
+
+/// Reads a [`HeaderLink`] and the headers after it, and returns the header it links to `head`.
+pub fn read_linked_header(head: &ProvenHead) -> Header {
+    println!("cycle-tracker-report-start: read linked header");
+    let link: HeaderLink = sp1_zkvm::io::read();
+    let headers: Vec<Header> = (0..link.header_count()).map(|_| read_header()).collect();
+    let header = linked_header(&head.header, &head.values.path_root, &link, &headers)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .clone();
+    println!("cycle-tracker-report-end: read linked header");
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groth16_proof_verifies_in_the_program() {
+        let fixture: serde_json::Value = serde_json::from_str(include_str!(
+            "../../verifier/tests/fixtures/fibonacci_groth16.json"
+        ))
+        .unwrap();
+        let bytes = |field: &str| hex::decode(fixture[field].as_str().unwrap()).unwrap();
+        let program_vk: [u32; 8] = serde_json::from_value(fixture["program_vk"].clone()).unwrap();
+
+        let verify = |public_values: &[u8]| {
+            ZkvmProofVerifier.verify_groth16_proof(
+                &bytes("proof"),
+                public_values,
+                &program_vk,
+                &bytes("groth16_vk"),
+            )
+        };
+        verify(&bytes("public_values")).unwrap();
+        let mut tampered = bytes("public_values");
+        tampered[0] ^= 1;
+        assert!(verify(&tampered).is_err());
+    }
+}
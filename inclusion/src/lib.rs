@@ -0,0 +1,72 @@
+//! Checks that data is part of a Celestia block, given the block's data root. Shared by the guest
+//! programs that build on the recursive head proof and by the host tools that prepare their
+//! inputs.
+
+use core::fmt;
+
+mod merkle;
+mod namespace;
+mod proof;
+mod share;
+
+pub use merkle::{simple_hash, MerkleProof};
+pub use namespace::{NamespaceDataProof, RowNamespaceData};
+pub use proof::{NmtProof, RowProof, ShareProof};
+pub use share::{
+    blobs_from_shares, share_commitment, Blob, NAMESPACE_SIZE, SHARE_SIZE, SIGNER_SIZE,
+    SUBTREE_ROOT_THRESHOLD,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InclusionError {
+    InvalidBlob(&'static str),
+    InvalidMerkleProof,
+    InvalidNamespaceProof(&'static str),
+    InvalidRowProof(&'static str),
+    InvalidShareProof(&'static str),
+}
+
+impl fmt::Display for InclusionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InclusionError::InvalidBlob(reason) => write!(f, "invalid blob: {}", reason),
+            InclusionError::InvalidMerkleProof => write!(f, "merkle proof does not verify"),
+            InclusionError::InvalidNamespaceProof(reason) => {
+                write!(f, "invalid namespace proof: {}", reason)
+            }
+            InclusionError::InvalidRowProof(reason) => write!(f, "invalid row proof: {}", reason),
+            InclusionError::InvalidShareProof(reason) => {
+                write!(f, "invalid share proof: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InclusionError {}
+
+/// Checks that `blob` is in the block with `data_root` and returns its share commitment.
+pub fn verify_blob_inclusion(
+    blob: &Blob,
+    proof: &ShareProof,
+    data_root: &[u8; 32],
+) -> Result<[u8; 32], InclusionError> {
+    // The shares are rebuilt from the blob, so a valid proof covers exactly this blob: its first
+    // share starts a sequence of the blob's length.
+    let shares = blob.to_shares()?;
+    proof.verify(data_root, blob.namespace_id(), &shares)?;
+    Ok(share_commitment(blob.namespace_id(), &shares))
+}
+
+/// Checks that `proof` holds all of the namespace's data in the block with `data_root`, and
+/// returns the share commitments of its blobs in the order they appear in the block.
+pub fn verify_namespace_data(
+    proof: &NamespaceDataProof,
+    data_root: &[u8; 32],
+    namespace: &[u8; NAMESPACE_SIZE],
+) -> Result<Vec<[u8; 32]>, InclusionError> {
+    let shares = proof.verify(data_root, nmt_rs::NamespaceId(*namespace))?;
+    blobs_from_shares(namespace, &shares)?
+        .iter()
+        .map(Blob::commitment)
+        .collect()
+}
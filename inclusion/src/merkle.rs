@@ -0,0 +1,125 @@
+//! The RFC 6962 merkle tree CometBFT uses for header fields such as `data_hash`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::InclusionError;
+
+fn leaf_hash(leaf: &[u8]) -> [u8; 32] {
+    Sha256::new_with_prefix([0u8])
+        .chain_update(leaf)
+        .finalize()
+        .into()
+}
+
+fn inner_hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+    Sha256::new_with_prefix([1u8])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// The largest power of two less than `total`.
+fn split_point(total: u64) -> u64 {
+    let k = 1 << total.ilog2();
+    if k == total {
+        k >> 1
+    } else {
+        k
+    }
+}
+
+/// The merkle root of `leaves`, as computed by `merkle.HashFromByteSlices`.
+pub fn simple_hash(leaves: &[impl AsRef<[u8]>]) -> [u8; 32] {
+    match leaves.len() {
+        0 => Sha256::digest([]).into(),
+        1 => leaf_hash(leaves[0].as_ref()),
+        len => {
+            let (left, right) = leaves.split_at(split_point(len as u64) as usize);
+            inner_hash(&simple_hash(left), &simple_hash(right))
+        }
+    }
+}
+
+/// A CometBFT `merkle.Proof` of one leaf.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub total: u64,
+    pub index: u64,
+    pub leaf_hash: Vec<u8>,
+    /// Sibling hashes, from the leaf up.
+    pub aunts: Vec<Vec<u8>>,
+}
+
+impl MerkleProof {
+    /// Checks that `leaf` is the `index`th leaf of the tree with root `root`.
+    pub fn verify(&self, root: &[u8; 32], leaf: &[u8]) -> Result<(), InclusionError> {
+        if self.leaf_hash != leaf_hash(leaf) {
+            return Err(InclusionError::InvalidMerkleProof);
+        }
+        match compute_root(self.index, self.total, &self.leaf_hash, &self.aunts) {
+            Some(computed) if &computed == root => Ok(()),
+            _ => Err(InclusionError::InvalidMerkleProof),
+        }
+    }
+}
+
+fn compute_root(index: u64, total: u64, leaf_hash: &[u8], aunts: &[Vec<u8>]) -> Option<[u8; 32]> {
+    if index >= total {
+        return None;
+    }
+    if total == 1 {
+        return match aunts {
+            [] => leaf_hash.try_into().ok(),
+            _ => None,
+        };
+    }
+    let (aunt, rest) = aunts.split_last()?;
+    let left_size = split_point(total);
+    if index < left_size {
+        let left = compute_root(index, left_size, leaf_hash, rest)?;
+        Some(inner_hash(&left, aunt))
+    } else {
+        let right = compute_root(index - left_size, total - left_size, leaf_hash, rest)?;
+        Some(inner_hash(aunt, &right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_single_leaf() {
+        assert_eq!(
+            hex::encode(simple_hash(&[] as &[&[u8]])),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(simple_hash(&[b"leaf"]), leaf_hash(b"leaf"));
+    }
+
+    #[test]
+    fn proofs_of_every_leaf() {
+        let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 3]).collect();
+        let root = simple_hash(&leaves);
+
+        // Aunts of leaf 2 in a tree of 5: leaf 3, then the hash of leaves 0 and 1, then leaf 4.
+        let proof = MerkleProof {
+            total: 5,
+            index: 2,
+            leaf_hash: leaf_hash(&leaves[2]).to_vec(),
+            aunts: vec![
+                leaf_hash(&leaves[3]).to_vec(),
+                simple_hash(&leaves[..2]).to_vec(),
+                leaf_hash(&leaves[4]).to_vec(),
+            ],
+        };
+        proof.verify(&root, &leaves[2]).unwrap();
+        assert!(proof.verify(&root, &leaves[3]).is_err());
+
+        let mut wrong_index = proof.clone();
+        wrong_index.index = 3;
+        assert!(wrong_index.verify(&root, &leaves[2]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Proofs that a set of shares is all of a namespace's data in a block, in the shape of
+//! celestia-node's `NamespaceData`.
+
+use nmt_rs::{
+    simple_merkle::proof::Proof, NamespaceId, NamespaceProof, NamespacedHash, NamespacedSha2Hasher,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle::simple_hash;
+use crate::proof::NmtProof;
+use crate::share::NAMESPACE_SIZE;
+use crate::InclusionError;
+
+/// The shares of a namespace in one row, with an NMT proof that they are all of the row's shares
+/// of it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RowNamespaceData {
+    pub shares: Vec<Vec<u8>>,
+    pub proof: NmtProof,
+    /// For a row whose namespace range covers the namespace without holding any of it: the
+    /// serialized namespaced hash of the leaf that follows where the namespace would be.
+    pub absence_leaf: Option<Vec<u8>>,
+}
+
+/// Proof that some shares are all of a namespace's shares in the block with a given data root.
+///
+/// The data availability header holds every row root, so the proof can't leave out a row whose
+/// range covers the namespace.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceDataProof {
+    pub row_roots: Vec<Vec<u8>>,
+    pub column_roots: Vec<Vec<u8>>,
+    /// One entry per row of the original square that covers the namespace, in row order.
+    pub rows: Vec<RowNamespaceData>,
+}
+
+impl NamespaceDataProof {
+    /// Checks the proof against the data root, and returns all of the namespace's shares in
+    /// square order. An empty list proves the namespace has no data in the block.
+    pub fn verify(
+        &self,
+        data_root: &[u8; 32],
+        namespace: NamespaceId<NAMESPACE_SIZE>,
+    ) -> Result<Vec<Vec<u8>>, InclusionError> {
+        let width = self.row_roots.len();
+        if width < 2 || !width.is_power_of_two() || self.column_roots.len() != width {
+            return Err(InclusionError::InvalidNamespaceProof(
+                "not an extended square",
+            ));
+        }
+        let roots: Vec<&[u8]> = self
+            .row_roots
+            .iter()
+            .chain(&self.column_roots)
+            .map(Vec::as_slice)
+            .collect();
+        if &simple_hash(&roots) != data_root {
+            return Err(InclusionError::InvalidMerkleProof);
+        }
+
+        // Only the first half of the rows is original data; the rest is parity.
+        let original_width = width / 2;
+        let mut rows = self.rows.iter();
+        let mut shares = Vec::new();
+        for root in &self.row_roots[..original_width] {
+            let root = NamespacedHash::try_from(root.as_slice())
+                .map_err(|_| InclusionError::InvalidNamespaceProof("malformed row root"))?;
+            if !root.contains::<NamespacedSha2Hasher<NAMESPACE_SIZE>>(namespace) {
+                continue;
+            }
+            let row = rows
+                .next()
+                .ok_or(InclusionError::InvalidNamespaceProof("missing a row"))?;
+            if row.proof.end as usize > original_width {
+                return Err(InclusionError::InvalidNamespaceProof(
+                    "shares outside the original square",
+                ));
+            }
+            row.to_namespace_proof()?
+                .verify_complete_namespace(&root, &row.shares, namespace)
+                .map_err(|_| InclusionError::InvalidNamespaceProof("NMT proof does not verify"))?;
+            shares.extend(row.shares.iter().cloned());
+        }
+        if rows.next().is_some() {
+            return Err(InclusionError::InvalidNamespaceProof("too many rows"));
+        }
+        Ok(shares)
+    }
+}
+
+impl RowNamespaceData {
+    fn to_namespace_proof(
+        &self,
+    ) -> Result<NamespaceProof<NamespacedSha2Hasher<NAMESPACE_SIZE>, NAMESPACE_SIZE>, InclusionError>
+    {
+        let siblings = self
+            .proof
+            .nodes
+            .iter()
+            .map(|node| NamespacedHash::try_from(node.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| InclusionError::InvalidNamespaceProof("malformed NMT node"))?;
+        let proof = Proof {
+            siblings,
+            range: self.proof.start..self.proof.end,
+        };
+        match (&self.absence_leaf, self.shares.is_empty()) {
+            (None, false) => Ok(NamespaceProof::PresenceProof {
+                proof,
+                ignore_max_ns: true,
+            }),
+            (Some(leaf), true) => Ok(NamespaceProof::AbsenceProof {
+                proof,
+                ignore_max_ns: true,
+                leaf: Some(NamespacedHash::try_from(leaf.as_slice()).map_err(|_| {
+                    InclusionError::InvalidNamespaceProof("malformed absence leaf")
+                })?),
+            }),
+            _ => Err(InclusionError::InvalidNamespaceProof(
+                "proof type does not match the shares",
+            )),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Proofs that a range of shares is in a block, in the shape of celestia-core's `ShareProof`.
+
+use nmt_rs::{
+    simple_merkle::proof::Proof, NamespaceId, NamespaceProof, NamespacedHash, NamespacedSha2Hasher,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle::MerkleProof;
+use crate::share::NAMESPACE_SIZE;
+use crate::InclusionError;
+
+/// An NMT range proof of the shares `start..end` of one row.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NmtProof {
+    pub start: u32,
+    pub end: u32,
+    /// Serialized namespaced hashes (min namespace, max namespace, digest) of the siblings.
+    pub nodes: Vec<Vec<u8>>,
+}
+
+/// Proofs of the row roots `start_row..=end_row` into the data root.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RowProof {
+    pub row_roots: Vec<Vec<u8>>,
+    pub proofs: Vec<MerkleProof>,
+    pub start_row: u32,
+    pub end_row: u32,
+}
+
+/// Proof that consecutive shares of one namespace are in the original data square, one NMT proof
+/// per row they span.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShareProof {
+    pub share_proofs: Vec<NmtProof>,
+    pub row_proof: RowProof,
+}
+
+impl RowProof {
+    /// Checks the row roots against the data root, and returns the width of the original square.
+    pub fn verify(&self, data_root: &[u8; 32]) -> Result<u32, InclusionError> {
+        let rows = self
+            .end_row
+            .checked_sub(self.start_row)
+            .ok_or(InclusionError::InvalidRowProof("end row before start row"))?
+            + 1;
+        if self.row_roots.len() != rows as usize || self.proofs.len() != rows as usize {
+            return Err(InclusionError::InvalidRowProof("wrong number of rows"));
+        }
+
+        // The data root commits to the 2k row roots of the extended square, then its 2k column
+        // roots, where the width k of the original square is a power of two. Only rows hold
+        // shares in square order.
+        let total = self.proofs[0].total;
+        if total < 4 || !total.is_power_of_two() {
+            return Err(InclusionError::InvalidRowProof("not an extended square"));
+        }
+        for (i, (root, proof)) in self.row_roots.iter().zip(&self.proofs).enumerate() {
+            if proof.total != total || proof.index != self.start_row as u64 + i as u64 {
+                return Err(InclusionError::InvalidRowProof("row index mismatch"));
+            }
+            if proof.index >= total / 4 {
+                return Err(InclusionError::InvalidRowProof(
+                    "not a row of the original square",
+                ));
+            }
+            proof.verify(data_root, root)?;
+        }
+        Ok((total / 4) as u32)
+    }
+}
+
+impl ShareProof {
+    /// Checks that `shares` are consecutive shares of `namespace` in the block with `data_root`.
+    pub fn verify(
+        &self,
+        data_root: &[u8; 32],
+        namespace: NamespaceId<NAMESPACE_SIZE>,
+        shares: &[Vec<u8>],
+    ) -> Result<(), InclusionError> {
+        let width = self.row_proof.verify(data_root)?;
+        if self.share_proofs.len() != self.row_proof.row_roots.len() {
+            return Err(InclusionError::InvalidShareProof(
+                "one proof per row is required",
+            ));
+        }
+
+        let mut rest = shares;
+        let last = self.share_proofs.len() - 1;
+        for (i, (proof, root)) in self
+            .share_proofs
+            .iter()
+            .zip(&self.row_proof.row_roots)
+            .enumerate()
+        {
+            // The range must continue from the previous row and stay in the original square.
+            let continues = i == 0 || proof.start == 0;
+            let reaches_end = i == last || proof.end == width;
+            if proof.start >= proof.end || proof.end > width || !continues || !reaches_end {
+                return Err(InclusionError::InvalidShareProof(
+                    "shares are not consecutive",
+                ));
+            }
+
+            let count = (proof.end - proof.start) as usize;
+            if rest.len() < count {
+                return Err(InclusionError::InvalidShareProof("too few shares"));
+            }
+            let (leaves, tail) = rest.split_at(count);
+            rest = tail;
+
+            let root = NamespacedHash::try_from(root.as_slice())
+                .map_err(|_| InclusionError::InvalidShareProof("malformed row root"))?;
+            proof
+                .to_namespace_proof()?
+                .verify_range(&root, leaves, namespace)
+                .map_err(|_| InclusionError::InvalidShareProof("NMT proof does not verify"))?;
+        }
+        if !rest.is_empty() {
+            return Err(InclusionError::InvalidShareProof("too many shares"));
+        }
+        Ok(())
+    }
+}
+
+impl NmtProof {
+    fn to_namespace_proof(
+        &self,
+    ) -> Result<NamespaceProof<NamespacedSha2Hasher<NAMESPACE_SIZE>, NAMESPACE_SIZE>, InclusionError>
+    {
+        let siblings = self
+            .nodes
+            .iter()
+            .map(|node| NamespacedHash::try_from(node.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| InclusionError::InvalidShareProof("malformed NMT node"))?;
+        Ok(NamespaceProof::PresenceProof {
+            proof: Proof {
+                siblings,
+                range: self.start..self.end,
+            },
+            ignore_max_ns: true,
+        })
+    }
+}
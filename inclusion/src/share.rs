@@ -0,0 +1,332 @@
+//! Splitting blobs into shares and computing their share commitment, following celestia-app's
+//! `shares` and `inclusion` packages.
+
+use nmt_rs::{
+    simple_merkle::db::NoopDb, NamespaceId, NamespaceMerkleHasher, NamespaceMerkleTree,
+    NamespacedSha2Hasher,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle::simple_hash;
+use crate::InclusionError;
+
+pub const SHARE_SIZE: usize = 512;
+pub const NAMESPACE_SIZE: usize = 29;
+pub const SIGNER_SIZE: usize = 20;
+
+/// Above this many subtree roots, a blob commitment uses wider subtrees.
+pub const SUBTREE_ROOT_THRESHOLD: u64 = 64;
+
+/// A blob as posted with `MsgPayForBlobs`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    /// The version byte followed by the 28 byte namespace id.
+    pub namespace: [u8; NAMESPACE_SIZE],
+    pub data: Vec<u8>,
+    /// 0, or 1 for blobs that carry their signer.
+    pub share_version: u8,
+    pub signer: Option<[u8; SIGNER_SIZE]>,
+}
+
+pub(crate) type Nmt =
+    NamespaceMerkleTree<NoopDb, NamespacedSha2Hasher<NAMESPACE_SIZE>, NAMESPACE_SIZE>;
+
+pub(crate) fn nmt_hasher() -> NamespacedSha2Hasher<NAMESPACE_SIZE> {
+    NamespacedSha2Hasher::with_ignore_max_ns(true)
+}
+
+impl Blob {
+    pub fn namespace_id(&self) -> NamespaceId<NAMESPACE_SIZE> {
+        NamespaceId(self.namespace)
+    }
+
+    /// Splits the blob into the sparse shares it occupies in the data square.
+    pub fn to_shares(&self) -> Result<Vec<Vec<u8>>, InclusionError> {
+        let signer: &[u8] = match (self.share_version, &self.signer) {
+            (0, None) => &[],
+            (1, Some(signer)) => signer,
+            _ => {
+                return Err(InclusionError::InvalidBlob(
+                    "share version and signer disagree",
+                ))
+            }
+        };
+        if self.data.is_empty() {
+            return Err(InclusionError::InvalidBlob("empty blob"));
+        }
+        let sequence_len: u32 = self
+            .data
+            .len()
+            .try_into()
+            .map_err(|_| InclusionError::InvalidBlob("blob too large"))?;
+
+        let mut shares = Vec::new();
+        let mut data = self.data.as_slice();
+        loop {
+            let first = shares.is_empty();
+            let mut share = Vec::with_capacity(SHARE_SIZE);
+            share.extend_from_slice(&self.namespace);
+            share.push((self.share_version << 1) | first as u8);
+            if first {
+                share.extend_from_slice(&sequence_len.to_be_bytes());
+                share.extend_from_slice(signer);
+            }
+            let len = data.len().min(SHARE_SIZE - share.len());
+            share.extend_from_slice(&data[..len]);
+            share.resize(SHARE_SIZE, 0);
+            shares.push(share);
+
+            data = &data[len..];
+            if data.is_empty() {
+                return Ok(shares);
+            }
+        }
+    }
+
+    /// The share commitment of the blob, as found in `MsgPayForBlobs`.
+    pub fn commitment(&self) -> Result<[u8; 32], InclusionError> {
+        Ok(share_commitment(self.namespace_id(), &self.to_shares()?))
+    }
+}
+
+/// Parses the sparse shares of one namespace back into its blobs, in order, skipping namespace
+/// padding. The shares must be exactly the ones `Blob::to_shares` produces for each blob.
+pub fn blobs_from_shares(
+    namespace: &[u8; NAMESPACE_SIZE],
+    shares: &[Vec<u8>],
+) -> Result<Vec<Blob>, InclusionError> {
+    let mut blobs = Vec::new();
+    let mut rest = shares;
+    while let Some(first) = rest.first() {
+        if first.len() != SHARE_SIZE || first[..NAMESPACE_SIZE] != namespace[..] {
+            return Err(InclusionError::InvalidBlob("share of another namespace"));
+        }
+        let info = first[NAMESPACE_SIZE];
+        if info & 1 == 0 {
+            return Err(InclusionError::InvalidBlob(
+                "expected the start of a sequence",
+            ));
+        }
+        let share_version = info >> 1;
+        let len_start = NAMESPACE_SIZE + 1;
+        let sequence_len = u32::from_be_bytes(
+            first[len_start..len_start + 4]
+                .try_into()
+                .expect("slice is 4 bytes"),
+        ) as usize;
+        let mut payload_start = len_start + 4;
+
+        // Namespace padding between blobs is a sequence of length zero.
+        if sequence_len == 0 {
+            rest = &rest[1..];
+            continue;
+        }
+        let signer = match share_version {
+            0 => None,
+            1 => {
+                let signer = first[payload_start..payload_start + SIGNER_SIZE]
+                    .try_into()
+                    .expect("slice is signer sized");
+                payload_start += SIGNER_SIZE;
+                Some(signer)
+            }
+            _ => return Err(InclusionError::InvalidBlob("unsupported share version")),
+        };
+
+        let mut data = first[payload_start..].to_vec();
+        let mut count = 1;
+        while data.len() < sequence_len {
+            let share = rest
+                .get(count)
+                .ok_or(InclusionError::InvalidBlob("blob is missing shares"))?;
+            if share.len() != SHARE_SIZE {
+                return Err(InclusionError::InvalidBlob("malformed share"));
+            }
+            data.extend_from_slice(&share[NAMESPACE_SIZE + 1..]);
+            count += 1;
+        }
+        data.truncate(sequence_len);
+
+        // Rebuilding the shares checks the namespace, info bytes and padding of the rest.
+        let blob = Blob {
+            namespace: *namespace,
+            data,
+            share_version,
+            signer,
+        };
+        if blob.to_shares()? != rest[..count] {
+            return Err(InclusionError::InvalidBlob(
+                "shares are not in canonical form",
+            ));
+        }
+        blobs.push(blob);
+        rest = &rest[count..];
+    }
+    Ok(blobs)
+}
+
+/// The merkle root of the NMT roots of the subtrees the shares are split into.
+pub fn share_commitment(namespace: NamespaceId<NAMESPACE_SIZE>, shares: &[Vec<u8>]) -> [u8; 32] {
+    let width = subtree_width(shares.len() as u64);
+    let mut subtree_roots = Vec::new();
+    let mut rest = shares;
+    for size in merkle_mountain_range_sizes(shares.len() as u64, width) {
+        let (leaves, tail) = rest.split_at(size as usize);
+        let mut tree = Nmt::with_hasher(nmt_hasher());
+        for share in leaves {
+            tree.push_leaf(share, namespace)
+                .expect("all shares are in one namespace");
+        }
+        subtree_roots.push(tree.root().iter().collect::<Vec<u8>>());
+        rest = tail;
+    }
+    simple_hash(&subtree_roots)
+}
+
+/// The widest subtree a blob of `share_count` shares commits to (ADR-013).
+fn subtree_width(share_count: u64) -> u64 {
+    let width = share_count
+        .div_ceil(SUBTREE_ROOT_THRESHOLD)
+        .next_power_of_two();
+    width.min(blob_min_square_size(share_count))
+}
+
+/// The smallest square that fits `share_count` shares.
+fn blob_min_square_size(share_count: u64) -> u64 {
+    let mut side = 1;
+    while side * side < share_count {
+        side += 1;
+    }
+    side.next_power_of_two()
+}
+
+fn merkle_mountain_range_sizes(mut total: u64, max_tree_size: u64) -> Vec<u64> {
+    let mut sizes = Vec::new();
+    while total != 0 {
+        let size = if total >= max_tree_size {
+            max_tree_size
+        } else {
+            // The largest power of two that fits.
+            1 << total.ilog2()
+        };
+        sizes.push(size);
+        total -= size;
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARE_INFO_BYTES: usize = 1;
+    const SEQUENCE_LEN_BYTES: usize = 4;
+
+    fn blob(len: usize) -> Blob {
+        let mut namespace = [0u8; NAMESPACE_SIZE];
+        namespace[NAMESPACE_SIZE - 10..].copy_from_slice(&[1; 10]);
+        Blob {
+            namespace,
+            data: (0..len).map(|i| i as u8).collect(),
+            share_version: 0,
+            signer: None,
+        }
+    }
+
+    #[test]
+    fn single_share_layout() {
+        let shares = blob(7).to_shares().unwrap();
+        assert_eq!(shares.len(), 1);
+        let share = &shares[0];
+        assert_eq!(share.len(), SHARE_SIZE);
+        assert_eq!(
+            &share[NAMESPACE_SIZE..NAMESPACE_SIZE + 12],
+            &[1, 0, 0, 0, 7, 0, 1, 2, 3, 4, 5, 6]
+        );
+        assert!(share[NAMESPACE_SIZE + 12..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn continuation_shares() {
+        let first = SHARE_SIZE - NAMESPACE_SIZE - SHARE_INFO_BYTES - SEQUENCE_LEN_BYTES;
+        let continuation = SHARE_SIZE - NAMESPACE_SIZE - SHARE_INFO_BYTES;
+        assert_eq!(blob(first).to_shares().unwrap().len(), 1);
+        assert_eq!(blob(first + 1).to_shares().unwrap().len(), 2);
+        assert_eq!(blob(first + continuation).to_shares().unwrap().len(), 2);
+
+        let shares = blob(first + 1).to_shares().unwrap();
+        assert_eq!(shares[1][NAMESPACE_SIZE], 0);
+        assert_eq!(shares[1][NAMESPACE_SIZE + 1], first as u8);
+    }
+
+    #[test]
+    fn signer_requires_share_version_one() {
+        let mut signed = blob(3);
+        signed.signer = Some([9; SIGNER_SIZE]);
+        assert!(signed.to_shares().is_err());
+
+        signed.share_version = 1;
+        let share = &signed.to_shares().unwrap()[0];
+        assert_eq!(share[NAMESPACE_SIZE], 0b11);
+        assert_eq!(
+            &share[NAMESPACE_SIZE + 5..NAMESPACE_SIZE + 25],
+            &[9; SIGNER_SIZE]
+        );
+    }
+
+    #[test]
+    fn commitment_matches_celestia_types() {
+        // Computed with celestia-types 0.11 for the same namespace and data.
+        let mut namespace = [0u8; NAMESPACE_SIZE];
+        namespace[NAMESPACE_SIZE - 10..]
+            .copy_from_slice(&hex::decode("d8cbb533a24261c4c0a3").unwrap());
+        let blob = Blob {
+            namespace,
+            data: vec![7; 200_000],
+            share_version: 0,
+            signer: None,
+        };
+        assert_eq!(
+            hex::encode(blob.commitment().unwrap()),
+            "28d3c7664cea7f2bd3d399b34da355a9381900be06d4246c4c625dffa0d97dac"
+        );
+    }
+
+    #[test]
+    fn blobs_round_trip_through_shares() {
+        let mut signed = blob(900);
+        signed.share_version = 1;
+        signed.signer = Some([9; SIGNER_SIZE]);
+        let first = blob(7);
+        let namespace = first.namespace;
+
+        let mut padding = namespace.to_vec();
+        padding.push(1);
+        padding.resize(SHARE_SIZE, 0);
+
+        let mut shares = first.to_shares().unwrap();
+        shares.push(padding);
+        shares.extend(signed.to_shares().unwrap());
+        assert_eq!(
+            blobs_from_shares(&namespace, &shares).unwrap(),
+            vec![first, signed]
+        );
+        assert_eq!(blobs_from_shares(&namespace, &[]).unwrap(), vec![]);
+
+        // A blob cut short, or trailing bytes after its data, are rejected.
+        assert!(blobs_from_shares(&namespace, &shares[..shares.len() - 1]).is_err());
+        let last = shares.len() - 1;
+        shares[last][SHARE_SIZE - 1] = 1;
+        assert!(blobs_from_shares(&namespace, &shares).is_err());
+    }
+
+    #[test]
+    fn subtree_sizes() {
+        assert_eq!(subtree_width(1), 1);
+        assert_eq!(subtree_width(64), 1);
+        assert_eq!(subtree_width(65), 2);
+        assert_eq!(subtree_width(4096), 64);
+        assert_eq!(merkle_mountain_range_sizes(11, 4), vec![4, 4, 2, 1]);
+        assert_eq!(merkle_mountain_range_sizes(2, 4), vec![2]);
+    }
+}
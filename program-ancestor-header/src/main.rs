@@ -0,0 +1,32 @@
+//! Proves a header below the head proven by the recursion program, by following `last_block_id`
+//! hashes back from the head through every header in between.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use common::AncestorHeaderPublicValues;
+use consensus::zkvm::{read_linked_header, read_recursion_head};
+
+pub fn main() {
+    let head = read_recursion_head();
+    let target = read_linked_header(&head);
+
+    let data_root: [u8; 32] = target
+        .data_hash
+        .expect("target has no data hash")
+        .as_bytes()
+        .try_into()
+        .expect("data hash must be 32 bytes");
+    sp1_zkvm::io::commit(&AncestorHeaderPublicValues {
+        hash: target
+            .hash()
+            .as_bytes()
+            .try_into()
+            .expect("header hash must be 32 bytes"),
+        height: target.height.value(),
+        data_root,
+        head_hash: head.hash,
+        genesis_hash: head.genesis_hash,
+        recursion_vk_digest: head.recursion_vk_digest,
+    });
+}
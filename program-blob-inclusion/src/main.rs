@@ -0,0 +1,36 @@
+//! Proves that a blob was posted to Celestia, in a block linked to the head proven by the
+//! recursion program: the head itself, an ancestor of it, or a header verified on the way to it.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use common::BlobInclusionPublicValues;
+use consensus::zkvm::{read_linked_header, read_recursion_head};
+use inclusion::{verify_blob_inclusion, Blob, ShareProof};
+
+pub fn main() {
+    let head = read_recursion_head();
+    let block = read_linked_header(&head);
+    let data_root: [u8; 32] = block
+        .data_hash
+        .expect("block has no data hash")
+        .as_bytes()
+        .try_into()
+        .expect("data hash must be 32 bytes");
+
+    println!("cycle-tracker-report-start: verify blob inclusion");
+    let blob: Blob = sp1_zkvm::io::read();
+    let share_proof: ShareProof = sp1_zkvm::io::read();
+    let commitment = verify_blob_inclusion(&blob, &share_proof, &data_root)
+        .unwrap_or_else(|e| panic!("Blob inclusion failed: {}", e));
+    println!("cycle-tracker-report-end: verify blob inclusion");
+
+    sp1_zkvm::io::commit(&BlobInclusionPublicValues {
+        namespace: blob.namespace,
+        commitment,
+        height: block.height.value(),
+        head_hash: head.hash,
+        genesis_hash: head.genesis_hash,
+        recursion_vk_digest: head.recursion_vk_digest,
+    });
+}
@@ -0,0 +1,37 @@
+//! Proves which blobs a namespace holds in a block linked to the head proven by the recursion
+//! program (the head itself, an ancestor of it, or a header verified on the way to it): all of
+//! them, so that none can be withheld.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use common::NamespaceCompletenessPublicValues;
+use consensus::zkvm::{read_linked_header, read_recursion_head};
+use inclusion::{verify_namespace_data, NamespaceDataProof};
+
+pub fn main() {
+    let head = read_recursion_head();
+    let block = read_linked_header(&head);
+    let data_root: [u8; 32] = block
+        .data_hash
+        .expect("block has no data hash")
+        .as_bytes()
+        .try_into()
+        .expect("data hash must be 32 bytes");
+
+    println!("cycle-tracker-report-start: verify namespace data");
+    let namespace: [u8; 29] = sp1_zkvm::io::read();
+    let proof: NamespaceDataProof = sp1_zkvm::io::read();
+    let commitments = verify_namespace_data(&proof, &data_root, &namespace)
+        .unwrap_or_else(|e| panic!("Namespace data failed: {}", e));
+    println!("cycle-tracker-report-end: verify namespace data");
+
+    sp1_zkvm::io::commit(&NamespaceCompletenessPublicValues {
+        namespace,
+        commitments,
+        height: block.height.value(),
+        head_hash: head.hash,
+        genesis_hash: head.genesis_hash,
+        recursion_vk_digest: head.recursion_vk_digest,
+    });
+}
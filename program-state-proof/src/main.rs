@@ -0,0 +1,33 @@
+//! Proves a key's value in Celestia state, or its absence, against the `app_hash` of a block
+//! linked to the head proven by the recursion program: the head itself, an ancestor of it, or a
+//! header verified on the way to it.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use common::StatePublicValues;
+use consensus::zkvm::{read_linked_header, read_recursion_head};
+use state_proof::StateProof;
+
+pub fn main() {
+    let head = read_recursion_head();
+    let block = read_linked_header(&head);
+
+    // The app hash of a block commits to the state left by the block before it.
+    println!("cycle-tracker-report-start: verify state proof");
+    let proof: StateProof = sp1_zkvm::io::read();
+    proof
+        .verify(block.app_hash.as_bytes())
+        .unwrap_or_else(|e| panic!("State proof failed: {}", e));
+    println!("cycle-tracker-report-end: verify state proof");
+
+    sp1_zkvm::io::commit(&StatePublicValues {
+        store: proof.store,
+        key: proof.key,
+        value: proof.value,
+        height: block.height.value() - 1,
+        head_hash: head.hash,
+        genesis_hash: head.genesis_hash,
+        recursion_vk_digest: head.recursion_vk_digest,
+    });
+}
@@ -1,5 +1,9 @@
 use sp1_build::build_program_with_args;
 
 fn main() {
-    build_program_with_args("../program-verify-consensus-recursion", Default::default())
+    build_program_with_args("../program-verify-consensus-recursion", Default::default());
+    build_program_with_args("../program-blob-inclusion", Default::default());
+    build_program_with_args("../program-namespace-completeness", Default::default());
+    build_program_with_args("../program-state-proof", Default::default());
+    build_program_with_args("../program-ancestor-header", Default::default());
 }
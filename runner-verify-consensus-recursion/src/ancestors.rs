@@ -0,0 +1,42 @@
+use consensus::head::{walk_ancestors, HeadError, HeaderLink};
+use sp1_sdk::{SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+use std::fs;
+use std::path::Path;
+use tendermint::block::Header;
+
+use crate::verify::{linked_header_stdin, recursion_head_stdin};
+
+/// Checks that `ancestors` are the headers below `head`, newest first, each one the block named
+/// by the `last_block_id` of the one before. Returns the last one, or the head if there are none.
+pub fn check_ancestors<'a>(
+    head: &'a Header,
+    ancestors: &'a [Header],
+) -> Result<&'a Header, String> {
+    walk_ancestors(head, ancestors).map_err(|e| match e {
+        HeadError::Ancestor(e) => e,
+        e => e.to_string(),
+    })
+}
+
+/// Reads the ancestor chain written by `fetch_ancestors`: a JSON array of headers, newest first.
+pub fn read_ancestors(path: &Path) -> Result<Vec<Header>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize ancestors {:?}: {}", path, e))
+}
+
+/// Writes the input of the ancestor header program: the target is linked to the head by `link`,
+/// either [`HeaderLink::Ancestors`] or [`HeaderLink::PathAncestors`].
+pub fn ancestor_header_stdin(
+    recursion_proof: &SP1ProofWithPublicValues,
+    recursion_vk: &SP1VerifyingKey,
+    head: &Header,
+    link: &HeaderLink,
+    headers: &[Header],
+    mock: bool,
+) -> Result<SP1Stdin, String> {
+    let mut stdin = recursion_head_stdin(recursion_proof, recursion_vk, head, mock)?;
+    linked_header_stdin(&mut stdin, link, headers)?;
+    Ok(stdin)
+}
@@ -0,0 +1,144 @@
+use clap::Parser;
+use runner_verify_consensus_recursion::{
+    hop::execute_path,
+    profile::{CycleProfile, HopProfile},
+    prover::{ProverMode, RecursionProver},
+};
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+use std::fs;
+use std::path::PathBuf;
+
+/// Profile the cycles of each span of the recursion program over every hop of a headers directory
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the directory of header JSON files, starting at genesis
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// Only run the first N hops
+    #[arg(short = 'n', long)]
+    hops: Option<usize>,
+
+    /// Send every signature of each h2's commit, as `run_program --keep-all-signatures` does
+    #[arg(long, default_value_t = false)]
+    keep_all_signatures: bool,
+
+    /// Where to write the profile as JSON, which can serve as a later baseline
+    #[arg(long, value_name = "PATH")]
+    json: Option<PathBuf>,
+
+    /// Where to write the profile as CSV
+    #[arg(long, value_name = "PATH")]
+    csv: Option<PathBuf>,
+
+    /// A profile JSON file to compare against. Exits non-zero if any span regressed.
+    #[arg(short = 'b', long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// How many percent more cycles than the baseline a span may take
+    #[arg(short = 't', long, default_value_t = 1.0)]
+    tolerance: f64,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    let headers = list_headers_dir(&args.headers_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if headers.len() < 2 {
+        eprintln!("Error: need at least two headers in {:?}", args.headers_dir);
+        std::process::exit(1);
+    }
+    let hops = args
+        .hops
+        .unwrap_or(headers.len() - 1)
+        .min(headers.len() - 1);
+    let baseline = args.baseline.as_ref().map(|path| {
+        CycleProfile::read(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    let client =
+        RecursionProver::new(ProverMode::Mock, None).expect("local provers need no configuration");
+    let blocks = headers.iter().take(hops + 1).map(|(_, path)| {
+        read_light_block_file(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    println!(
+        "{:>10} {:>10} {:>13} {:>13}",
+        "h1", "h2", "cycles", "verify"
+    );
+    let mut profiles = Vec::new();
+    execute_path(&client, &[], blocks, args.keep_all_signatures, |hop| {
+        let (h1, h2) = (hop.h1.height().value(), hop.h2.height().value());
+        let (_, report) = hop.result.unwrap_or_else(|e| {
+            eprintln!("Error: hop {} -> {}: {}", h1, h2, e);
+            std::process::exit(1);
+        });
+
+        let profile = HopProfile::new(h1, h2, &report);
+        println!(
+            "{:>10} {:>10} {:>13} {:>13}",
+            profile.h1,
+            profile.h2,
+            profile.cycles,
+            profile
+                .spans
+                .get("setup verifier and verify consensus")
+                .copied()
+                .unwrap_or(0)
+        );
+        profiles.push(profile);
+    });
+
+    let profile = CycleProfile::new(profiles);
+    println!("\n{:>13}  span", "cycles");
+    for (span, cycles) in &profile.spans {
+        println!("{:>13}  {}", cycles, span);
+    }
+    println!("{:>13}  total over {} hops", profile.cycles, hops);
+
+    if let Some(output_path) = &args.json {
+        let json = serde_json::to_string_pretty(&profile).expect("failed to serialize profile");
+        fs::write(output_path, json).expect("failed to write profile JSON");
+        println!("Profile saved to {:?}", output_path);
+    }
+    if let Some(output_path) = &args.csv {
+        fs::write(output_path, profile.to_csv()).expect("failed to write profile CSV");
+        println!("Profile saved to {:?}", output_path);
+    }
+
+    if let Some(baseline) = &baseline {
+        let regressions = profile.regressions(baseline, args.tolerance);
+        if regressions.is_empty() {
+            println!(
+                "No span regressed by more than {}% over the baseline",
+                args.tolerance
+            );
+            return;
+        }
+        for regression in &regressions {
+            eprintln!(
+                "Regression: hop {} -> {}, {}: {} cycles, baseline {} (+{:.1}%)",
+                regression.h1,
+                regression.h2,
+                regression.span,
+                regression.cycles,
+                regression.baseline,
+                100.0 * (regression.cycles as f64 / regression.baseline as f64 - 1.0)
+            );
+        }
+        std::process::exit(1);
+    }
+}
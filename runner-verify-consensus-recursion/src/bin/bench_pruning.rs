@@ -0,0 +1,144 @@
+use clap::Parser;
+use common::mmr::PathAccumulator;
+use consensus::encoding::encode_light_block;
+use runner_verify_consensus_recursion::{
+    hop::HopInput,
+    prover::{ProverMode, RecursionProver},
+    prune::prune_signatures,
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+use sp1_sdk::SP1VerifyingKey;
+use std::path::PathBuf;
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// The cycle tracker around consensus verification in the recursion program.
+const VERIFY_TRACKER: &str = "setup verifier and verify consensus";
+
+/// Compare the cycles of each hop of a headers directory with and without signature pruning
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the directory of header JSON files, one hop between each consecutive pair
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// Only run the first N hops
+    #[arg(short = 'n', long)]
+    hops: Option<usize>,
+}
+
+/// Total and consensus verification cycles of executing a hop that starts from genesis, so that
+/// it needs no previous proof.
+fn execute(
+    client: &RecursionProver,
+    vk: &SP1VerifyingKey,
+    h1: &LightBlock,
+    h2: &LightBlock,
+) -> (u64, u64) {
+    let (_public_values, report) = HopInput {
+        is_upgrade: false,
+        checkpoints: &[],
+        genesis_hash: h1.signed_header.header().hash().as_bytes(),
+        h1,
+        h2,
+        previous_path: &PathAccumulator::default(),
+        previous_proof: None,
+        previous_vk: None,
+    }
+    .execute(client, vk)
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let verify = report
+        .cycle_tracker
+        .get(VERIFY_TRACKER)
+        .copied()
+        .unwrap_or(0);
+    (report.total_instruction_count(), verify)
+}
+
+/// The size of a light block as the program reads it.
+fn encoded_len(block: &LightBlock) -> usize {
+    encode_light_block(block)
+        .expect("failed to encode h2")
+        .len()
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    if args.hops == Some(0) {
+        eprintln!("Error: --hops must be at least 1");
+        std::process::exit(1);
+    }
+    let headers = list_headers_dir(&args.headers_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if headers.len() < 2 {
+        eprintln!("Error: need at least two headers in {:?}", args.headers_dir);
+        std::process::exit(1);
+    }
+    let hops = args
+        .hops
+        .unwrap_or(headers.len() - 1)
+        .min(headers.len() - 1);
+
+    let client =
+        RecursionProver::new(ProverMode::Cpu, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    println!(
+        "{:>10} {:>10} {:>11} {:>13} {:>13} {:>13} {:>13} {:>10} {:>10}",
+        "h1", "h2", "signatures", "cycles", "pruned", "verify", "pruned", "bytes", "pruned"
+    );
+    let (mut total_full, mut total_pruned) = (0, 0);
+    for pair in headers.windows(2).take(hops) {
+        let read = |path| {
+            read_light_block_file(path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            })
+        };
+        let h1 = read(&pair[0].1);
+        let mut h2 = read(&pair[1].1);
+
+        let full_bytes = encoded_len(&h2);
+        let (full_cycles, full_verify) = execute(&client, &vk, &h1, &h2);
+
+        let signatures = h2.signed_header.commit.signatures.len();
+        let kept = prune_signatures(&mut h2, &h1);
+        let pruned_bytes = encoded_len(&h2);
+        let (pruned_cycles, pruned_verify) = execute(&client, &vk, &h1, &h2);
+
+        println!(
+            "{:>10} {:>10} {:>11} {:>13} {:>13} {:>13} {:>13} {:>10} {:>10}",
+            pair[0].0,
+            pair[1].0,
+            format!("{}/{}", kept, signatures),
+            full_cycles,
+            pruned_cycles,
+            full_verify,
+            pruned_verify,
+            full_bytes,
+            pruned_bytes
+        );
+        total_full += full_cycles;
+        total_pruned += pruned_cycles;
+    }
+
+    let saved = total_full.saturating_sub(total_pruned);
+    println!(
+        "{} hops: {} cycles without pruning, {} with ({:.1}% saved, {} per hop)",
+        hops,
+        total_full,
+        total_pruned,
+        100.0 * saved as f64 / total_full as f64,
+        saved / hops as u64
+    );
+}
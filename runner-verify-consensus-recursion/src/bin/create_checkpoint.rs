@@ -1,34 +1,8 @@
 use clap::Parser;
-use common::Groth16VkeyCheckpoint;
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint};
 use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, HashableKey};
 use std::fs;
-use serde::{Deserialize, Serialize};
-
-/// A buffer of serializable/deserializable objects.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Buffer {
-    pub data: Vec<u8>,
-    #[serde(skip)]
-    pub ptr: usize,
-}
-
-impl Buffer {
-    pub fn from(data: &[u8]) -> Self {
-        Self {
-            data: data.to_vec(),
-            ptr: 0,
-        }
-    }
-
-    /// Read the serializable object from the buffer.
-    pub fn read<T: Serialize + serde::de::DeserializeOwned>(&mut self) -> T {
-        let result: T =
-            bincode::deserialize(&self.data[self.ptr..]).expect("failed to deserialize");
-        let nb_bytes = bincode::serialized_size(&result).expect("failed to get serialized size");
-        self.ptr += nb_bytes as usize;
-        result
-    }
-}
+use runner_verify_consensus_recursion::public_values;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -96,31 +70,27 @@ fn main() {
     let (_pk, vk) = client.setup(&elf_bytes);
     let program_vk_hash = vk.vk.hash_u32();
 
-    // Extract block hash from previous proof's public values using Buffer
-    // Public values structure (in order):
-    // 1. checkpoints: Vec<Groth16VkeyCheckpoint>
-    // 2. genesis_hash: Vec<u8>
-    // 3. h2_hash: Vec<u8> (this is the block hash we want!)
-    // 4. vk_digest: [u32; 8]
-    let public_values = previous_proof.public_values.to_vec();
-    let mut public_values_buffer = Buffer::from(&public_values);
-
-    // Read and discard the checkpoints
-    let _previous_proof_checkpoints: Vec<Groth16VkeyCheckpoint> = public_values_buffer.read();
-
-    // Read and discard the genesis hash
-    let _previous_proof_genesis_hash: Vec<u8> = public_values_buffer.read();
+    // Decode the previous proof's public values, in either layout
+    let previous_values = public_values::decode(previous_proof.public_values.as_slice())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
 
-    // Read the h2 hash (block hash)
-    let previous_proof_h2_hash: Vec<u8> = public_values_buffer.read();
-
-    if previous_proof_h2_hash.len() != 32 {
-        eprintln!("Error: h2 hash is not 32 bytes, got {} bytes", previous_proof_h2_hash.len());
+    // The old checkpoints must be the history the previous proof commits to
+    if checkpoint_history_hash(&old_checkpoints) != previous_values.checkpoints_hash {
+        eprintln!("Error: old checkpoints do not match the previous proof's checkpoints hash");
         std::process::exit(1);
     }
 
-    let mut block_hash = [0u8; 32];
-    block_hash.copy_from_slice(&previous_proof_h2_hash);
+    // The new checkpoint is at the previous proof's head
+    let block_hash: [u8; 32] = match previous_values.h2_hash.as_slice().try_into() {
+        Ok(hash) => hash,
+        Err(_) => {
+            eprintln!("Error: h2 hash is not 32 bytes, got {} bytes", previous_values.h2_hash.len());
+            std::process::exit(1);
+        }
+    };
 
     // Create the new checkpoint
     let new_checkpoint = Groth16VkeyCheckpoint {
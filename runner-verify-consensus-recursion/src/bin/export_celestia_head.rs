@@ -0,0 +1,147 @@
+use clap::{Parser, ValueEnum};
+use runner_verify_consensus_recursion::{
+    bootstrap::{find_light_block_by_hash, trusted_state_bundle},
+    celestia::{
+        celestia_node_config, celestia_node_flags, DataAvailabilityHeader, ExtendedHeader,
+        TrustedHead,
+    },
+    prover::{ProverMode, RecursionProver},
+    verify::{read_genesis_hash, read_verified_proof},
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::read_light_block_file;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// `[Header]` section for celestia-node's config.toml
+    CelestiaNodeToml,
+    /// celestia-node command line flags
+    CelestiaNodeFlags,
+    /// Hash, height and time as JSON
+    Json,
+    /// The head as celestia-node ExtendedHeader JSON
+    ExtendedHeader,
+}
+
+/// Export the head proven by a recursion proof as a trusted head for Celestia light nodes
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the proof JSON file
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to directory containing header JSON files, searched for the proven head
+    #[arg(
+        short = 'd',
+        long,
+        value_name = "PATH",
+        required_unless_present = "head"
+    )]
+    headers_dir: Option<PathBuf>,
+
+    /// Path to the head header JSON file (instead of searching the headers directory)
+    #[arg(long, value_name = "PATH", conflicts_with = "headers_dir")]
+    head: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short = 'f', long, value_enum, default_value_t = Format::CelestiaNodeToml)]
+    format: Format,
+
+    /// Path to the head's data availability header JSON, included in the extended header
+    #[arg(long, value_name = "PATH")]
+    dah: Option<PathBuf>,
+
+    /// Path to write the output to (defaults to stdout)
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// The proof was generated by the mock prover
+    #[arg(short = 'm', long, default_value_t = false)]
+    mock: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    let groth16_vk_bytes = match &args.groth16_vkey {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading groth16 vkey file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => sp1_verifier::GROTH16_VK_BYTES.to_vec(),
+    };
+
+    let mode = if args.mock {
+        ProverMode::Mock
+    } else {
+        ProverMode::Cpu
+    };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    let (_proof, values) =
+        read_verified_proof(&args.proof, &client, &vk, &groth16_vk_bytes, &genesis_hash)
+            .unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+
+    let head = match (&args.head, &args.headers_dir) {
+        (Some(path), _) => read_light_block_file(path),
+        (None, Some(dir)) => find_light_block_by_hash(dir, &values.h2_hash),
+        (None, None) => unreachable!("clap requires one of --head or --headers-dir"),
+    }
+    .and_then(|head| trusted_state_bundle(&values, &genesis_hash, head))
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    })
+    .light_block;
+    let header = head.signed_header.header();
+
+    let output = match args.format {
+        Format::CelestiaNodeToml => celestia_node_config(header),
+        Format::CelestiaNodeFlags => celestia_node_flags(header),
+        Format::Json => serde_json::to_string_pretty(&TrustedHead::from(header))
+            .expect("failed to serialize trusted head"),
+        Format::ExtendedHeader => {
+            let dah: Option<DataAvailabilityHeader> = args.dah.as_ref().map(|path| {
+                let content = fs::read_to_string(path).unwrap_or_else(|e| {
+                    eprintln!("Error reading DAH file {:?}: {}", path, e);
+                    std::process::exit(1);
+                });
+                serde_json::from_str(&content).unwrap_or_else(|e| {
+                    eprintln!("Error deserializing DAH JSON: {}", e);
+                    std::process::exit(1);
+                })
+            });
+            let extended_header = ExtendedHeader::new(&head, dah).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            serde_json::to_string_pretty(&extended_header)
+                .expect("failed to serialize extended header")
+        }
+    };
+
+    match &args.output {
+        Some(path) => fs::write(path, output).expect("failed to write output"),
+        None => println!("{}", output),
+    }
+}
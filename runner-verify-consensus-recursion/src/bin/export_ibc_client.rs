@@ -0,0 +1,138 @@
+use clap::Parser;
+use prost::Message;
+use runner_verify_consensus_recursion::{
+    bootstrap::{find_light_block_by_hash, trusted_state_bundle},
+    ibc::{client_state, consensus_state, msg_create_client, ClientParams},
+    prover::{ProverMode, RecursionProver},
+    verify::{read_genesis_hash, read_verified_proof},
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::read_light_block_file;
+use std::fs;
+use std::path::PathBuf;
+
+/// Export an IBC 07-tendermint client for the head proven by a recursion proof
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the proof JSON file
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to directory containing header JSON files, searched for the proven head
+    #[arg(
+        short = 'd',
+        long,
+        value_name = "PATH",
+        required_unless_present = "head"
+    )]
+    headers_dir: Option<PathBuf>,
+
+    /// Path to the head header JSON file (instead of searching the headers directory)
+    #[arg(long, value_name = "PATH", conflicts_with = "headers_dir")]
+    head: Option<PathBuf>,
+
+    /// Directory to write client_state.bin, consensus_state.bin and msg_create_client.bin to
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output_dir: PathBuf,
+
+    /// Address of the account submitting MsgCreateClient on the counterparty chain
+    #[arg(short = 's', long, value_name = "ADDRESS")]
+    signer: String,
+
+    /// Unbonding period of the proven chain, in seconds (Celestia's is 21 days)
+    #[arg(long, value_name = "SECS", default_value_t = 21 * 24 * 60 * 60)]
+    unbonding_period: u64,
+
+    /// Maximum clock drift allowed between the chains, in seconds
+    #[arg(long, value_name = "SECS", default_value_t = 10)]
+    max_clock_drift: u64,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// The proof was generated by the mock prover
+    #[arg(short = 'm', long, default_value_t = false)]
+    mock: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    let groth16_vk_bytes = match &args.groth16_vkey {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading groth16 vkey file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => sp1_verifier::GROTH16_VK_BYTES.to_vec(),
+    };
+
+    let mode = if args.mock {
+        ProverMode::Mock
+    } else {
+        ProverMode::Cpu
+    };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    let (_proof, values) =
+        read_verified_proof(&args.proof, &client, &vk, &groth16_vk_bytes, &genesis_hash)
+            .unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+    println!(
+        "✓ Proof is valid, head hash: {}",
+        hex::encode_upper(&values.h2_hash)
+    );
+
+    let head = match (&args.head, &args.headers_dir) {
+        (Some(path), _) => read_light_block_file(path),
+        (None, Some(dir)) => find_light_block_by_hash(dir, &values.h2_hash),
+        (None, None) => unreachable!("clap requires one of --head or --headers-dir"),
+    }
+    .and_then(|head| trusted_state_bundle(&values, &genesis_hash, head))
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    })
+    .light_block;
+    let header = head.signed_header.header();
+
+    let params = ClientParams {
+        unbonding_period_secs: args.unbonding_period,
+        max_clock_drift_secs: args.max_clock_drift,
+    };
+    let client_state = client_state(header, &params).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let consensus_state = consensus_state(header);
+    let msg = msg_create_client(&client_state, &consensus_state, &args.signer);
+
+    fs::create_dir_all(&args.output_dir).expect("failed to create output directory");
+    for (name, bytes) in [
+        ("client_state.bin", client_state.encode_to_vec()),
+        ("consensus_state.bin", consensus_state.encode_to_vec()),
+        ("msg_create_client.bin", msg.encode_to_vec()),
+    ] {
+        fs::write(args.output_dir.join(name), bytes).expect("failed to write output");
+    }
+    println!(
+        "IBC client for {} at height {} saved to {:?}",
+        client_state.chain_id, header.height, args.output_dir
+    );
+}
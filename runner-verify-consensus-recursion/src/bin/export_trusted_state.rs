@@ -0,0 +1,97 @@
+use clap::Parser;
+use runner_verify_consensus_recursion::{
+    bootstrap::{find_light_block_by_hash, trusted_state_bundle},
+    prover::{ProverMode, RecursionProver},
+    verify::{read_genesis_hash, read_verified_proof},
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::read_light_block_file;
+use std::fs;
+use std::path::PathBuf;
+
+/// Export a light client trusted state for the head proven by a recursion proof
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the proof JSON file
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to directory containing header JSON files, searched for the proven head
+    #[arg(short = 'd', long, value_name = "PATH", required_unless_present = "head")]
+    headers_dir: Option<PathBuf>,
+
+    /// Path to the head header JSON file (instead of searching the headers directory)
+    #[arg(long, value_name = "PATH", conflicts_with = "headers_dir")]
+    head: Option<PathBuf>,
+
+    /// Path to write the trusted state JSON to
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output: PathBuf,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// The proof was generated by the mock prover
+    #[arg(short = 'm', long, default_value_t = false)]
+    mock: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    let groth16_vk_bytes = match &args.groth16_vkey {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading groth16 vkey file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => sp1_verifier::GROTH16_VK_BYTES.to_vec(),
+    };
+
+    let mode = if args.mock { ProverMode::Mock } else { ProverMode::Cpu };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    let (_proof, values) =
+        read_verified_proof(&args.proof, &client, &vk, &groth16_vk_bytes, &genesis_hash)
+            .unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+    println!("✓ Proof is valid, head hash: {}", hex::encode_upper(&values.h2_hash));
+
+    let head = match (&args.head, &args.headers_dir) {
+        (Some(path), _) => read_light_block_file(path),
+        (None, Some(dir)) => find_light_block_by_hash(dir, &values.h2_hash),
+        (None, None) => unreachable!("clap requires one of --head or --headers-dir"),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let bundle = trusted_state_bundle(&values, &genesis_hash, head).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let json = serde_json::to_string_pretty(&bundle).expect("failed to serialize trusted state");
+    fs::write(&args.output, json).expect("failed to write trusted state to output location");
+    println!(
+        "Trusted state at height {} saved to {:?}",
+        bundle.trusted_height, args.output
+    );
+}
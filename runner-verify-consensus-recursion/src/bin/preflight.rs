@@ -0,0 +1,137 @@
+use clap::Parser;
+use common::Groth16VkeyCheckpoint;
+use runner_verify_consensus_recursion::{
+    hop::execute_path,
+    preflight::proving_cost,
+    prover::{ProverMode, RecursionProver},
+};
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+use std::fs;
+use std::path::PathBuf;
+
+/// Execute every hop of a headers directory before proving any of them, reporting the hops that
+/// fail and what proving each one would cost
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the directory of header JSON files, starting at genesis
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// The upgrade history to prove the path with, as for `run_program`
+    #[arg(short = 'u', long, value_name = "PATH")]
+    upgrade_history: Option<PathBuf>,
+
+    /// Only run the first N hops
+    #[arg(short = 'n', long)]
+    hops: Option<usize>,
+
+    /// Send every signature of each h2's commit, as `run_program --keep-all-signatures` does
+    #[arg(long, default_value_t = false)]
+    keep_all_signatures: bool,
+
+    /// Price of a billion prover gas units, to estimate the cost of proving each hop
+    #[arg(long, value_name = "PRICE")]
+    gas_price: Option<f64>,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    let headers = list_headers_dir(&args.headers_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if headers.len() < 2 {
+        eprintln!("Error: need at least two headers in {:?}", args.headers_dir);
+        std::process::exit(1);
+    }
+    let hops = args
+        .hops
+        .unwrap_or(headers.len() - 1)
+        .min(headers.len() - 1);
+
+    let upgrade_history: Vec<Groth16VkeyCheckpoint> = match &args.upgrade_history {
+        Some(path) => {
+            let content = fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Error reading upgrade history file: {}", e);
+                std::process::exit(1);
+            });
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("Error deserializing upgrade history JSON: {}", e);
+                std::process::exit(1);
+            })
+        }
+        None => Vec::new(),
+    };
+
+    let client =
+        RecursionProver::new(ProverMode::Mock, None).expect("local provers need no configuration");
+    let blocks = headers.iter().take(hops + 1).map(|(_, path)| {
+        read_light_block_file(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    println!(
+        "{:>10} {:>10} {:>13} {:>13} {:>10}  result",
+        "h1", "h2", "cycles", "gas", "cost"
+    );
+    let mut failures = Vec::new();
+    let (mut total_cycles, mut total_gas) = (0, 0);
+    execute_path(
+        &client,
+        &upgrade_history,
+        blocks,
+        args.keep_all_signatures,
+        |hop| {
+            let (h1, h2) = (hop.h1.height().value(), hop.h2.height().value());
+            // A hop that fails is reported, and the hops after it run as if it had been proven.
+            match hop.result {
+                Ok((_, report)) => {
+                    let cycles = report.total_instruction_count();
+                    let gas = report.gas.unwrap_or(0);
+                    let cost = match args.gas_price {
+                        Some(price) => format!("{:.4}", proving_cost(gas, price)),
+                        None => "-".to_string(),
+                    };
+                    println!(
+                        "{:>10} {:>10} {:>13} {:>13} {:>10}  ok",
+                        h1, h2, cycles, gas, cost
+                    );
+                    total_cycles += cycles;
+                    total_gas += gas;
+                }
+                Err(e) => {
+                    println!(
+                        "{:>10} {:>10} {:>13} {:>13} {:>10}  {}",
+                        h1, h2, "-", "-", "-", e
+                    );
+                    failures.push((h1, h2, e));
+                }
+            }
+        },
+    );
+
+    match args.gas_price {
+        Some(price) => println!(
+            "{} hops: {} cycles, {} gas, estimated cost {:.4}",
+            hops,
+            total_cycles,
+            total_gas,
+            proving_cost(total_gas, price)
+        ),
+        None => println!("{} hops: {} cycles, {} gas", hops, total_cycles, total_gas),
+    }
+    if !failures.is_empty() {
+        for (h1, h2, e) in &failures {
+            eprintln!("Error: hop {} -> {} failed: {}", h1, h2, e);
+        }
+        std::process::exit(1);
+    }
+    println!("Every hop executed, ready to prove");
+}
@@ -0,0 +1,189 @@
+use clap::Parser;
+use consensus::head::linked_header;
+use runner_verify_consensus_recursion::{
+    ancestors::ancestor_header_stdin,
+    bootstrap::{find_light_block_by_hash, trusted_state_bundle},
+    prover::{ProverMode, RecursionProver},
+    public_values::decode_ancestor_header,
+    verify::{read_genesis_hash, read_header_link, read_verified_proof},
+    ANCESTOR_HEADER_ELF, CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::read_light_block_file;
+use std::fs;
+use std::path::PathBuf;
+
+/// Prove a header below the head proven by a recursion proof, by hash chaining back to it
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the recursion proof JSON file (a compressed proof)
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to directory containing header JSON files, searched for the proven head
+    #[arg(
+        short = 'd',
+        long,
+        value_name = "PATH",
+        required_unless_present = "head"
+    )]
+    headers_dir: Option<PathBuf>,
+
+    /// Path to the head header JSON file (instead of searching the headers directory)
+    #[arg(long, value_name = "PATH", conflicts_with = "headers_dir")]
+    head: Option<PathBuf>,
+
+    /// Path to the ancestor headers JSON file written by fetch_ancestors, newest first
+    #[arg(short = 'a', long, value_name = "PATH")]
+    ancestors: PathBuf,
+
+    /// Height of a header verified on the way to the head to walk back from, rather than the
+    /// head (needs the headers directory the proof was generated from)
+    #[arg(long, value_name = "HEIGHT", requires = "headers_dir")]
+    path_height: Option<u64>,
+
+    /// Where to generate proofs: mock, cpu, cuda or network
+    #[arg(long, value_enum, env = "SP1_PROVER", default_value_t = ProverMode::Network)]
+    prover: ProverMode,
+
+    /// Private key for the network prover (only required with `--prover network`)
+    #[arg(
+        short = 'k',
+        long,
+        value_name = "PRIVATE_KEY",
+        env = "NETWORK_PRIVATE_KEY"
+    )]
+    private_key: Option<String>,
+
+    /// Path to output proof file
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output_proof: PathBuf,
+
+    /// dry run mode
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Wrap the proof in groth16, for verification on other chains
+    #[arg(short = 'r', long, default_value_t = false)]
+    groth16: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    // A dry run only executes the program, so it never needs the network.
+    let prover_mode = if args.dry_run && args.prover == ProverMode::Network {
+        ProverMode::Cpu
+    } else {
+        args.prover
+    };
+    let client =
+        RecursionProver::new(prover_mode, args.private_key.as_deref()).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+
+    // Check every input on the host first, so that bad inputs fail fast.
+    let (_recursion_pk, recursion_vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    let (recursion_proof, values) = read_verified_proof(
+        &args.proof,
+        &client,
+        &recursion_vk,
+        &sp1_verifier::GROTH16_VK_BYTES,
+        &genesis_hash,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let head = match (&args.head, &args.headers_dir) {
+        (Some(path), _) => read_light_block_file(path),
+        (None, Some(dir)) => find_light_block_by_hash(dir, &values.h2_hash),
+        (None, None) => unreachable!("clap requires one of --head or --headers-dir"),
+    }
+    .and_then(|head| trusted_state_bundle(&values, &genesis_hash, head))
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    })
+    .light_block;
+    let header = head.signed_header.header();
+
+    let (link, linked) = read_header_link(
+        &values,
+        args.headers_dir.as_deref(),
+        Some(&args.ancestors),
+        args.path_height,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let target = linked_header(header, &values.path_root, &link, &linked).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if target.data_hash.is_none() {
+        eprintln!("Error: target header has no data hash");
+        std::process::exit(1);
+    }
+    let start = linked
+        .first()
+        .filter(|_| args.path_height.is_some())
+        .unwrap_or(header);
+    println!(
+        "✓ Header {} ({}) chains back from the header at height {}, linked to the head at height {}",
+        target.height,
+        target.hash(),
+        start.height,
+        header.height
+    );
+
+    let stdin = ancestor_header_stdin(
+        &recursion_proof,
+        &recursion_vk,
+        header,
+        &link,
+        &linked,
+        client.is_mock(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    if args.dry_run {
+        let (public_values, execution_report) = client
+            .execute(ANCESTOR_HEADER_ELF, &stdin)
+            .expect("failed to execute program");
+        let committed = decode_ancestor_header(public_values.as_slice())
+            .expect("failed to decode public values");
+        println!("Committed: {:?}", committed);
+        println!("Execution report: {:?}", execution_report);
+        return;
+    }
+
+    let (pk, _vk) = client.setup(ANCESTOR_HEADER_ELF);
+    let proof = client
+        .prove(&pk, &stdin, args.groth16)
+        .expect("failed to generate proof");
+    let proof_json =
+        serde_json::to_string_pretty(&proof).expect("failed to serialize proof as JSON");
+    fs::write(&args.output_proof, &proof_json)
+        .expect("failed to write proof JSON to output location");
+    println!("Proof successfully saved to {:?}", args.output_proof);
+}
@@ -0,0 +1,209 @@
+use clap::Parser;
+use consensus::head::linked_header;
+use runner_verify_consensus_recursion::{
+    blob::{blob_inclusion_stdin, read_blob, read_share_proof},
+    bootstrap::{find_light_block_by_hash, trusted_state_bundle},
+    prover::{ProverMode, RecursionProver},
+    public_values::decode_blob_inclusion,
+    verify::{read_genesis_hash, read_header_link, read_verified_proof},
+    BLOB_INCLUSION_ELF, CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::headers_dir::read_light_block_file;
+use std::fs;
+use std::path::PathBuf;
+
+/// Prove that a blob was posted in the head proven by a recursion proof, or a block linked to it
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the recursion proof JSON file (a compressed proof)
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to directory containing header JSON files, searched for the proven head
+    #[arg(
+        short = 'd',
+        long,
+        value_name = "PATH",
+        required_unless_present = "head"
+    )]
+    headers_dir: Option<PathBuf>,
+
+    /// Path to the head header JSON file (instead of searching the headers directory)
+    #[arg(long, value_name = "PATH", conflicts_with = "headers_dir")]
+    head: Option<PathBuf>,
+
+    /// Path to the ancestor headers JSON file written by fetch_ancestors, if the blob is in the
+    /// last of them rather than in the head (or the path header, with --path-height)
+    #[arg(short = 'a', long, value_name = "PATH")]
+    ancestors: Option<PathBuf>,
+
+    /// Height of the block with the blob, or of the header its ancestors start from, if it was
+    /// verified on the way to the head (needs the headers directory the proof was generated from)
+    #[arg(long, value_name = "HEIGHT", requires = "headers_dir")]
+    path_height: Option<u64>,
+
+    /// Path to the blob JSON file, as returned by celestia-node's blob.Get
+    #[arg(short = 'b', long, value_name = "PATH")]
+    blob: PathBuf,
+
+    /// Path to the share proof JSON file, as returned by celestia-core's prove_shares_v2
+    #[arg(short = 's', long, value_name = "PATH")]
+    share_proof: PathBuf,
+
+    /// Where to generate proofs: mock, cpu, cuda or network
+    #[arg(long, value_enum, env = "SP1_PROVER", default_value_t = ProverMode::Network)]
+    prover: ProverMode,
+
+    /// Private key for the network prover (only required with `--prover network`)
+    #[arg(
+        short = 'k',
+        long,
+        value_name = "PRIVATE_KEY",
+        env = "NETWORK_PRIVATE_KEY"
+    )]
+    private_key: Option<String>,
+
+    /// Path to output proof file
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output_proof: PathBuf,
+
+    /// dry run mode
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Wrap the proof in groth16, for verification on other chains
+    #[arg(short = 'r', long, default_value_t = false)]
+    groth16: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    // A dry run only executes the program, so it never needs the network.
+    let prover_mode = if args.dry_run && args.prover == ProverMode::Network {
+        ProverMode::Cpu
+    } else {
+        args.prover
+    };
+    let client =
+        RecursionProver::new(prover_mode, args.private_key.as_deref()).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+
+    // Check every input on the host first, so that bad inputs fail fast.
+    let (_recursion_pk, recursion_vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    let (recursion_proof, values) = read_verified_proof(
+        &args.proof,
+        &client,
+        &recursion_vk,
+        &sp1_verifier::GROTH16_VK_BYTES,
+        &genesis_hash,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let head = match (&args.head, &args.headers_dir) {
+        (Some(path), _) => read_light_block_file(path),
+        (None, Some(dir)) => find_light_block_by_hash(dir, &values.h2_hash),
+        (None, None) => unreachable!("clap requires one of --head or --headers-dir"),
+    }
+    .and_then(|head| trusted_state_bundle(&values, &genesis_hash, head))
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    })
+    .light_block;
+    let header = head.signed_header.header();
+
+    let (link, linked) = read_header_link(
+        &values,
+        args.headers_dir.as_deref(),
+        args.ancestors.as_deref(),
+        args.path_height,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let block = linked_header(header, &values.path_root, &link, &linked).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let data_root: [u8; 32] = block
+        .data_hash
+        .and_then(|hash| hash.as_bytes().try_into().ok())
+        .unwrap_or_else(|| {
+            eprintln!("Error: block {} has no data hash", block.height);
+            std::process::exit(1);
+        });
+
+    let blob = read_blob(&args.blob).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let share_proof = read_share_proof(&args.share_proof, &blob).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if let Err(e) = inclusion::verify_blob_inclusion(&blob, &share_proof, &data_root) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    println!(
+        "✓ Blob is included at height {}, linked to the head at height {}",
+        block.height, header.height
+    );
+
+    let stdin = blob_inclusion_stdin(
+        &recursion_proof,
+        &recursion_vk,
+        header,
+        &link,
+        &linked,
+        &blob,
+        &share_proof,
+        client.is_mock(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    if args.dry_run {
+        let (public_values, execution_report) = client
+            .execute(BLOB_INCLUSION_ELF, &stdin)
+            .expect("failed to execute program");
+        let committed = decode_blob_inclusion(public_values.as_slice())
+            .expect("failed to decode public values");
+        println!("Committed: {:?}", committed);
+        println!("Execution report: {:?}", execution_report);
+        return;
+    }
+
+    let (pk, _vk) = client.setup(BLOB_INCLUSION_ELF);
+    let proof = client
+        .prove(&pk, &stdin, args.groth16)
+        .expect("failed to generate proof");
+    let proof_json =
+        serde_json::to_string_pretty(&proof).expect("failed to serialize proof as JSON");
+    fs::write(&args.output_proof, &proof_json)
+        .expect("failed to write proof JSON to output location");
+    println!("Proof successfully saved to {:?}", args.output_proof);
+}
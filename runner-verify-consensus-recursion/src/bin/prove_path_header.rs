@@ -0,0 +1,102 @@
+use clap::Parser;
+use runner_verify_consensus_recursion::{
+    path::{prove_path_header, read_path_leaves},
+    prover::{ProverMode, RecursionProver},
+    verify::{read_genesis_hash, read_verified_proof},
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use std::fs;
+use std::path::PathBuf;
+
+/// Prove that a header was verified on the way to the head of a recursion proof
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the proof JSON file
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Genesis the proof must start from: its hash in hex, or the path of its header JSON file
+    #[arg(short = 'g', long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to the directory of header JSON files the proof was generated from
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// Height of the header to prove
+    #[arg(long)]
+    height: u64,
+
+    /// Path to write the inclusion proof JSON to
+    #[arg(short = 'o', long, value_name = "PATH")]
+    output: PathBuf,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// The proof was generated by the mock prover
+    #[arg(short = 'm', long, default_value_t = false)]
+    mock: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: --genesis: {}", e);
+        std::process::exit(1);
+    });
+
+    let groth16_vk_bytes = match &args.groth16_vkey {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading groth16 vkey file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => sp1_verifier::GROTH16_VK_BYTES.to_vec(),
+    };
+
+    let mode = if args.mock {
+        ProverMode::Mock
+    } else {
+        ProverMode::Cpu
+    };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    let (_proof, values) =
+        read_verified_proof(&args.proof, &client, &vk, &groth16_vk_bytes, &genesis_hash)
+            .unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+    println!(
+        "✓ Proof is valid, path root: {}",
+        hex::encode_upper(values.path_root)
+    );
+
+    let leaves = read_path_leaves(&args.headers_dir, &values).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let inclusion = prove_path_header(&leaves, args.height, values.path_root).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    assert!(inclusion.verify(), "inclusion proof must verify");
+
+    let json = serde_json::to_string_pretty(&inclusion).expect("failed to serialize proof");
+    fs::write(&args.output, json).expect("failed to write proof to output location");
+    println!(
+        "Header {} at height {} is hop {} of {}, proof saved to {:?}",
+        hex::encode_upper(inclusion.leaf.hash),
+        inclusion.leaf.height,
+        inclusion.proof.index + 1,
+        inclusion.proof.leaves,
+        args.output
+    );
+}
@@ -1,15 +1,18 @@
 use clap::Parser;
 use sp1_verifier;
-use sp1_sdk::{include_elf, ProverClient, SP1Stdin, SP1ProofWithPublicValues, Prover, HashableKey, SP1Proof,
-    network::{FulfillmentStrategy, NetworkMode},
-};
+use sp1_sdk::SP1ProofWithPublicValues;
 use std::fs;
 use std::path::PathBuf;
 use tendermint_light_client_verifier::types::LightBlock;
-use common::Groth16VkeyCheckpoint;
-
-pub const CONSENSUS_VERIFIER_RECURSION_ELF: &[u8] =
-    include_elf!("program-verify-consensus-recursion");
+use common::{mmr::PathAccumulator, Groth16VkeyCheckpoint};
+use runner_verify_consensus_recursion::{
+    hop::HopInput,
+    path::{path_leaf, read_path_accumulator},
+    prune::prune_signatures,
+    prover::{ProverMode, RecursionProver},
+    verify::{check_previous_values, check_upgrade_values, read_verified_proof},
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
 
 /// Run program with header JSON files
 #[derive(Parser, Debug)]
@@ -30,18 +33,39 @@ struct Args {
     #[arg(long, value_name = "PATH")]
     h2: PathBuf,
 
-    /// Private key for the prover client
-    #[arg(short = 'k', long, value_name = "PRIVATE_KEY")]
-    private_key: String,
+    /// Where to generate proofs: mock, cpu, cuda or network
+    #[arg(long, value_enum, env = "SP1_PROVER", default_value_t = ProverMode::Network)]
+    prover: ProverMode,
+
+    /// Private key for the network prover (only required with `--prover network`)
+    #[arg(short = 'k', long, value_name = "PRIVATE_KEY", env = "NETWORK_PRIVATE_KEY")]
+    private_key: Option<String>,
 
     /// previous proof file
     #[arg(short = 'p', long, value_name = "PATH")]
     previous_proof: Option<PathBuf>,
 
+    /// The previous proof is of the older program named by the last checkpoint of the upgrade
+    /// history, which is at h1
+    #[arg(long, default_value_t = false, requires = "previous_elf")]
+    upgrade: bool,
+
+    /// Path to the ELF of the older program, to verify the previous proof of an upgrade against
+    #[arg(long, value_name = "ELF", requires = "upgrade")]
+    previous_elf: Option<PathBuf>,
+
+    /// Path accumulator of the heads verified before this hop, as written with the previous proof
+    #[arg(short = 'a', long, value_name = "PATH")]
+    path_accumulator: Option<PathBuf>,
+
     /// Path to output proof file
     #[arg(short = 'o', long, value_name = "PATH")]
     output_proof: PathBuf,
 
+    /// Where to write the path accumulator including this hop, for proving the next one
+    #[arg(long, value_name = "PATH")]
+    output_path_accumulator: Option<PathBuf>,
+
     /// dry run mode
     #[arg(short = 'd', long, default_value_t = false)]
     dry_run: bool,
@@ -49,11 +73,16 @@ struct Args {
     /// Use groth16: useful for upgrades, especially upgrading SP1 versions, or different zkVMs.
     #[arg(short = 'r', long, default_value_t = false)]
     groth16: bool,
+
+    /// Send every signature of h2's commit, instead of only those verification needs
+    #[arg(long, default_value_t = false)]
+    keep_all_signatures: bool,
 }
 
 fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
 
     let groth16_vk_bytes = sp1_verifier::GROTH16_VK_BYTES.clone();
     println!("Groth16 VK bytes length: {:?}", groth16_vk_bytes.len());
@@ -103,7 +132,7 @@ fn main() {
             eprintln!("Error reading h2 file: {}", e);
             std::process::exit(1);
         });
-    let h2: LightBlock = serde_json::from_str(&content)
+    let mut h2: LightBlock = serde_json::from_str(&content)
         .unwrap_or_else(|e| {
             eprintln!("Error deserializing h2 JSON: {}", e);
             std::process::exit(1);
@@ -132,94 +161,114 @@ fn main() {
     let h1_hash = h1.signed_header.header().hash();
     let h1_is_genesis = genesis_hash == h1_hash;
 
-    let mut previous_proof: Option<SP1ProofWithPublicValues> = None;
-    if !h1_is_genesis {
-        if args.previous_proof.is_none() {
+    // Setup the prover client. A dry run only executes the program, so it never needs the network.
+    let prover_mode = if args.dry_run && args.prover == ProverMode::Network {
+        ProverMode::Cpu
+    } else {
+        args.prover
+    };
+    let client = RecursionProver::new(prover_mode, args.private_key.as_deref())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+
+    let (pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    let previous_vk = args.previous_elf.as_ref().map(|path| {
+        let elf = fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading previous ELF file {:?}: {}", path, e);
+            std::process::exit(1);
+        });
+        client.setup(&elf).1
+    });
+
+    // The program checks the previous proof, its public values and the path accumulator, so check
+    // them here first rather than fail inside the prover.
+    let (previous_proof, previous_values) = if h1_is_genesis {
+        (None, None)
+    } else {
+        let Some(previous_proof_path) = &args.previous_proof else {
             eprintln!("Error: previous_proof is required when h1 is not the same as genesis");
             std::process::exit(1);
+        };
+        let (previous_proof, values) = read_verified_proof(
+            previous_proof_path,
+            &client,
+            previous_vk.as_ref().unwrap_or(&vk),
+            &sp1_verifier::GROTH16_VK_BYTES,
+            genesis_hash.as_bytes(),
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error: previous proof: {}", e);
+            std::process::exit(1);
+        });
+        let check = if args.upgrade { check_upgrade_values } else { check_previous_values };
+        check(
+            &values,
+            genesis_hash.as_bytes(),
+            h1_hash.as_bytes(),
+            &upgrade_history,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        (Some(previous_proof), Some(values))
+    };
+    let previous_path = match (&previous_values, &args.path_accumulator) {
+        (None, _) => PathAccumulator::default(),
+        // A proof of the baseline layout committed no path, so the accumulator starts over.
+        (Some(values), None)
+            if args.upgrade && values.path_root == PathAccumulator::default().root() =>
+        {
+            PathAccumulator::default()
         }
-        let previous_proof_path = args.previous_proof.as_ref().unwrap();
-        if !previous_proof_path.exists() {
-            eprintln!("Error: previous_proof file does not exist: {:?}", previous_proof_path);
+        (Some(_), None) => {
+            eprintln!("Error: path_accumulator is required when h1 is not the same as genesis");
             std::process::exit(1);
         }
-        let previous_proof_content = fs::read_to_string(previous_proof_path).unwrap();
-        previous_proof = Some(serde_json::from_str(&previous_proof_content).unwrap());
-    }
-
-    // Setup the prover client.
-    let client = ProverClient::builder()
-        .network_for(NetworkMode::Mainnet)
-        .private_key(&args.private_key)
-        .build();
-
-    let (pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
-
-    let mut stdin = SP1Stdin::new();
-
-    // Write is_upgrade flag
-    stdin.write(&false);
-
-    // Write checkpoints
-    stdin.write(&upgrade_history);
-
-    // Write genesis hash
-    stdin.write_vec(genesis.signed_header.header().hash().as_bytes().to_vec());
-
-    // Write h1
-    let h1_bytes = serde_cbor::to_vec(&h1).unwrap();
-    stdin.write_vec(h1_bytes);
-
-    // Write h2
-    let h2_bytes = serde_cbor::to_vec(&h2).unwrap();
-    stdin.write_vec(h2_bytes);
-
-    // Write vk digest
-    stdin.write(&vk.vk.hash_u32());
-
-    if let Some(previous_proof) = previous_proof {
-        // Check proof type and write groth16 proof if applicable
-        match &previous_proof.proof {
-            SP1Proof::Compressed(compressed_stark_proof) => {
-                stdin.write(&Vec::<u8>::new());
-                stdin.write_proof(compressed_stark_proof.as_ref().clone(), vk.vk.clone());
-            },
-            SP1Proof::Groth16(groth16_proof) => {
-                stdin.write(&groth16_proof.raw_proof.as_bytes().to_vec());
-            },
-            _ => {
-                panic!("Unsupported proof type");
+        (Some(values), Some(path)) => {
+            let accumulator = read_path_accumulator(path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            if accumulator.root() != values.path_root {
+                eprintln!("Error: path accumulator does not match the previous proof's path root");
+                std::process::exit(1);
             }
+            accumulator
         }
+    };
+    let mut path = previous_path.clone();
+    path.push(path_leaf(h2.signed_header.header()).digest());
 
-        // write pv digest
-        stdin.write(&previous_proof.public_values.hash());
-
-        // In the old version i use write instead of write_vec
-        // don't remember why.
-        stdin.write(&previous_proof.public_values.to_vec());
+    if !args.keep_all_signatures {
+        let total = h2.signed_header.commit.signatures.len();
+        let kept = prune_signatures(&mut h2, &h1);
+        println!("Kept {} of {} h2 commit signatures", kept, total);
     }
 
+    let stdin = HopInput {
+        is_upgrade: args.upgrade,
+        checkpoints: &upgrade_history,
+        genesis_hash: genesis_hash.as_bytes(),
+        h1: &h1,
+        h2: &h2,
+        previous_path: &previous_path,
+        previous_proof: previous_proof.as_ref(),
+        previous_vk: previous_vk.as_ref(),
+    }
+    .stdin(&vk)
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
 
     if !args.dry_run {
 
-        let proof: SP1ProofWithPublicValues;
-
-        if args.groth16 {
-            proof = client
-                .prove(&pk, &stdin)
-                .strategy(FulfillmentStrategy::Auction)
-                .groth16()
-                .run()
-                .expect("failed to generate proof");
-        } else {
-            proof = client
-                .prove(&pk, &stdin)
-                .strategy(FulfillmentStrategy::Auction)
-                .compressed()
-                .run()
-                .expect("failed to generate proof");
-        }
+        let proof: SP1ProofWithPublicValues = client
+            .prove(&pk, &stdin, args.groth16)
+            .expect("failed to generate proof");
 
         // Save proof to output location as JSON
         let output_path = &args.output_proof;
@@ -230,10 +279,15 @@ fn main() {
     } else {
         let result = client
             .execute(CONSENSUS_VERIFIER_RECURSION_ELF, &stdin)
-            .run()
             .expect("failed to execute program");
 
         let (_public_values, execution_report) = result;
         println!("Execution report: {:?}", execution_report);
     }
+
+    if let Some(output_path) = &args.output_path_accumulator {
+        let path_json = serde_json::to_string_pretty(&path).expect("failed to serialize path accumulator");
+        fs::write(output_path, &path_json).expect("failed to write path accumulator to output location");
+        println!("Path accumulator ({} heads) saved to {:?}", path.leaves, output_path);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use runner_verify_consensus_recursion::{
+    proof_api::{router, watch},
+    proof_store::{ProgramVerifier, ProofStore, StoreConfig},
+    prover::{ProverMode, RecursionProver},
+    verify::read_genesis_hash,
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+
+/// Serve the recursion proofs of a proof store over HTTP: the latest proof or one by head hash,
+/// its public values and head, and the upgrade history, as JSON or binary
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the store, with proofs/, headers/ and optionally checkpoints.json
+    #[arg(short, long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Genesis every proof must start from: its hash (hex) or the path of its header JSON file
+    #[arg(short, long, value_name = "HASH|PATH")]
+    genesis: String,
+
+    /// Path to the program ELF the proofs are of (defaults to the program built with this binary)
+    #[arg(short, long, value_name = "ELF")]
+    elf: Option<PathBuf>,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// The proofs were generated by the mock prover
+    #[arg(short, long, default_value_t = false)]
+    mock: bool,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// How often to look for new proofs, in seconds
+    #[arg(long, default_value_t = 10)]
+    refresh_secs: u64,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let genesis_hash = read_genesis_hash(&args.genesis).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let read = |path: &PathBuf| {
+        fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error: failed to read {:?}: {}", path, e);
+            std::process::exit(1);
+        })
+    };
+    let elf = args
+        .elf
+        .as_ref()
+        .map_or_else(|| CONSENSUS_VERIFIER_RECURSION_ELF.to_vec(), read);
+    let groth16_vk = args
+        .groth16_vkey
+        .as_ref()
+        .map_or_else(|| sp1_verifier::GROTH16_VK_BYTES.to_vec(), read);
+    let mode = if args.mock {
+        ProverMode::Mock
+    } else {
+        ProverMode::Cpu
+    };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(&elf);
+    let config = StoreConfig {
+        genesis_hash,
+        verifier: Box::new(ProgramVerifier {
+            client,
+            vk,
+            groth16_vk,
+        }),
+    };
+
+    let store = ProofStore::open(args.store.clone(), config).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let index = store.index();
+    for e in &index.errors {
+        eprintln!("Error: {}", e);
+    }
+    let store = Arc::new(store);
+    tokio::spawn(watch(
+        store.clone(),
+        Duration::from_secs(args.refresh_secs.max(1)),
+    ));
+
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Error: failed to listen on {}: {}", args.listen, e);
+            std::process::exit(1);
+        });
+    println!(
+        "Serving {} proofs from {:?} at http://{}",
+        index.proofs.len(),
+        args.store,
+        args.listen
+    );
+    if let Err(e) = axum::serve(listener, router(store)).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,183 @@
+use clap::Parser;
+use runner_verify_consensus_recursion::{
+    checkpoints::{read_checkpoints, replay_checkpoint_history},
+    prover::{ProverMode, RecursionProver},
+    public_values::{self, parse_hex},
+    verify::verify_recursion_proof,
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use sp1_sdk::{HashableKey, SP1ProofWithPublicValues};
+use std::fs;
+use std::path::PathBuf;
+use verifier::program_vk_hash_hex;
+
+/// Verify a recursion proof and print what it attests
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the proof JSON file
+    #[arg(short = 'p', long, value_name = "PATH")]
+    proof: PathBuf,
+
+    /// Path to the program ELF (defaults to the program built with this binary)
+    #[arg(short = 'e', long, value_name = "ELF")]
+    elf: Option<PathBuf>,
+
+    /// Path to the groth16 vkey to check wrapped proofs against (defaults to the current SP1 one)
+    #[arg(short = 'v', long, value_name = "VKEY")]
+    groth16_vkey: Option<PathBuf>,
+
+    /// Fail unless the proof commits to this genesis hash (hex)
+    #[arg(short = 'g', long, value_name = "HASH")]
+    expected_genesis: Option<String>,
+
+    /// Fail unless the committed vk digest is one of these (bytes32 hex, as printed by this tool)
+    #[arg(short = 'a', long = "allowed-vk", value_name = "DIGEST")]
+    allowed_vks: Vec<String>,
+
+    /// Path to the checkpoint history JSON file, checked against the committed checkpoints hash
+    #[arg(short = 'c', long, value_name = "PATH")]
+    checkpoints: Option<PathBuf>,
+
+    /// The proof was generated by the mock prover
+    #[arg(short = 'm', long, default_value_t = false)]
+    mock: bool,
+}
+
+fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    let args = Args::parse();
+
+    if !args.proof.exists() {
+        eprintln!("Error: proof file does not exist: {:?}", args.proof);
+        std::process::exit(1);
+    }
+    let content = fs::read_to_string(&args.proof).unwrap_or_else(|e| {
+        eprintln!("Error reading proof file: {}", e);
+        std::process::exit(1);
+    });
+    let proof: SP1ProofWithPublicValues = serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Error deserializing proof JSON: {}", e);
+        std::process::exit(1);
+    });
+
+    let elf_bytes = match &args.elf {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading ELF file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => CONSENSUS_VERIFIER_RECURSION_ELF.to_vec(),
+    };
+
+    let mode = if args.mock { ProverMode::Mock } else { ProverMode::Cpu };
+    let client = RecursionProver::new(mode, None).expect("local provers need no configuration");
+    let (_pk, vk) = client.setup(&elf_bytes);
+    println!("Program vk digest: {}", vk.bytes32());
+
+    // Verify the proof itself.
+    let groth16_vk_bytes = match &args.groth16_vkey {
+        Some(path) => fs::read(path).unwrap_or_else(|e| {
+            eprintln!("Error reading groth16 vkey file {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => sp1_verifier::GROTH16_VK_BYTES.to_vec(),
+    };
+    let verification = verify_recursion_proof(&client, &proof, &vk, &groth16_vk_bytes);
+    if let Err(e) = verification {
+        eprintln!("Error: proof verification failed: {}", e);
+        std::process::exit(1);
+    }
+    println!("✓ Proof is valid");
+
+    // Decode what the proof attests.
+    let values = public_values::decode(proof.public_values.as_slice()).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    println!("Genesis hash: {}", hex::encode_upper(&values.genesis_hash));
+    println!("Head hash: {}", hex::encode_upper(&values.h2_hash));
+    println!("Vk digest: {}", program_vk_hash_hex(&values.vk_digest));
+    println!("Path root: {}", hex::encode_upper(values.path_root));
+    println!(
+        "Checkpoints hash: {}",
+        hex::encode_upper(values.checkpoints_hash)
+    );
+
+    // Check the public values against what the caller expects.
+    let mut failures = Vec::new();
+
+    // The proof only commits the hash of its checkpoints, so rebuild it from the history.
+    if let Some(path) = &args.checkpoints {
+        let checkpoints = read_checkpoints(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        let hashes = replay_checkpoint_history(&checkpoints);
+        println!("Checkpoints: {}", checkpoints.len());
+        for (i, (checkpoint, hash)) in checkpoints.iter().zip(&hashes).enumerate() {
+            println!(
+                "  [{}] block {} program vk {} groth16 vk: {} history hash {}",
+                i,
+                hex::encode_upper(checkpoint.block_hash),
+                program_vk_hash_hex(&checkpoint.program_vk_hash),
+                match &checkpoint.groth16_vk {
+                    Some(groth16_vk) => format!("{} bytes", groth16_vk.len()),
+                    None => "none".to_string(),
+                },
+                hex::encode_upper(hash)
+            );
+        }
+        let history_hash = hashes.last().copied().unwrap_or([0; 32]);
+        if history_hash != values.checkpoints_hash {
+            failures.push(format!(
+                "checkpoint history hashes to {}, not the committed checkpoints hash",
+                hex::encode_upper(history_hash)
+            ));
+        }
+    }
+
+    if values.vk_digest != vk.vk.hash_u32() {
+        failures.push("committed vk digest does not match the program vk".to_string());
+    }
+
+    if let Some(expected_genesis) = &args.expected_genesis {
+        let expected = parse_hex(expected_genesis).unwrap_or_else(|e| {
+            eprintln!("Error: --expected-genesis: {}", e);
+            std::process::exit(1);
+        });
+        if values.genesis_hash != expected {
+            failures.push(format!(
+                "genesis hash {} does not match expected {}",
+                hex::encode_upper(&values.genesis_hash),
+                hex::encode_upper(&expected)
+            ));
+        }
+    }
+
+    if !args.allowed_vks.is_empty() {
+        let committed = program_vk_hash_hex(&values.vk_digest);
+        let allowed = args
+            .allowed_vks
+            .iter()
+            .map(|digest| parse_hex(digest).map(|bytes| format!("0x{}", hex::encode(bytes))))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| {
+                eprintln!("Error: --allowed-vk: {}", e);
+                std::process::exit(1);
+            });
+        if !allowed.contains(&committed) {
+            failures.push(format!("vk digest {} is not in the allowed set", committed));
+        }
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("✗ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✓ Public values match expectations");
+}
@@ -0,0 +1,309 @@
+use crate::celestia::DataAvailabilityHeader;
+use crate::public_values::parse_hex;
+use crate::verify::{linked_header_stdin, recursion_head_stdin};
+use consensus::head::HeaderLink;
+use inclusion::{
+    Blob, MerkleProof, NamespaceDataProof, NmtProof, RowNamespaceData, RowProof, ShareProof,
+    NAMESPACE_SIZE, SIGNER_SIZE,
+};
+use serde::{Deserialize, Deserializer};
+use sp1_sdk::{SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+use std::fs;
+use std::path::Path;
+use tendermint::{block::Header, serializers::bytes};
+
+/// A blob as returned by celestia-node's `blob.Get`.
+#[derive(Deserialize, Debug)]
+pub struct BlobJson {
+    #[serde(with = "bytes::base64string")]
+    pub namespace: Vec<u8>,
+    #[serde(with = "bytes::base64string")]
+    pub data: Vec<u8>,
+    #[serde(default)]
+    pub share_version: u8,
+    #[serde(with = "bytes::base64string")]
+    pub commitment: Vec<u8>,
+    #[serde(default, with = "bytes::option_base64string")]
+    pub signer: Vec<u8>,
+}
+
+impl BlobJson {
+    /// Converts to the program's blob, checking that the commitment matches the data.
+    pub fn to_blob(&self) -> Result<Blob, String> {
+        let namespace: [u8; NAMESPACE_SIZE] = self
+            .namespace
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("namespace must be {} bytes", NAMESPACE_SIZE))?;
+        let signer = match self.signer.len() {
+            0 => None,
+            SIGNER_SIZE => Some(self.signer.as_slice().try_into().unwrap()),
+            _ => return Err(format!("signer must be {} bytes", SIGNER_SIZE)),
+        };
+        let blob = Blob {
+            namespace,
+            data: self.data.clone(),
+            share_version: self.share_version,
+            signer,
+        };
+        if blob.commitment().map_err(|e| e.to_string())? != self.commitment.as_slice() {
+            return Err("blob commitment does not match its data".to_string());
+        }
+        Ok(blob)
+    }
+}
+
+/// celestia-core's `ShareProof`, as returned by the `prove_shares_v2` RPC.
+#[derive(Deserialize, Debug)]
+pub struct ShareProofJson {
+    #[serde(with = "bytes::vec_base64string")]
+    pub data: Vec<Vec<u8>>,
+    pub share_proofs: Vec<NmtProofJson>,
+    #[serde(with = "bytes::base64string")]
+    pub namespace_id: Vec<u8>,
+    #[serde(default)]
+    pub namespace_version: u32,
+    pub row_proof: RowProofJson,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NmtProofJson {
+    // Protobuf JSON leaves out zero values.
+    #[serde(default)]
+    pub start: u32,
+    #[serde(default)]
+    pub end: u32,
+    #[serde(default, with = "bytes::vec_base64string")]
+    pub nodes: Vec<Vec<u8>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RowProofJson {
+    pub row_roots: Vec<HexBytes>,
+    pub proofs: Vec<MerkleProofJson>,
+    #[serde(default)]
+    pub start_row: u32,
+    #[serde(default)]
+    pub end_row: u32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HexBytes(#[serde(with = "bytes::hexstring")] pub Vec<u8>);
+
+#[derive(Deserialize, Debug)]
+pub struct MerkleProofJson {
+    #[serde(deserialize_with = "number_or_string")]
+    pub total: u64,
+    #[serde(default, deserialize_with = "number_or_string")]
+    pub index: u64,
+    #[serde(with = "bytes::base64string")]
+    pub leaf_hash: Vec<u8>,
+    #[serde(default, with = "bytes::vec_base64string")]
+    pub aunts: Vec<Vec<u8>>,
+}
+
+/// CometBFT encodes 64 bit integers as strings, protobuf JSON as numbers.
+fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+impl ShareProofJson {
+    /// The namespace of the proven shares: the version byte followed by the id.
+    pub fn namespace(&self) -> Result<[u8; NAMESPACE_SIZE], String> {
+        let version: u8 = self
+            .namespace_version
+            .try_into()
+            .map_err(|_| "namespace version must be a single byte")?;
+        let mut namespace = vec![version];
+        namespace.extend_from_slice(&self.namespace_id);
+        namespace
+            .try_into()
+            .map_err(|_| format!("namespace id must be {} bytes", NAMESPACE_SIZE - 1))
+    }
+
+    /// The proof without the shares, which the program rebuilds from the blob.
+    pub fn to_proof(&self) -> ShareProof {
+        ShareProof {
+            share_proofs: self
+                .share_proofs
+                .iter()
+                .map(|proof| NmtProof {
+                    start: proof.start,
+                    end: proof.end,
+                    nodes: proof.nodes.clone(),
+                })
+                .collect(),
+            row_proof: RowProof {
+                row_roots: self
+                    .row_proof
+                    .row_roots
+                    .iter()
+                    .map(|root| root.0.clone())
+                    .collect(),
+                proofs: self
+                    .row_proof
+                    .proofs
+                    .iter()
+                    .map(|proof| MerkleProof {
+                        total: proof.total,
+                        index: proof.index,
+                        leaf_hash: proof.leaf_hash.clone(),
+                        aunts: proof.aunts.clone(),
+                    })
+                    .collect(),
+                start_row: self.row_proof.start_row,
+                end_row: self.row_proof.end_row,
+            },
+        }
+    }
+}
+
+pub fn read_blob(path: &Path) -> Result<Blob, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    let json: BlobJson = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize blob {:?}: {}", path, e))?;
+    json.to_blob()
+}
+
+/// Reads a share proof and checks that it proves the shares of `blob`.
+pub fn read_share_proof(path: &Path, blob: &Blob) -> Result<ShareProof, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    let json: ShareProofJson = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize share proof {:?}: {}", path, e))?;
+
+    if json.namespace()? != blob.namespace {
+        return Err("share proof is for another namespace".to_string());
+    }
+    if json.data != blob.to_shares().map_err(|e| e.to_string())? {
+        return Err("share proof is for other shares than the blob's".to_string());
+    }
+    Ok(json.to_proof())
+}
+
+/// One row of celestia-node's `share.GetNamespaceData` response.
+#[derive(Deserialize, Debug)]
+pub struct RowNamespaceDataJson {
+    #[serde(default, with = "bytes::vec_base64string")]
+    pub shares: Vec<Vec<u8>>,
+    pub proof: NamespaceProofJson,
+}
+
+/// An NMT `Proof`, which is an absence proof when it carries a leaf hash.
+#[derive(Deserialize, Debug)]
+pub struct NamespaceProofJson {
+    #[serde(default)]
+    pub start: u32,
+    #[serde(default)]
+    pub end: u32,
+    #[serde(default, with = "bytes::vec_base64string")]
+    pub nodes: Vec<Vec<u8>>,
+    #[serde(default, with = "bytes::option_base64string")]
+    pub leaf_hash: Vec<u8>,
+    #[serde(default)]
+    pub is_max_namespace_ignored: bool,
+}
+
+/// Parses a namespace given in hex: all 29 bytes, or the id of a version 0 namespace (at most 10
+/// bytes, as celestia-node's CLI takes it).
+pub fn parse_namespace(s: &str) -> Result<[u8; NAMESPACE_SIZE], String> {
+    let bytes = parse_hex(s)?;
+    match bytes.len() {
+        NAMESPACE_SIZE => Ok(bytes.try_into().unwrap()),
+        1..=10 => {
+            let mut namespace = [0u8; NAMESPACE_SIZE];
+            namespace[NAMESPACE_SIZE - bytes.len()..].copy_from_slice(&bytes);
+            Ok(namespace)
+        }
+        _ => Err(format!(
+            "namespace must be {} bytes, or a version 0 id of at most 10 bytes",
+            NAMESPACE_SIZE
+        )),
+    }
+}
+
+/// Reads a `share.GetNamespaceData` response into a proof against the block's DAH.
+pub fn read_namespace_data(
+    path: &Path,
+    dah: &DataAvailabilityHeader,
+) -> Result<NamespaceDataProof, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    // celestia-node returns null rather than an empty list for a namespace without data.
+    let json: Option<Vec<RowNamespaceDataJson>> = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize namespace data {:?}: {}", path, e))?;
+
+    let rows = json
+        .unwrap_or_default()
+        .into_iter()
+        .map(|row| {
+            if !row.proof.is_max_namespace_ignored {
+                return Err("namespace proofs must ignore the parity namespace".to_string());
+            }
+            Ok(RowNamespaceData {
+                shares: row.shares,
+                proof: NmtProof {
+                    start: row.proof.start,
+                    end: row.proof.end,
+                    nodes: row.proof.nodes,
+                },
+                absence_leaf: (!row.proof.leaf_hash.is_empty()).then_some(row.proof.leaf_hash),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(NamespaceDataProof {
+        row_roots: dah.decoded_row_roots()?,
+        column_roots: dah.decoded_column_roots()?,
+        rows,
+    })
+}
+
+/// Writes the input of the blob inclusion program, for a blob in the block `link` links to the
+/// head through `linked` headers.
+#[allow(clippy::too_many_arguments)]
+pub fn blob_inclusion_stdin(
+    recursion_proof: &SP1ProofWithPublicValues,
+    recursion_vk: &SP1VerifyingKey,
+    head: &Header,
+    link: &HeaderLink,
+    linked: &[Header],
+    blob: &Blob,
+    share_proof: &ShareProof,
+    mock: bool,
+) -> Result<SP1Stdin, String> {
+    let mut stdin = recursion_head_stdin(recursion_proof, recursion_vk, head, mock)?;
+    linked_header_stdin(&mut stdin, link, linked)?;
+    stdin.write(blob);
+    stdin.write(share_proof);
+    Ok(stdin)
+}
+
+/// Writes the input of the namespace completeness program, for the block `link` links to the head
+/// through `linked` headers.
+#[allow(clippy::too_many_arguments)]
+pub fn namespace_completeness_stdin(
+    recursion_proof: &SP1ProofWithPublicValues,
+    recursion_vk: &SP1VerifyingKey,
+    head: &Header,
+    link: &HeaderLink,
+    linked: &[Header],
+    namespace: &[u8; NAMESPACE_SIZE],
+    proof: &NamespaceDataProof,
+    mock: bool,
+) -> Result<SP1Stdin, String> {
+    let mut stdin = recursion_head_stdin(recursion_proof, recursion_vk, head, mock)?;
+    linked_header_stdin(&mut stdin, link, linked)?;
+    stdin.write(namespace);
+    stdin.write(proof);
+    Ok(stdin)
+}
@@ -0,0 +1,73 @@
+use common::RecursionPublicValues;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tendermint::{block::Height, Hash};
+use tendermint_light_client_verifier::types::LightBlock;
+
+use crate::verify::check_genesis;
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+
+/// A trusted state for seeding a `tendermint-light-client` instance, e.g. with
+/// `LightClientBuilder::trust_light_block` or a light store entry with `Status::Trusted`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TrustedStateBundle {
+    pub trusted_height: Height,
+    pub trusted_hash: Hash,
+    /// The proven head, with its validator set and next validator set.
+    pub light_block: LightBlock,
+}
+
+/// Builds the trusted state for the head committed by a verified proof.
+///
+/// The proof only commits the head's hash, so everything else in `head` is checked against the
+/// header: the commit must be for it, and both validator sets must hash to the ones it names. The
+/// proof must also start from the trusted `genesis_hash`.
+pub fn trusted_state_bundle(
+    public_values: &RecursionPublicValues,
+    genesis_hash: &[u8],
+    head: LightBlock,
+) -> Result<TrustedStateBundle, String> {
+    check_genesis(public_values, genesis_hash)?;
+    let header = head.signed_header.header();
+    let hash = header.hash();
+
+    if hash.as_bytes() != public_values.h2_hash.as_slice() {
+        return Err(format!(
+            "head block {} at height {} is not the proven head {}",
+            hash,
+            header.height,
+            hex::encode_upper(&public_values.h2_hash)
+        ));
+    }
+    if head.signed_header.commit().block_id.hash != hash {
+        return Err("head commit is not for the head header".to_string());
+    }
+    if head.validators.hash() != header.validators_hash {
+        return Err("validator set does not match the head header".to_string());
+    }
+    if head.next_validators.hash() != header.next_validators_hash {
+        return Err("next validator set does not match the head header".to_string());
+    }
+
+    Ok(TrustedStateBundle {
+        trusted_height: header.height,
+        trusted_hash: hash,
+        light_block: head,
+    })
+}
+
+/// Finds the light block with the given hash in a headers directory.
+pub fn find_light_block_by_hash(dir: &Path, hash: &[u8]) -> Result<LightBlock, String> {
+    // The head is usually the last block of the path, so search backwards.
+    for (_height, path) in list_headers_dir(dir)?.iter().rev() {
+        let block = read_light_block_file(path)?;
+        if block.signed_header.header().hash().as_bytes() == hash {
+            return Ok(block);
+        }
+    }
+    Err(format!(
+        "no block with hash {} in {:?}",
+        hex::encode_upper(hash),
+        dir
+    ))
+}
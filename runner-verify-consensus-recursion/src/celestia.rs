@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use subtle_encoding::base64;
+use tendermint::{
+    block::{Commit, Header},
+    crypto::default::Sha256,
+    merkle::simple_hash_from_byte_vectors,
+    validator::Set as ValidatorSet,
+};
+use tendermint_light_client_verifier::types::LightBlock;
+use tendermint_proto::v0_38::types::ValidatorSet as RawValidatorSet;
+
+/// A `[Header]` fragment for celestia-node's `config.toml`, starting header sync from `head`.
+pub fn celestia_node_config(head: &Header) -> String {
+    format!(
+        "[Header]\n  # Height {}, {}\n  TrustedHash = \"{}\"\n",
+        head.height,
+        head.time.to_rfc3339(),
+        head.hash()
+    )
+}
+
+/// The celestia-node command line flag that starts header sync from `head`.
+pub fn celestia_node_flags(head: &Header) -> String {
+    format!("--headers.trusted-hash {}", head.hash())
+}
+
+/// The trusted head as plain JSON, for light nodes configured programmatically (e.g. Lumina).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TrustedHead {
+    pub hash: String,
+    pub height: u64,
+    pub time: String,
+}
+
+impl From<&Header> for TrustedHead {
+    fn from(head: &Header) -> Self {
+        TrustedHead {
+            hash: head.hash().to_string(),
+            height: head.height.value(),
+            time: head.time.to_rfc3339(),
+        }
+    }
+}
+
+/// The data availability header of a block: the roots of its extended data square.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DataAvailabilityHeader {
+    /// Base64 encoded row roots.
+    pub row_roots: Vec<String>,
+    /// Base64 encoded column roots.
+    pub column_roots: Vec<String>,
+}
+
+impl DataAvailabilityHeader {
+    /// The data root committed to by these roots, as found in a header's `data_hash`.
+    pub fn hash(&self) -> Result<[u8; 32], String> {
+        let mut roots = self.decoded_row_roots()?;
+        roots.extend(self.decoded_column_roots()?);
+        Ok(simple_hash_from_byte_vectors::<Sha256>(&roots))
+    }
+
+    pub fn decoded_row_roots(&self) -> Result<Vec<Vec<u8>>, String> {
+        decode_roots(&self.row_roots)
+    }
+
+    pub fn decoded_column_roots(&self) -> Result<Vec<Vec<u8>>, String> {
+        decode_roots(&self.column_roots)
+    }
+}
+
+fn decode_roots(roots: &[String]) -> Result<Vec<Vec<u8>>, String> {
+    roots
+        .iter()
+        .map(|root| base64::decode(root).map_err(|e| format!("invalid root {:?}: {}", root, e)))
+        .collect()
+}
+
+/// The head rendered like celestia-node's `ExtendedHeader` JSON.
+///
+/// The DAH is not part of a light block, so it is only included when the caller has one.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExtendedHeader {
+    pub header: Header,
+    #[serde(with = "celestia_commit")]
+    pub commit: Commit,
+    #[serde(serialize_with = "serialize_validator_set")]
+    pub validator_set: ValidatorSet,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dah: Option<DataAvailabilityHeader>,
+}
+
+impl ExtendedHeader {
+    /// Builds the extended header of `block`, checking that `dah` matches its data root.
+    pub fn new(block: &LightBlock, dah: Option<DataAvailabilityHeader>) -> Result<Self, String> {
+        let header = block.signed_header.header().clone();
+        if let Some(dah) = &dah {
+            let data_hash = header.data_hash.ok_or("header has no data hash")?;
+            if dah.hash()? != data_hash.as_bytes() {
+                return Err("DAH does not match the header's data hash".to_string());
+            }
+        }
+        Ok(ExtendedHeader {
+            header,
+            commit: block.signed_header.commit().clone(),
+            validator_set: block.validators.clone(),
+            dah,
+        })
+    }
+}
+
+/// celestia-node writes a validator set as its protobuf JSON: each validator's `voting_power` and
+/// `proposer_priority`, and no total. Both forms deserialize.
+fn serialize_validator_set<S: serde::Serializer>(
+    validator_set: &ValidatorSet,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    RawValidatorSet::from(validator_set.clone()).serialize(serializer)
+}
+
+/// celestia-node writes a commit's height as a number, where CometBFT's RPC writes a string.
+mod celestia_commit {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use serde_json::Value;
+    use tendermint::block::Commit;
+
+    pub fn serialize<S: Serializer>(commit: &Commit, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(commit).map_err(S::Error::custom)?;
+        value["height"] = commit.height.value().into();
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Commit, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let height = value["height"]
+            .as_u64()
+            .ok_or_else(|| D::Error::custom("commit height is not a number"))?;
+        value["height"] = height.to_string().into();
+        serde_json::from_value(value).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,24 @@
+use common::Groth16VkeyCheckpoint;
+use std::fs;
+use std::path::Path;
+
+/// Reads a checkpoint history JSON file, as written by `create_checkpoint`.
+pub fn read_checkpoints(path: &Path) -> Result<Vec<Groth16VkeyCheckpoint>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize checkpoints {:?}: {}", path, e))
+}
+
+/// Replays a checkpoint history, returning the running hash after each checkpoint. The last one
+/// is what a proof with this history commits; a proof with no upgrades commits zero.
+pub fn replay_checkpoint_history(checkpoints: &[Groth16VkeyCheckpoint]) -> Vec<[u8; 32]> {
+    let mut hash = [0; 32];
+    checkpoints
+        .iter()
+        .map(|checkpoint| {
+            hash = checkpoint.extend_history(&hash);
+            hash
+        })
+        .collect()
+}
@@ -0,0 +1,168 @@
+use common::mmr::PathAccumulator;
+use common::Groth16VkeyCheckpoint;
+use consensus::encoding::encode_light_block;
+use sp1_sdk::{
+    ExecutionReport, HashableKey, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey,
+    SP1PublicValues, SP1Stdin, SP1VerifyingKey, SP1_CIRCUIT_VERSION,
+};
+use tendermint_light_client_verifier::types::LightBlock;
+
+use crate::path::path_leaf;
+use crate::preflight::expected_public_values;
+use crate::prover::RecursionProver;
+use crate::prune::prune_signatures;
+use crate::CONSENSUS_VERIFIER_RECURSION_ELF;
+
+/// The inputs of one hop of the recursion program.
+pub struct HopInput<'a> {
+    /// Whether the previous proof is of an older program, named by the last checkpoint.
+    pub is_upgrade: bool,
+    pub checkpoints: &'a [Groth16VkeyCheckpoint],
+    pub genesis_hash: &'a [u8],
+    pub h1: &'a LightBlock,
+    pub h2: &'a LightBlock,
+    /// The path accumulator before this hop.
+    pub previous_path: &'a PathAccumulator,
+    /// The proof ending at h1, unless h1 is genesis.
+    pub previous_proof: Option<&'a SP1ProofWithPublicValues>,
+    /// The vk of the older program, which a compressed previous proof of an upgrade is of.
+    pub previous_vk: Option<&'a SP1VerifyingKey>,
+}
+
+impl HopInput<'_> {
+    /// Writes the inputs in the order the program reads them, for the program with `vk`.
+    pub fn stdin(&self, vk: &SP1VerifyingKey) -> Result<SP1Stdin, String> {
+        let mut stdin = SP1Stdin::new();
+
+        stdin.write(&self.is_upgrade);
+        stdin.write(&self.checkpoints);
+        stdin.write_vec(self.genesis_hash.to_vec());
+        stdin.write_vec(encode_light_block(self.h1).map_err(|e| format!("encoding h1: {}", e))?);
+        stdin.write_vec(encode_light_block(self.h2).map_err(|e| format!("encoding h2: {}", e))?);
+        stdin.write(&vk.vk.hash_u32());
+        stdin.write(self.previous_path);
+
+        if let Some(previous_proof) = self.previous_proof {
+            // An empty groth16 proof tells the program to verify a compressed proof instead.
+            match &previous_proof.proof {
+                SP1Proof::Compressed(compressed_stark_proof) => {
+                    let previous_vk = match (self.is_upgrade, self.previous_vk) {
+                        (false, _) => vk,
+                        (true, Some(previous_vk)) => previous_vk,
+                        (true, None) => {
+                            return Err(
+                                "an upgrade on a compressed proof needs the older program's vk"
+                                    .to_string(),
+                            )
+                        }
+                    };
+                    stdin.write(&Vec::<u8>::new());
+                    stdin.write_proof(
+                        compressed_stark_proof.as_ref().clone(),
+                        previous_vk.vk.clone(),
+                    );
+                }
+                // The encoded proof behind its Groth16 vk hash prefix, as `Groth16Verifier` takes it.
+                SP1Proof::Groth16(_) => stdin.write(&previous_proof.bytes()),
+                _ => return Err("previous proof is neither compressed nor groth16".to_string()),
+            }
+            stdin.write(&previous_proof.public_values.hash());
+            stdin.write(&previous_proof.public_values.to_vec());
+        }
+        Ok(stdin)
+    }
+
+    /// Executes the hop in the recursion program with `vk`, without proving it.
+    pub fn execute(
+        &self,
+        client: &RecursionProver,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(SP1PublicValues, ExecutionReport), String> {
+        let stdin = self.stdin(vk)?;
+        client.execute(CONSENSUS_VERIFIER_RECURSION_ELF, &stdin)
+    }
+}
+
+/// A mock compressed proof with the given public values, for chaining hops in execute mode, where
+/// the program does not check the proofs it recurses on.
+pub fn mock_previous_proof(
+    pk: &SP1ProvingKey,
+    public_values: SP1PublicValues,
+) -> SP1ProofWithPublicValues {
+    SP1ProofWithPublicValues::create_mock_proof(
+        pk,
+        public_values,
+        SP1ProofMode::Compressed,
+        SP1_CIRCUIT_VERSION,
+    )
+}
+
+/// A hop run by [`execute_path`].
+pub struct ExecutedHop<'a> {
+    pub h1: &'a LightBlock,
+    /// h2 as the program got it, with its signatures pruned unless all were kept.
+    pub h2: &'a LightBlock,
+    /// The path accumulator including h2.
+    pub path: &'a PathAccumulator,
+    pub result: Result<(SP1PublicValues, ExecutionReport), String>,
+}
+
+/// Executes every hop between consecutive `blocks`, the first of which is genesis, and hands each
+/// to `on_hop`. Each hop after the first recurses on a mock proof of the one before, which the
+/// mock client does not verify. Everything else runs as it would when proving. The hops after one
+/// that fails run as if it had been proven.
+pub fn execute_path(
+    client: &RecursionProver,
+    checkpoints: &[Groth16VkeyCheckpoint],
+    blocks: impl IntoIterator<Item = LightBlock>,
+    keep_all_signatures: bool,
+    mut on_hop: impl FnMut(ExecutedHop),
+) {
+    let (pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    let mut blocks = blocks.into_iter();
+    let Some(mut h1) = blocks.next() else {
+        return;
+    };
+    let genesis_hash = h1.signed_header.header().hash();
+
+    let mut path = PathAccumulator::default();
+    let mut previous_proof: Option<SP1ProofWithPublicValues> = None;
+    for block in blocks {
+        // The next hop starts from the block as it is, as when proving.
+        let mut h2 = block.clone();
+        if !keep_all_signatures {
+            prune_signatures(&mut h2, &h1);
+        }
+        let result = HopInput {
+            is_upgrade: false,
+            checkpoints,
+            genesis_hash: genesis_hash.as_bytes(),
+            h1: &h1,
+            h2: &h2,
+            previous_path: &path,
+            previous_proof: previous_proof.as_ref(),
+            previous_vk: None,
+        }
+        .execute(client, &vk);
+        path.push(path_leaf(h2.signed_header.header()).digest());
+
+        let public_values = match &result {
+            Ok((public_values, _)) => public_values.clone(),
+            Err(_) => expected_public_values(
+                checkpoints,
+                genesis_hash.as_bytes(),
+                &h2,
+                vk.vk.hash_u32(),
+                &path,
+            ),
+        };
+        on_hop(ExecutedHop {
+            h1: &h1,
+            h2: &h2,
+            path: &path,
+            result,
+        });
+        previous_proof = Some(mock_previous_proof(&pk, public_values));
+        h1 = block;
+    }
+}
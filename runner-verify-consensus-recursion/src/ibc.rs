@@ -0,0 +1,110 @@
+use common::{TRUSTING_PERIOD_SECS, TRUST_THRESHOLD};
+use ibc_proto::{
+    google::protobuf::{Any, Duration, Timestamp},
+    ibc::{
+        core::{
+            client::v1::{Height, MsgCreateClient},
+            commitment::v1::MerkleRoot,
+        },
+        lightclients::tendermint::v1::{ClientState, ConsensusState, Fraction},
+    },
+    ics23,
+};
+use prost::{Message, Name};
+use tendermint::block::Header;
+
+/// Parameters of the IBC client that are not fixed by the proof.
+#[derive(Debug, Clone)]
+pub struct ClientParams {
+    /// Staking unbonding period of the counterparty chain. Must be longer than the trusting period.
+    pub unbonding_period_secs: u64,
+    pub max_clock_drift_secs: u64,
+}
+
+/// Builds an 07-tendermint client state for the chain, trusting `head`.
+///
+/// The trust level and trusting period are the ones the recursion program verified every hop
+/// with, so the client trusts the head exactly as much as the proof does.
+pub fn client_state(head: &Header, params: &ClientParams) -> Result<ClientState, String> {
+    if params.unbonding_period_secs <= TRUSTING_PERIOD_SECS {
+        return Err(format!(
+            "unbonding period {}s must be longer than the trusting period {}s",
+            params.unbonding_period_secs, TRUSTING_PERIOD_SECS
+        ));
+    }
+
+    let chain_id = head.chain_id.to_string();
+    let (numerator, denominator) = TRUST_THRESHOLD;
+    Ok(ClientState {
+        latest_height: Some(Height {
+            revision_number: revision_number(&chain_id),
+            revision_height: head.height.value(),
+        }),
+        chain_id,
+        trust_level: Some(Fraction {
+            numerator,
+            denominator,
+        }),
+        trusting_period: Some(seconds(TRUSTING_PERIOD_SECS)),
+        unbonding_period: Some(seconds(params.unbonding_period_secs)),
+        max_clock_drift: Some(seconds(params.max_clock_drift_secs)),
+        frozen_height: Some(Height::default()),
+        proof_specs: vec![ics23::iavl_spec(), ics23::tendermint_spec()],
+        // The upgrade path of chains using the default Cosmos SDK upgrade module.
+        upgrade_path: vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+        ..Default::default()
+    })
+}
+
+/// Builds the 07-tendermint consensus state of `head`.
+pub fn consensus_state(head: &Header) -> ConsensusState {
+    let nanos = head.time.unix_timestamp_nanos();
+    ConsensusState {
+        timestamp: Some(Timestamp {
+            seconds: nanos.div_euclid(1_000_000_000) as i64,
+            nanos: nanos.rem_euclid(1_000_000_000) as i32,
+        }),
+        root: Some(MerkleRoot {
+            hash: head.app_hash.as_bytes().to_vec(),
+        }),
+        next_validators_hash: head.next_validators_hash.as_bytes().to_vec(),
+    }
+}
+
+/// Wraps both states in a `MsgCreateClient` signed by `signer`.
+pub fn msg_create_client(
+    client_state: &ClientState,
+    consensus_state: &ConsensusState,
+    signer: &str,
+) -> MsgCreateClient {
+    MsgCreateClient {
+        client_state: Some(to_any(client_state)),
+        consensus_state: Some(to_any(consensus_state)),
+        signer: signer.to_string(),
+    }
+}
+
+/// Packs a message into an `Any` under its type URL.
+pub fn to_any<M: Message + Name>(msg: &M) -> Any {
+    Any {
+        type_url: M::type_url(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+/// The revision number IBC derives from a chain id: the `N` in `name-N`, or 0 without one.
+pub fn revision_number(chain_id: &str) -> u64 {
+    match chain_id.rsplit_once('-') {
+        Some((name, number)) if !name.is_empty() && !number.starts_with('0') => {
+            number.parse().unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+fn seconds(secs: u64) -> Duration {
+    Duration {
+        seconds: secs as i64,
+        nanos: 0,
+    }
+}
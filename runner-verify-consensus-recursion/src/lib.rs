@@ -0,0 +1,35 @@
+use sp1_sdk::include_elf;
+
+pub mod ancestors;
+pub mod blob;
+pub mod bootstrap;
+pub mod celestia;
+pub mod checkpoints;
+pub mod hop;
+pub mod ibc;
+pub mod path;
+pub mod preflight;
+pub mod profile;
+pub mod proof_api;
+pub mod proof_store;
+pub mod prover;
+pub mod prune;
+pub mod public_values;
+pub mod state;
+pub mod verify;
+
+/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
+pub const CONSENSUS_VERIFIER_RECURSION_ELF: &[u8] =
+    include_elf!("program-verify-consensus-recursion");
+
+/// The ELF of the blob inclusion program, which builds on recursion proofs.
+pub const BLOB_INCLUSION_ELF: &[u8] = include_elf!("program-blob-inclusion");
+
+/// The ELF of the namespace completeness program, which builds on recursion proofs.
+pub const NAMESPACE_COMPLETENESS_ELF: &[u8] = include_elf!("program-namespace-completeness");
+
+/// The ELF of the state proof program, which builds on recursion proofs.
+pub const STATE_PROOF_ELF: &[u8] = include_elf!("program-state-proof");
+
+/// The ELF of the ancestor header program, which builds on recursion proofs.
+pub const ANCESTOR_HEADER_ELF: &[u8] = include_elf!("program-ancestor-header");
@@ -0,0 +1,118 @@
+use common::mmr::{PathAccumulator, PathLeaf, PathProof};
+use common::RecursionPublicValues;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tendermint::block::Header;
+
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+
+/// The leaf the recursion program adds for a verified head.
+pub fn path_leaf(header: &Header) -> PathLeaf {
+    PathLeaf {
+        hash: header
+            .hash()
+            .as_bytes()
+            .try_into()
+            .expect("header hash must be 32 bytes"),
+        height: header.height.value(),
+        time_nanos: header.time.unix_timestamp_nanos(),
+    }
+}
+
+/// Reads a path accumulator JSON file, as written by `run_program`.
+pub fn read_path_accumulator(path: &Path) -> Result<PathAccumulator, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize path accumulator {:?}: {}", path, e))
+}
+
+/// Reads the leaves of a proof's path from the headers directory it was proven from: every header
+/// after genesis, up to and including the head. Checks them against the committed path root.
+pub fn read_path_leaves(
+    dir: &Path,
+    values: &RecursionPublicValues,
+) -> Result<Vec<PathLeaf>, String> {
+    let headers = list_headers_dir(dir)?;
+    let mut headers = headers.iter();
+    let genesis = match headers.next() {
+        Some((_height, path)) => read_light_block_file(path)?,
+        None => return Err(format!("no headers in {:?}", dir)),
+    };
+    if genesis.signed_header.header().hash().as_bytes() != values.genesis_hash.as_slice() {
+        return Err("first header is not the proven genesis".to_string());
+    }
+
+    let mut leaves = Vec::new();
+    for (_height, path) in headers {
+        let leaf = path_leaf(read_light_block_file(path)?.signed_header.header());
+        let is_head = leaf.hash.as_slice() == values.h2_hash.as_slice();
+        leaves.push(leaf);
+        if is_head {
+            let digests: Vec<[u8; 32]> = leaves.iter().map(PathLeaf::digest).collect();
+            if PathAccumulator::from_leaves(&digests).root() != values.path_root {
+                return Err("headers do not match the proven path root".to_string());
+            }
+            return Ok(leaves);
+        }
+    }
+    Err(format!(
+        "proven head {} is not in {:?}",
+        hex::encode_upper(&values.h2_hash),
+        dir
+    ))
+}
+
+/// A head verified along a proof's path, with the proof of its leaf against the path root.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathHeaderProof {
+    pub leaf: PathLeaf,
+    pub proof: PathProof,
+    pub path_root: [u8; 32],
+}
+
+impl PathHeaderProof {
+    pub fn verify(&self) -> bool {
+        self.proof.verify(&self.leaf.digest(), &self.path_root)
+    }
+}
+
+/// Proves the path header at `height`, given the leaves from [`read_path_leaves`].
+pub fn prove_path_header(
+    leaves: &[PathLeaf],
+    height: u64,
+    path_root: [u8; 32],
+) -> Result<PathHeaderProof, String> {
+    let index = leaves
+        .iter()
+        .position(|leaf| leaf.height == height)
+        .ok_or_else(|| format!("no header at height {} in the path", height))?;
+    let digests: Vec<[u8; 32]> = leaves.iter().map(PathLeaf::digest).collect();
+    let proof = PathProof::new(&digests, index as u64).expect("index is in range");
+    Ok(PathHeaderProof {
+        leaf: leaves[index].clone(),
+        proof,
+        path_root,
+    })
+}
+
+/// The header at `height` on the path of a proof generated from `dir`, with the proof of its leaf
+/// against the committed path root.
+pub fn read_path_header(
+    dir: &Path,
+    values: &RecursionPublicValues,
+    height: u64,
+) -> Result<(Header, PathProof), String> {
+    let leaves = read_path_leaves(dir, values)?;
+    let inclusion = prove_path_header(&leaves, height, values.path_root)?;
+    let path = list_headers_dir(dir)?
+        .into_iter()
+        .find_map(|(h, path)| (h == height).then_some(path))
+        .ok_or_else(|| format!("no header at height {} in {:?}", height, dir))?;
+    let header = read_light_block_file(&path)?.signed_header.header;
+    if path_leaf(&header) != inclusion.leaf {
+        return Err(format!("header at height {} changed in {:?}", height, dir));
+    }
+    Ok((header, inclusion.proof))
+}
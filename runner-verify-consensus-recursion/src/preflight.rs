@@ -0,0 +1,32 @@
+use common::mmr::PathAccumulator;
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint, RecursionPublicValues};
+use sp1_sdk::SP1PublicValues;
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// The public values the recursion program commits for a hop to `h2`, given the path accumulator
+/// including it. Lets a preflight carry on past a hop that failed as if it had been proven.
+pub fn expected_public_values(
+    checkpoints: &[Groth16VkeyCheckpoint],
+    genesis_hash: &[u8],
+    h2: &LightBlock,
+    vk_digest: [u32; 8],
+    path: &PathAccumulator,
+) -> SP1PublicValues {
+    let values = RecursionPublicValues {
+        checkpoints_hash: checkpoint_history_hash(checkpoints),
+        genesis_hash: genesis_hash.to_vec(),
+        h2_hash: h2.signed_header.header().hash().as_bytes().to_vec(),
+        vk_digest,
+        path_root: path.root(),
+    };
+    SP1PublicValues::from(
+        bincode::serialize(&values)
+            .expect("public values serialize")
+            .as_slice(),
+    )
+}
+
+/// The cost of proving with `gas` prover gas units at a price per billion of them.
+pub fn proving_cost(gas: u64, price_per_billion_gas: f64) -> f64 {
+    gas as f64 * price_per_billion_gas / 1e9
+}
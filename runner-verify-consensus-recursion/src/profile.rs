@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use sp1_sdk::ExecutionReport;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The span name the CSV report uses for a hop's total cycles.
+pub const TOTAL_SPAN: &str = "total";
+
+/// The cycles of executing one hop, in total and per cycle tracker span of the program. Spans may
+/// nest, so they need not add up to the total.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HopProfile {
+    pub h1: u64,
+    pub h2: u64,
+    pub cycles: u64,
+    pub spans: BTreeMap<String, u64>,
+}
+
+impl HopProfile {
+    pub fn new(h1: u64, h2: u64, report: &ExecutionReport) -> Self {
+        HopProfile {
+            h1,
+            h2,
+            cycles: report.total_instruction_count(),
+            spans: report
+                .cycle_tracker
+                .iter()
+                .map(|(span, cycles)| (span.clone(), *cycles))
+                .collect(),
+        }
+    }
+}
+
+/// The cycle profile of a path of hops, with the totals over all of them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CycleProfile {
+    pub hops: Vec<HopProfile>,
+    pub cycles: u64,
+    pub spans: BTreeMap<String, u64>,
+}
+
+/// A span of a hop that took more cycles than the baseline allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub h1: u64,
+    pub h2: u64,
+    pub span: String,
+    pub baseline: u64,
+    pub cycles: u64,
+}
+
+impl CycleProfile {
+    pub fn new(hops: Vec<HopProfile>) -> Self {
+        let mut spans = BTreeMap::new();
+        for hop in &hops {
+            for (span, cycles) in &hop.spans {
+                *spans.entry(span.clone()).or_insert(0) += cycles;
+            }
+        }
+        CycleProfile {
+            cycles: hops.iter().map(|hop| hop.cycles).sum(),
+            hops,
+            spans,
+        }
+    }
+
+    /// Reads a profile JSON file, as written by `bench_cycles`.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("failed to deserialize cycle profile {:?}: {}", path, e))
+    }
+
+    /// One `h1,h2,span,cycles` row per span of each hop, then the totals with `all` as heights.
+    /// Each hop's total cycles are under the [`TOTAL_SPAN`] span.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("h1,h2,span,cycles\n");
+        let mut row = |h1: &str, h2: &str, span: &str, cycles: u64| {
+            csv.push_str(&format!("{},{},{},{}\n", h1, h2, csv_field(span), cycles));
+        };
+        for hop in &self.hops {
+            let (h1, h2) = (hop.h1.to_string(), hop.h2.to_string());
+            for (span, cycles) in &hop.spans {
+                row(&h1, &h2, span, *cycles);
+            }
+            row(&h1, &h2, TOTAL_SPAN, hop.cycles);
+        }
+        for (span, cycles) in &self.spans {
+            row("all", "all", span, *cycles);
+        }
+        row("all", "all", TOTAL_SPAN, self.cycles);
+        csv
+    }
+
+    /// The spans, and hop totals, that grew by more than `tolerance_percent` over the baseline.
+    /// Hops are matched by their heights, so a baseline of a longer path still applies, and spans
+    /// the baseline does not have are skipped.
+    pub fn regressions(&self, baseline: &CycleProfile, tolerance_percent: f64) -> Vec<Regression> {
+        let exceeds = |baseline: u64, cycles: u64| {
+            cycles as f64 > baseline as f64 * (1.0 + tolerance_percent / 100.0)
+        };
+
+        let mut regressions = Vec::new();
+        for hop in &self.hops {
+            let Some(base) = baseline
+                .hops
+                .iter()
+                .find(|base| base.h1 == hop.h1 && base.h2 == hop.h2)
+            else {
+                continue;
+            };
+            let spans = hop
+                .spans
+                .iter()
+                .map(|(span, cycles)| (span.as_str(), *cycles, base.spans.get(span).copied()))
+                .chain([(TOTAL_SPAN, hop.cycles, Some(base.cycles))]);
+            for (span, cycles, base_cycles) in spans {
+                match base_cycles {
+                    Some(base_cycles) if exceeds(base_cycles, cycles) => {
+                        regressions.push(Regression {
+                            h1: hop.h1,
+                            h2: hop.h2,
+                            span: span.to_string(),
+                            baseline: base_cycles,
+                            cycles,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        regressions
+    }
+}
+
+/// Quotes a CSV field if it needs to be.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,247 @@
+use crate::proof_store::{ProofStore, StoredProof};
+use crate::public_values::parse_hex;
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use consensus::encoding::encode_light_block;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use verifier::program_vk_hash_hex;
+
+/// Nothing served is fixed, so caches revalidate it by ETag. The latest proof and the checkpoint
+/// history move on, and the proof of a head hash can be replaced by another proof of that head: a
+/// Groth16 wrap, or one over another path, which commits another path root.
+const REVALIDATE: &str = "no-cache";
+
+const JSON: &str = "application/json";
+const BINARY: &str = "application/octet-stream";
+
+/// How a response is encoded, as the request's `Accept` header chooses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// The JSON the CLI tools read and write.
+    Json,
+    /// What the programs read: bincode, and the compact light block encoding for headers.
+    Binary,
+}
+
+/// Picks the format an `Accept` header prefers, JSON unless it says otherwise. `None` if it
+/// accepts neither.
+pub fn negotiate(accept: Option<&str>) -> Option<Format> {
+    let Some(accept) = accept else {
+        return Some(Format::Json);
+    };
+    let mut ranges: Vec<(f32, &str)> = accept
+        .split(',')
+        .map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or("");
+            let quality = parts
+                .find_map(|parameter| parameter.strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (quality, media_type)
+        })
+        .filter(|(quality, _)| *quality > 0.0)
+        .collect();
+    // Stable, so that ranges of equal quality keep their order.
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranges
+        .into_iter()
+        .find_map(|(_, media_type)| match media_type {
+            JSON | "application/*" | "*/*" => Some(Format::Json),
+            BINARY => Some(Format::Binary),
+            _ => None,
+        })
+}
+
+/// Serves the proofs of a store:
+///
+/// - `/proofs/{latest|head hash}`: the proof
+/// - `/proofs/{latest|head hash}/public_values`: its decoded public values
+/// - `/proofs/{latest|head hash}/head`: the light block it ends at
+/// - `/checkpoints`: the upgrade history
+pub fn router(store: Arc<ProofStore>) -> Router {
+    Router::new()
+        .route("/proofs/:id", get(proof))
+        .route("/proofs/:id/public_values", get(public_values))
+        .route("/proofs/:id/head", get(head))
+        .route("/checkpoints", get(checkpoints))
+        .with_state(store)
+}
+
+/// Refreshes the store's index every `interval`, so that new proofs are served as they land.
+pub async fn watch(store: Arc<ProofStore>, interval: Duration) {
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        ticks.tick().await;
+        let refreshing = store.clone();
+        let refreshed = tokio::task::spawn_blocking(move || refreshing.refresh())
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+        match refreshed {
+            Ok(true) => {
+                let index = store.index();
+                println!("Indexed {} proofs", index.proofs.len());
+                for e in &index.errors {
+                    eprintln!("Error: {}", e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, JSON)
+        .body(Body::from(json!({ "error": message }).to_string()))
+        .unwrap()
+}
+
+/// The proof named by `latest` or a head hash.
+fn select(store: &ProofStore, id: &str) -> Result<Arc<StoredProof>, (StatusCode, String)> {
+    let index = store.index();
+    let head_hash = if id == "latest" {
+        index
+            .latest
+            .clone()
+            .ok_or_else(|| (StatusCode::NOT_FOUND, "no proofs yet".to_string()))?
+    } else {
+        parse_hex(id).map_err(|e| (StatusCode::BAD_REQUEST, e))?
+    };
+    let stored = index.proofs.get(&head_hash).cloned().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("no proof of head {}", hex::encode(&head_hash)),
+        )
+    })?;
+    Ok(stored)
+}
+
+/// Responds with the representation the request accepts, tagged by its hash. A request that
+/// already has it, by `If-None-Match`, gets an empty 304.
+fn represent(
+    headers: &HeaderMap,
+    json: impl FnOnce() -> Result<Vec<u8>, String>,
+    binary: impl FnOnce() -> Result<Vec<u8>, String>,
+) -> Response {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok());
+    let Some(format) = negotiate(accept) else {
+        return error(
+            StatusCode::NOT_ACCEPTABLE,
+            format!("available as {} or {}", JSON, BINARY),
+        );
+    };
+    let (body, content_type) = match format {
+        Format::Json => (json(), JSON),
+        Format::Binary => (binary(), BINARY),
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&body)[..16]));
+    let cached = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == etag || tag == "*");
+
+    let response = Response::builder()
+        .header(header::ETAG, HeaderValue::from_str(&etag).unwrap())
+        .header(header::CACHE_CONTROL, REVALIDATE)
+        .header(header::VARY, "Accept");
+    if cached {
+        return response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .unwrap();
+    }
+    response
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+async fn proof(
+    State(store): State<Arc<ProofStore>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let stored = match select(&store, &id) {
+        Ok(selected) => selected,
+        Err((status, message)) => return error(status, message),
+    };
+    represent(
+        &headers,
+        || serde_json::to_vec(&stored.proof).map_err(|e| e.to_string()),
+        || bincode::serialize(&stored.proof).map_err(|e| e.to_string()),
+    )
+}
+
+async fn public_values(
+    State(store): State<Arc<ProofStore>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let stored = match select(&store, &id) {
+        Ok(selected) => selected,
+        Err((status, message)) => return error(status, message),
+    };
+    let values = &stored.public_values;
+    represent(
+        &headers,
+        || {
+            let decoded = json!({
+                "checkpoints_hash": hex::encode(values.checkpoints_hash),
+                "genesis_hash": hex::encode(&values.genesis_hash),
+                "h2_hash": hex::encode(&values.h2_hash),
+                "vk_digest": program_vk_hash_hex(&values.vk_digest),
+                "path_root": hex::encode(values.path_root),
+            });
+            Ok(decoded.to_string().into_bytes())
+        },
+        || Ok(stored.proof.public_values.to_vec()),
+    )
+}
+
+async fn head(
+    State(store): State<Arc<ProofStore>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let stored = match select(&store, &id) {
+        Ok(selected) => selected,
+        Err((status, message)) => return error(status, message),
+    };
+    represent(
+        &headers,
+        || serde_json::to_vec(&stored.head).map_err(|e| e.to_string()),
+        || encode_light_block(&stored.head).map_err(|e| e.to_string()),
+    )
+}
+
+async fn checkpoints(State(store): State<Arc<ProofStore>>, headers: HeaderMap) -> Response {
+    let index = store.index();
+    represent(
+        &headers,
+        || serde_json::to_vec(&index.checkpoints).map_err(|e| e.to_string()),
+        || bincode::serialize(&index.checkpoints).map_err(|e| e.to_string()),
+    )
+}
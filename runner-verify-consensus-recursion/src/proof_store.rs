@@ -0,0 +1,234 @@
+use crate::checkpoints::{read_checkpoints, replay_checkpoint_history};
+use crate::prover::RecursionProver;
+use crate::public_values;
+use crate::verify::{check_genesis, verify_recursion_proof};
+use common::{Groth16VkeyCheckpoint, RecursionPublicValues};
+use scraper::headers_dir::{list_headers_dir, read_light_block_file};
+use sp1_sdk::{HashableKey, SP1Proof, SP1ProofWithPublicValues, SP1VerifyingKey};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// A proof of the store, with its decoded public values and the header it ends at.
+pub struct StoredProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub public_values: RecursionPublicValues,
+    pub head: LightBlock,
+}
+
+/// Everything a proof store holds, ready to serve.
+#[derive(Default)]
+pub struct ProofIndex {
+    /// Proofs by the hash of their head.
+    pub proofs: HashMap<Vec<u8>, Arc<StoredProof>>,
+    /// The head hash of the proof with the highest head.
+    pub latest: Option<Vec<u8>>,
+    pub checkpoints: Vec<Groth16VkeyCheckpoint>,
+    /// Files that were left out, and why.
+    pub errors: Vec<String>,
+}
+
+/// Verifies the proofs of a store.
+pub trait StoreVerifier: Send + Sync {
+    /// Verifies a compressed or Groth16 proof, returning the digest of the vk it verified against.
+    fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<[u32; 8], String>;
+}
+
+/// Verifies proofs of the program with [`verify_recursion_proof`].
+pub struct ProgramVerifier {
+    pub client: RecursionProver,
+    pub vk: SP1VerifyingKey,
+    pub groth16_vk: Vec<u8>,
+}
+
+impl StoreVerifier for ProgramVerifier {
+    fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<[u32; 8], String> {
+        verify_recursion_proof(&self.client, proof, &self.vk, &self.groth16_vk)?;
+        Ok(self.vk.vk.hash_u32())
+    }
+}
+
+/// What the proofs of a store must be: of the chain from `genesis_hash`, and verified by
+/// `verifier`.
+pub struct StoreConfig {
+    pub genesis_hash: Vec<u8>,
+    pub verifier: Box<dyn StoreVerifier>,
+}
+
+/// A directory of proofs to serve, laid out as:
+///
+/// - `proofs/*.json`: recursion proofs, as `run_program` writes them
+/// - `headers/block_<height>.json`: the headers they were proven from, at least each one's head
+/// - `checkpoints.json`: the upgrade history, as `create_checkpoint` writes it, if there is one
+pub struct ProofStore {
+    pub dir: PathBuf,
+    config: StoreConfig,
+    index: RwLock<Arc<ProofIndex>>,
+    /// The files the index was built from, with their sizes and modification times.
+    fingerprint: RwLock<Vec<(PathBuf, u64, Option<SystemTime>)>>,
+}
+
+impl ProofStore {
+    /// Opens a store and indexes it.
+    pub fn open(dir: PathBuf, config: StoreConfig) -> Result<Self, String> {
+        let store = ProofStore {
+            dir,
+            config,
+            index: RwLock::default(),
+            fingerprint: RwLock::default(),
+        };
+        store.refresh()?;
+        Ok(store)
+    }
+
+    /// The current index.
+    pub fn index(&self) -> Arc<ProofIndex> {
+        self.index.read().unwrap().clone()
+    }
+
+    /// Indexes the store again if any of its files changed. Returns whether it did.
+    pub fn refresh(&self) -> Result<bool, String> {
+        let fingerprint = fingerprint(&self.dir)?;
+        if *self.fingerprint.read().unwrap() == fingerprint {
+            return Ok(false);
+        }
+        let index = scan(&self.dir, &self.config)?;
+        *self.index.write().unwrap() = Arc::new(index);
+        *self.fingerprint.write().unwrap() = fingerprint;
+        Ok(true)
+    }
+}
+
+/// The proof files of a store, by name.
+fn proof_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let proofs_dir = dir.join("proofs");
+    let mut files: Vec<PathBuf> = fs::read_dir(&proofs_dir)
+        .map_err(|e| format!("failed to read proofs directory {:?}: {}", proofs_dir, e))?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn fingerprint(dir: &Path) -> Result<Vec<(PathBuf, u64, Option<SystemTime>)>, String> {
+    let headers = list_headers_dir(&dir.join("headers"))?
+        .into_iter()
+        .map(|(_, path)| path);
+    let checkpoints = Some(dir.join("checkpoints.json")).filter(|path| path.exists());
+    Ok(proof_files(dir)?
+        .into_iter()
+        .chain(headers)
+        .chain(checkpoints)
+        .map(|path| {
+            let metadata = fs::metadata(&path).ok();
+            let size = metadata.as_ref().map_or(0, |metadata| metadata.len());
+            let modified = metadata.and_then(|metadata| metadata.modified().ok());
+            (path, size, modified)
+        })
+        .collect())
+}
+
+/// Indexes every proof of a store that verifies, starts from its genesis, ends at a head in its
+/// headers and commits one of its upgrade histories. The first proof of each height, by file
+/// name, is the one served, and a proof of another head at the same height is left out.
+pub fn scan(dir: &Path, config: &StoreConfig) -> Result<ProofIndex, String> {
+    let checkpoints_path = dir.join("checkpoints.json");
+    let checkpoints = if checkpoints_path.exists() {
+        read_checkpoints(&checkpoints_path)?
+    } else {
+        Vec::new()
+    };
+    let mut histories = replay_checkpoint_history(&checkpoints);
+    histories.push([0; 32]);
+
+    let mut index = ProofIndex {
+        checkpoints,
+        ..Default::default()
+    };
+    let mut heads = HashMap::new();
+    for (_, path) in list_headers_dir(&dir.join("headers"))? {
+        match read_light_block_file(&path) {
+            Ok(block) => {
+                heads.insert(block.signed_header.header.hash().as_bytes().to_vec(), block);
+            }
+            Err(e) => index.errors.push(e),
+        }
+    }
+
+    let mut heights = BTreeMap::new();
+    for path in proof_files(dir)? {
+        match read_proof(&path, config, &heads, &histories) {
+            Ok(stored) => {
+                let head_hash = stored.public_values.h2_hash.clone();
+                let height = stored.head.height().value();
+                if let Some(served) = heights.get(&height) {
+                    index.errors.push(if *served == head_hash {
+                        format!(
+                            "{:?}: another proof of head {} is served",
+                            path,
+                            hex::encode(&head_hash)
+                        )
+                    } else {
+                        format!(
+                            "{:?}: head {} conflicts with the served head {} at height {}",
+                            path,
+                            hex::encode(&head_hash),
+                            hex::encode(served),
+                            height
+                        )
+                    });
+                    continue;
+                }
+                heights.insert(height, head_hash.clone());
+                index.proofs.insert(head_hash, Arc::new(stored));
+            }
+            Err(e) => index.errors.push(format!("{:?}: {}", path, e)),
+        }
+    }
+    index.latest = heights.into_values().next_back();
+    Ok(index)
+}
+
+fn read_proof(
+    path: &Path,
+    config: &StoreConfig,
+    heads: &HashMap<Vec<u8>, LightBlock>,
+    histories: &[[u8; 32]],
+) -> Result<StoredProof, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read: {}", e))?;
+    let proof: SP1ProofWithPublicValues =
+        serde_json::from_str(&content).map_err(|e| format!("failed to deserialize: {}", e))?;
+    if !matches!(proof.proof, SP1Proof::Compressed(_) | SP1Proof::Groth16(_)) {
+        return Err("unsupported proof type, expected a compressed or groth16 proof".to_string());
+    }
+    let vk_digest = config
+        .verifier
+        .verify(&proof)
+        .map_err(|e| format!("proof verification failed: {}", e))?;
+    let public_values = public_values::decode(proof.public_values.as_slice())?;
+    if public_values.vk_digest != vk_digest {
+        return Err("committed vk digest does not match the program vk".to_string());
+    }
+    check_genesis(&public_values, &config.genesis_hash)?;
+    let head = heads.get(&public_values.h2_hash).cloned().ok_or_else(|| {
+        format!(
+            "head {} is not in the headers",
+            hex::encode(&public_values.h2_hash)
+        )
+    })?;
+    if !histories.contains(&public_values.checkpoints_hash) {
+        return Err("commits an upgrade history the store does not have".to_string());
+    }
+    Ok(StoredProof {
+        proof,
+        public_values,
+        head,
+    })
+}
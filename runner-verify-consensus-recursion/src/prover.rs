@@ -0,0 +1,151 @@
+use clap::ValueEnum;
+use sp1_sdk::{
+    network::{FulfillmentStrategy, NetworkMode},
+    CpuProver, CudaProver, ExecutionReport, NetworkProver, Prover, ProverClient, SP1ProofMode,
+    SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerificationError,
+    SP1VerifyingKey,
+};
+
+/// Where proofs are generated. Mirrors the values accepted by `SP1_PROVER`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProverMode {
+    /// Mock proofs generated locally, for tests and dry runs of whole chains.
+    Mock,
+    /// Real proofs generated locally on the CPU.
+    Cpu,
+    /// Real proofs generated locally on the GPU.
+    Cuda,
+    /// Real proofs requested from the Succinct Prover Network.
+    Network,
+}
+
+/// A prover client for any of the supported [`ProverMode`]s.
+#[allow(clippy::large_enum_variant)]
+pub enum RecursionProver {
+    Cpu { client: CpuProver, mock: bool },
+    Cuda(CudaProver),
+    Network(NetworkProver),
+}
+
+impl RecursionProver {
+    /// Builds the client for `mode`. The private key is only used (and required) in network mode.
+    pub fn new(mode: ProverMode, private_key: Option<&str>) -> Result<Self, String> {
+        let prover = match mode {
+            ProverMode::Mock => RecursionProver::Cpu {
+                client: ProverClient::builder().mock().build(),
+                mock: true,
+            },
+            ProverMode::Cpu => RecursionProver::Cpu {
+                client: ProverClient::builder().cpu().build(),
+                mock: false,
+            },
+            ProverMode::Cuda => RecursionProver::Cuda(ProverClient::builder().cuda().build()),
+            ProverMode::Network => {
+                let private_key = private_key
+                    .ok_or("a private key is required when using the network prover")?;
+                RecursionProver::Network(
+                    ProverClient::builder()
+                        .network_for(NetworkMode::Mainnet)
+                        .private_key(private_key)
+                        .build(),
+                )
+            }
+        };
+        Ok(prover)
+    }
+
+    /// Whether this client only produces mock proofs.
+    pub fn is_mock(&self) -> bool {
+        matches!(self, RecursionProver::Cpu { mock: true, .. })
+    }
+
+    pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        match self {
+            RecursionProver::Cpu { client, .. } => client.setup(elf),
+            RecursionProver::Cuda(client) => client.setup(elf),
+            RecursionProver::Network(client) => client.setup(elf),
+        }
+    }
+
+    /// Executes the program without proving it. If the program panics, the error includes the
+    /// panic message.
+    ///
+    /// In mock mode the previous proof in `stdin` is a mock proof too, so deferred proof
+    /// verification is skipped.
+    pub fn execute(
+        &self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport), String> {
+        let builder = match self {
+            RecursionProver::Cpu { client, .. } => client.execute(elf, stdin),
+            RecursionProver::Cuda(client) => client.execute(elf, stdin),
+            RecursionProver::Network(client) => client.execute(elf, stdin),
+        };
+        let mut stderr = Vec::new();
+        let result = builder
+            .deferred_proof_verification(!self.is_mock())
+            .stderr(&mut stderr)
+            .run();
+        // Still show it as the executor would have.
+        let stderr = String::from_utf8_lossy(&stderr);
+        for line in stderr.lines() {
+            eprintln!("stderr: {}", line);
+        }
+        result.map_err(|e| match panic_message(&stderr) {
+            Some(message) => format!("{}: {}", e, message),
+            None => e.to_string(),
+        })
+    }
+
+    /// Proves the program, as a groth16 proof if `groth16` is set and a compressed proof otherwise.
+    pub fn prove(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        groth16: bool,
+    ) -> Result<SP1ProofWithPublicValues, String> {
+        let mode = if groth16 {
+            SP1ProofMode::Groth16
+        } else {
+            SP1ProofMode::Compressed
+        };
+        let result = match self {
+            RecursionProver::Cpu { client, mock } => client
+                .prove(pk, stdin)
+                .mode(mode)
+                .deferred_proof_verification(!mock)
+                .run(),
+            RecursionProver::Cuda(client) => client.prove(pk, stdin).mode(mode).run(),
+            RecursionProver::Network(client) => client
+                .prove(pk, stdin)
+                .strategy(FulfillmentStrategy::Auction)
+                .mode(mode)
+                .run(),
+        };
+        result.map_err(|e| e.to_string())
+    }
+
+    pub fn verify(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), SP1VerificationError> {
+        match self {
+            RecursionProver::Cpu { client, .. } => client.verify(proof, vk),
+            RecursionProver::Cuda(client) => client.verify(proof, vk),
+            RecursionProver::Network(client) => client.verify(proof, vk),
+        }
+    }
+}
+
+/// The message and location of the panic in what a program wrote to stderr, if it panicked.
+pub fn panic_message(stderr: &str) -> Option<String> {
+    let (_, panic) = stderr.split_once("panicked at ")?;
+    let mut lines = panic.lines();
+    let location = lines.next()?.trim_end_matches(':');
+    let message: Vec<&str> = lines
+        .take_while(|line| !line.starts_with("note: "))
+        .collect();
+    Some(format!("{} (at {})", message.join("\n").trim(), location))
+}
@@ -0,0 +1,66 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use tendermint::{account, block::CommitSig};
+use tendermint_light_client_verifier::types::{LightBlock, ValidatorSet};
+
+/// Marks absent every signature of `untrusted`'s commit that verifying it from `trusted` does not
+/// need, and returns how many are left.
+///
+/// Verification stops counting signatures once they carry more than 2/3 of the untrusted
+/// validators' power and, unless the blocks are adjacent, more than the trust threshold of the
+/// trusted next validators' power. So the signatures of the most powerful validators up to both
+/// thresholds are enough. Nil votes count towards neither and are always dropped.
+pub fn prune_signatures(untrusted: &mut LightBlock, trusted: &LightBlock) -> usize {
+    let adjacent = trusted.height().increment() == untrusted.height();
+    let untrusted_powers = powers(&untrusted.validators);
+    let trusted_powers = powers(&trusted.next_validators);
+    let untrusted_total: u64 = untrusted_powers.values().sum();
+    let trusted_total: u64 = trusted_powers.values().sum();
+    let (numerator, denominator) = common::TRUST_THRESHOLD;
+
+    let signatures = &mut untrusted.signed_header.commit.signatures;
+    let power_of = |powers: &HashMap<account::Id, u64>, signature: &CommitSig| {
+        signature
+            .validator_address()
+            .and_then(|address| powers.get(&address).copied())
+            .unwrap_or(0)
+    };
+
+    let mut by_power: Vec<usize> = (0..signatures.len())
+        .filter(|&i| signatures[i].is_commit())
+        .collect();
+    by_power.sort_by_key(|&i| Reverse(power_of(&untrusted_powers, &signatures[i])));
+
+    let mut keep = vec![false; signatures.len()];
+    let (mut untrusted_tally, mut trusted_tally) = (0, 0);
+    for i in by_power {
+        if is_enough_power(untrusted_tally, untrusted_total, 2, 3)
+            && (adjacent || is_enough_power(trusted_tally, trusted_total, numerator, denominator))
+        {
+            break;
+        }
+        keep[i] = true;
+        untrusted_tally += power_of(&untrusted_powers, &signatures[i]);
+        trusted_tally += power_of(&trusted_powers, &signatures[i]);
+    }
+
+    for (signature, keep) in signatures.iter_mut().zip(&keep) {
+        if !keep {
+            *signature = CommitSig::BlockIdFlagAbsent;
+        }
+    }
+    keep.iter().filter(|keep| **keep).count()
+}
+
+fn powers(validators: &ValidatorSet) -> HashMap<account::Id, u64> {
+    validators
+        .validators()
+        .iter()
+        .map(|validator| (validator.address, validator.power()))
+        .collect()
+}
+
+/// The light client's threshold check: strictly more than `numerator / denominator` of the total.
+fn is_enough_power(tallied: u64, total: u64, numerator: u64, denominator: u64) -> bool {
+    tallied as u128 * denominator as u128 > total as u128 * numerator as u128
+}
@@ -0,0 +1,40 @@
+use common::{
+    AncestorHeaderPublicValues, BlobInclusionPublicValues, NamespaceCompletenessPublicValues,
+    RecursionPublicValues, StatePublicValues,
+};
+use consensus::recursion::decode_public_values;
+
+/// Decodes the public values of a recursion proof. Proofs from before the checkpoints hash and
+/// the path root are read as upgrades read them: with the hash of their checkpoint list and the
+/// root of an empty path.
+pub fn decode(public_values: &[u8]) -> Result<RecursionPublicValues, String> {
+    decode_public_values(public_values, true).map_err(|e| format!("malformed public values: {}", e))
+}
+
+/// Parses a hex string, with or without a `0x` prefix, in either case.
+pub fn parse_hex(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s.to_lowercase()).map_err(|e| format!("invalid hex {:?}: {}", s, e))
+}
+
+/// Decodes the public values of a blob inclusion proof.
+pub fn decode_blob_inclusion(public_values: &[u8]) -> Result<BlobInclusionPublicValues, String> {
+    bincode::deserialize(public_values).map_err(|e| format!("malformed public values: {}", e))
+}
+
+/// Decodes the public values of a namespace completeness proof.
+pub fn decode_namespace_completeness(
+    public_values: &[u8],
+) -> Result<NamespaceCompletenessPublicValues, String> {
+    bincode::deserialize(public_values).map_err(|e| format!("malformed public values: {}", e))
+}
+
+/// Decodes the public values of a state proof.
+pub fn decode_state(public_values: &[u8]) -> Result<StatePublicValues, String> {
+    bincode::deserialize(public_values).map_err(|e| format!("malformed public values: {}", e))
+}
+
+/// Decodes the public values of an ancestor header proof.
+pub fn decode_ancestor_header(public_values: &[u8]) -> Result<AncestorHeaderPublicValues, String> {
+    bincode::deserialize(public_values).map_err(|e| format!("malformed public values: {}", e))
+}
@@ -0,0 +1,125 @@
+use consensus::head::HeaderLink;
+use serde::Deserialize;
+use sp1_sdk::{SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+use state_proof::StateProof;
+use std::fs;
+use std::path::Path;
+use tendermint::{
+    block::Header,
+    serializers::{bytes, from_str},
+};
+
+use crate::verify::{linked_header_stdin, recursion_head_stdin};
+
+/// The JSON-RPC response of CometBFT's `abci_query`, made with `prove=true` on a
+/// `store/<name>/key` path.
+#[derive(Deserialize, Debug)]
+pub struct AbciQueryJson {
+    pub result: AbciQueryResult,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AbciQueryResult {
+    pub response: AbciQueryResponse,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AbciQueryResponse {
+    #[serde(default)]
+    pub code: u32,
+    #[serde(default)]
+    pub log: String,
+    #[serde(with = "bytes::base64string")]
+    pub key: Vec<u8>,
+    /// Empty (or null) for a key that is not set.
+    #[serde(default, with = "bytes::option_base64string")]
+    pub value: Vec<u8>,
+    #[serde(rename = "proofOps")]
+    pub proof_ops: Option<ProofOpsJson>,
+    #[serde(with = "from_str")]
+    pub height: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProofOpsJson {
+    pub ops: Vec<ProofOpJson>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProofOpJson {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(with = "bytes::base64string")]
+    pub key: Vec<u8>,
+    #[serde(with = "bytes::base64string")]
+    pub data: Vec<u8>,
+}
+
+impl AbciQueryResponse {
+    /// The state proof in the response: an IAVL proof of the key in its store, then a proof of the
+    /// store in the multistore.
+    pub fn to_state_proof(&self) -> Result<StateProof, String> {
+        if self.code != 0 {
+            return Err(format!(
+                "query failed with code {}: {}",
+                self.code, self.log
+            ));
+        }
+        let ops = match &self.proof_ops {
+            Some(proof_ops) => proof_ops.ops.as_slice(),
+            None => return Err("response has no proof, query with prove=true".to_string()),
+        };
+        let (store_op, multistore_op) = match ops {
+            [store_op, multistore_op]
+                if store_op.kind == "ics23:iavl" && multistore_op.kind == "ics23:simple" =>
+            {
+                (store_op, multistore_op)
+            }
+            _ => return Err("expected an ics23:iavl and an ics23:simple proof".to_string()),
+        };
+        if store_op.key != self.key {
+            return Err("store proof is for another key".to_string());
+        }
+        let store = String::from_utf8(multistore_op.key.clone())
+            .map_err(|_| "store name is not UTF-8".to_string())?;
+
+        Ok(StateProof {
+            store,
+            key: self.key.clone(),
+            value: (!self.value.is_empty()).then(|| self.value.clone()),
+            store_proof: store_op.data.clone(),
+            multistore_proof: multistore_op.data.clone(),
+        })
+    }
+}
+
+/// Reads an `abci_query` response, returning its state proof and the height of the state.
+pub fn read_abci_query(path: &Path) -> Result<(StateProof, u64), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    let json: AbciQueryJson = serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "failed to deserialize abci_query response {:?}: {}",
+            path, e
+        )
+    })?;
+    let response = json.result.response;
+    Ok((response.to_state_proof()?, response.height))
+}
+
+/// Writes the input of the state proof program, for the block `link` links to the head through
+/// `linked` headers.
+pub fn state_proof_stdin(
+    recursion_proof: &SP1ProofWithPublicValues,
+    recursion_vk: &SP1VerifyingKey,
+    head: &Header,
+    link: &HeaderLink,
+    linked: &[Header],
+    proof: &StateProof,
+    mock: bool,
+) -> Result<SP1Stdin, String> {
+    let mut stdin = recursion_head_stdin(recursion_proof, recursion_vk, head, mock)?;
+    linked_header_stdin(&mut stdin, link, linked)?;
+    stdin.write(proof);
+    Ok(stdin)
+}
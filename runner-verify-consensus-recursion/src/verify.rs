@@ -0,0 +1,215 @@
+use crate::{
+    ancestors::read_ancestors, path::read_path_header, prover::RecursionProver, public_values,
+};
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint, RecursionPublicValues};
+use consensus::head::HeaderLink;
+use scraper::headers_dir::read_light_block_file;
+use sp1_sdk::{HashableKey, SP1Proof, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+use sp1_verifier::Groth16Verifier;
+use std::fs;
+use std::path::Path;
+use tendermint::block::Header;
+
+/// Verifies a recursion proof against the program vk.
+///
+/// Groth16 proofs are checked against `groth16_vk`, so that proofs wrapped by an older SP1 version
+/// can be verified too. Mock proofs are handed to the (mock) client.
+pub fn verify_recursion_proof(
+    client: &RecursionProver,
+    proof: &SP1ProofWithPublicValues,
+    vk: &SP1VerifyingKey,
+    groth16_vk: &[u8],
+) -> Result<(), String> {
+    match &proof.proof {
+        SP1Proof::Groth16(_) if !client.is_mock() => Groth16Verifier::verify(
+            &proof.bytes(),
+            proof.public_values.as_slice(),
+            &vk.bytes32(),
+            groth16_vk,
+        )
+        .map_err(|e| e.to_string()),
+        SP1Proof::Compressed(_) | SP1Proof::Groth16(_) => {
+            client.verify(proof, vk).map_err(|e| e.to_string())
+        }
+        _ => Err("unsupported proof type, expected a compressed or groth16 proof".to_string()),
+    }
+}
+
+/// Reads the genesis a proof must start from, given as a hash in hex or as the path of its header
+/// JSON file.
+pub fn read_genesis_hash(genesis: &str) -> Result<Vec<u8>, String> {
+    let path = Path::new(genesis);
+    let hash = if path.is_file() {
+        let block = read_light_block_file(path)?;
+        block.signed_header.header().hash().as_bytes().to_vec()
+    } else {
+        public_values::parse_hex(genesis)?
+    };
+    if hash.len() != 32 {
+        return Err(format!(
+            "genesis hash {} is {} bytes, not 32",
+            hex::encode_upper(&hash),
+            hash.len()
+        ));
+    }
+    Ok(hash)
+}
+
+/// Checks that a proof starts from the trusted genesis. A valid proof of another chain, or of a
+/// fork from another header, proves nothing about this one.
+pub fn check_genesis(values: &RecursionPublicValues, genesis_hash: &[u8]) -> Result<(), String> {
+    if values.genesis_hash != genesis_hash {
+        return Err(format!(
+            "proof is from genesis {}, not the trusted genesis {}",
+            hex::encode_upper(&values.genesis_hash),
+            hex::encode_upper(genesis_hash)
+        ));
+    }
+    Ok(())
+}
+
+/// Reads a proof file, verifies it and decodes its public values.
+///
+/// Also checks that the proof commits to the vk it was verified against, which the guest cannot
+/// do by itself, and that it starts from `genesis_hash`.
+pub fn read_verified_proof(
+    path: &Path,
+    client: &RecursionProver,
+    vk: &SP1VerifyingKey,
+    groth16_vk: &[u8],
+    genesis_hash: &[u8],
+) -> Result<(SP1ProofWithPublicValues, RecursionPublicValues), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    let proof: SP1ProofWithPublicValues = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to deserialize proof {:?}: {}", path, e))?;
+
+    verify_recursion_proof(client, &proof, vk, groth16_vk)
+        .map_err(|e| format!("proof verification failed: {}", e))?;
+
+    let values = public_values::decode(proof.public_values.as_slice())?;
+    if values.vk_digest != vk.vk.hash_u32() {
+        return Err("committed vk digest does not match the program vk".to_string());
+    }
+    check_genesis(&values, genesis_hash)?;
+    Ok((proof, values))
+}
+
+/// Checks that a previous proof's public values lead into a hop from `h1_hash`, on the chain from
+/// `genesis_hash` with the given upgrade history. The program would panic on any mismatch.
+pub fn check_previous_values(
+    values: &RecursionPublicValues,
+    genesis_hash: &[u8],
+    h1_hash: &[u8],
+    checkpoints: &[Groth16VkeyCheckpoint],
+) -> Result<(), String> {
+    if values.genesis_hash != genesis_hash {
+        return Err(format!(
+            "previous proof is from genesis {}, not {}",
+            hex::encode_upper(&values.genesis_hash),
+            hex::encode_upper(genesis_hash)
+        ));
+    }
+    if values.h2_hash != h1_hash {
+        return Err(format!(
+            "previous proof ends at {}, not at h1 {}",
+            hex::encode_upper(&values.h2_hash),
+            hex::encode_upper(h1_hash)
+        ));
+    }
+    if values.checkpoints_hash != checkpoint_history_hash(checkpoints) {
+        return Err(
+            "upgrade history does not match the previous proof's checkpoints hash".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Checks that a previous proof of an older program leads into an upgrade at `h1_hash`: it must
+/// commit the history before the last checkpoint, which names h1 and the older program's vk.
+pub fn check_upgrade_values(
+    values: &RecursionPublicValues,
+    genesis_hash: &[u8],
+    h1_hash: &[u8],
+    checkpoints: &[Groth16VkeyCheckpoint],
+) -> Result<(), String> {
+    let Some((incoming, previous_checkpoints)) = checkpoints.split_last() else {
+        return Err("an upgrade needs a checkpoint for it in the upgrade history".to_string());
+    };
+    check_previous_values(values, genesis_hash, h1_hash, previous_checkpoints)?;
+    if incoming.block_hash.as_slice() != h1_hash {
+        return Err("the last checkpoint is not at h1".to_string());
+    }
+    if incoming.program_vk_hash != values.vk_digest {
+        return Err("the last checkpoint does not name the previous proof's vk".to_string());
+    }
+    Ok(())
+}
+
+/// Starts the input of a program that builds on a recursion proof: the proof and its head.
+///
+/// The recursion proof is verified inside the program, so it has to be a compressed proof. Mock
+/// proofs are passed by public values only, since the mock prover skips their verification.
+pub fn recursion_head_stdin(
+    recursion_proof: &SP1ProofWithPublicValues,
+    recursion_vk: &SP1VerifyingKey,
+    head: &Header,
+    mock: bool,
+) -> Result<SP1Stdin, String> {
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&recursion_vk.vk.hash_u32());
+    stdin.write_vec(recursion_proof.public_values.to_vec());
+    stdin.write_vec(serde_cbor::to_vec(head).map_err(|e| e.to_string())?);
+
+    match &recursion_proof.proof {
+        SP1Proof::Compressed(proof) => stdin.write_proof(*proof.clone(), recursion_vk.vk.clone()),
+        _ if mock => {}
+        _ => return Err("the recursion proof must be a compressed proof".to_string()),
+    }
+    Ok(stdin)
+}
+
+/// Reads how the header a program is about is linked to the head of a proof with `values`, and
+/// the headers after the link: the head itself, the last of `ancestors` (as written by
+/// `fetch_ancestors`), the header at `path_height` verified on the way to the head (read from the
+/// headers directory the proof was generated from), or the last of `ancestors` below that header.
+pub fn read_header_link(
+    values: &RecursionPublicValues,
+    headers_dir: Option<&Path>,
+    ancestors: Option<&Path>,
+    path_height: Option<u64>,
+) -> Result<(HeaderLink, Vec<Header>), String> {
+    let ancestors = ancestors.map(read_ancestors).transpose()?;
+    let Some(height) = path_height else {
+        return Ok(match ancestors {
+            Some(ancestors) => (HeaderLink::Ancestors(ancestors.len() as u64), ancestors),
+            None => (HeaderLink::Head, Vec::new()),
+        });
+    };
+    let dir = headers_dir.ok_or("a path header needs the headers directory")?;
+    let (start, proof) = read_path_header(dir, values, height)?;
+    Ok(match ancestors {
+        Some(ancestors) => {
+            let link = HeaderLink::PathAncestors {
+                proof,
+                count: ancestors.len() as u64,
+            };
+            (link, std::iter::once(start).chain(ancestors).collect())
+        }
+        None => (HeaderLink::Path(proof), vec![start]),
+    })
+}
+
+/// Continues the input of a program that reads a header linked to the head: the link, and the
+/// headers after it.
+pub fn linked_header_stdin(
+    stdin: &mut SP1Stdin,
+    link: &HeaderLink,
+    headers: &[Header],
+) -> Result<(), String> {
+    stdin.write(link);
+    for header in headers {
+        stdin.write_vec(serde_cbor::to_vec(header).map_err(|e| e.to_string())?);
+    }
+    Ok(())
+}
@@ -0,0 +1,342 @@
+//! Inputs the recursion program must reject. Each case runs twice: natively through
+//! `consensus::recursion`, and in execute mode through the program itself with the mock prover.
+
+use common::mmr::PathAccumulator;
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint, RecursionPublicValues};
+use consensus::recursion::{verify_hop, HopError, HopInputs, PreviousProof};
+use consensus::testing::{block, AcceptCompressed};
+use runner_verify_consensus_recursion::{
+    hop::{mock_previous_proof, HopInput},
+    path::path_leaf,
+    prover::{ProverMode, RecursionProver},
+    ANCESTOR_HEADER_ELF, CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use sp1_sdk::{HashableKey, SP1PublicValues};
+use tendermint::{block::CommitSig, Signature};
+use tendermint_light_client_verifier::types::LightBlock;
+
+fn hash(block: &LightBlock) -> [u8; 32] {
+    block
+        .signed_header
+        .header()
+        .hash()
+        .as_bytes()
+        .try_into()
+        .unwrap()
+}
+
+/// A hop, with the public values of its previous proof.
+#[derive(Clone)]
+struct Hop {
+    is_upgrade: bool,
+    checkpoints: Vec<Groth16VkeyCheckpoint>,
+    genesis_hash: Vec<u8>,
+    h1: LightBlock,
+    h2: LightBlock,
+    previous_path: PathAccumulator,
+    previous_public_values: Option<Vec<u8>>,
+}
+
+/// A hop that must fail, and the error it must fail with.
+struct Case {
+    name: &'static str,
+    hop: Hop,
+    error: HopError,
+}
+
+/// The public values of a proof of the hop from `genesis` to `h2`.
+fn proven(genesis: &LightBlock, h2: &LightBlock, vk_digest: [u32; 8]) -> RecursionPublicValues {
+    RecursionPublicValues {
+        checkpoints_hash: checkpoint_history_hash(&[]),
+        genesis_hash: hash(genesis).to_vec(),
+        h2_hash: hash(h2).to_vec(),
+        vk_digest,
+        path_root: path(h2).root(),
+    }
+}
+
+fn path(head: &LightBlock) -> PathAccumulator {
+    let mut path = PathAccumulator::default();
+    path.push(path_leaf(head.signed_header.header()).digest());
+    path
+}
+
+/// The hop from 3 to 6, after the hop from genesis at 1 to 3, of the program with `vk_digest`.
+fn valid_hop(vk_digest: [u32; 8]) -> Hop {
+    let genesis = block(1, 10);
+    let h1 = block(3, 30);
+    Hop {
+        is_upgrade: false,
+        checkpoints: Vec::new(),
+        genesis_hash: hash(&genesis).to_vec(),
+        previous_path: path(&h1),
+        previous_public_values: Some(
+            bincode::serialize(&proven(&genesis, &h1, vk_digest)).unwrap(),
+        ),
+        h1,
+        h2: block(6, 60),
+    }
+}
+
+/// The valid hop as an upgrade from the program with `old_vk_digest`, at h1.
+fn upgrade_hop(old_vk_digest: [u32; 8]) -> Hop {
+    let hop = valid_hop(old_vk_digest);
+    Hop {
+        is_upgrade: true,
+        checkpoints: vec![Groth16VkeyCheckpoint {
+            block_hash: hash(&hop.h1),
+            groth16_vk: None,
+            program_vk_hash: old_vk_digest,
+        }],
+        ..hop
+    }
+}
+
+fn with_previous(hop: Hop, values: RecursionPublicValues) -> Hop {
+    Hop {
+        previous_public_values: Some(bincode::serialize(&values).unwrap()),
+        ..hop
+    }
+}
+
+fn cases(vk_digest: [u32; 8]) -> Vec<Case> {
+    let valid = valid_hop(vk_digest);
+    let genesis = block(1, 10);
+    let other_genesis = block(1, 11);
+    let old_vk_digest = [9; 8];
+
+    let mut tampered = valid.h2.clone();
+    for signature in tampered.signed_header.commit.signatures.iter_mut() {
+        if let CommitSig::BlockIdFlagCommit {
+            signature: Some(signature),
+            ..
+        } = signature
+        {
+            let mut bytes = signature.as_bytes().to_vec();
+            bytes[0] ^= 1;
+            *signature = Signature::try_from(bytes.as_slice()).unwrap();
+        }
+    }
+
+    let mut wrong_block = upgrade_hop(old_vk_digest);
+    wrong_block.checkpoints[0].block_hash = hash(&genesis);
+    let mut wrong_vk = upgrade_hop(old_vk_digest);
+    wrong_vk.checkpoints[0].program_vk_hash = [8; 8];
+    // The previous proof is compressed, so there is no Groth16 proof for the checkpoint's vk.
+    let mut missing_groth16 = upgrade_hop(old_vk_digest);
+    missing_groth16.checkpoints[0].groth16_vk = Some(sp1_verifier::GROTH16_VK_BYTES.to_vec());
+
+    let genesis_hop = Hop {
+        h1: genesis.clone(),
+        h2: valid.h1.clone(),
+        previous_path: PathAccumulator::default(),
+        previous_public_values: None,
+        ..valid.clone()
+    };
+
+    vec![
+        Case {
+            name: "tampered h2 signatures",
+            hop: Hop {
+                h2: tampered,
+                ..valid.clone()
+            },
+            error: HopError::Consensus(String::new()),
+        },
+        Case {
+            name: "hop beyond the trusting period",
+            hop: Hop {
+                h2: block(6, 30 + common::TRUSTING_PERIOD_SECS as i64),
+                ..valid.clone()
+            },
+            error: HopError::Consensus(String::new()),
+        },
+        Case {
+            name: "genesis hop from a block other than the genesis",
+            hop: Hop {
+                genesis_hash: hash(&other_genesis).to_vec(),
+                ..genesis_hop.clone()
+            },
+            error: HopError::MissingPreviousProof,
+        },
+        Case {
+            name: "genesis hop with a previous proof",
+            hop: Hop {
+                previous_public_values: valid.previous_public_values.clone(),
+                ..genesis_hop.clone()
+            },
+            error: HopError::UnexpectedPreviousProof,
+        },
+        Case {
+            name: "missing previous proof",
+            hop: Hop {
+                previous_public_values: None,
+                ..valid.clone()
+            },
+            error: HopError::MissingPreviousProof,
+        },
+        Case {
+            name: "previous proof from a different genesis",
+            hop: with_previous(valid.clone(), proven(&other_genesis, &valid.h1, vk_digest)),
+            error: HopError::Genesis,
+        },
+        Case {
+            name: "genesis hop with a non-empty path",
+            hop: Hop {
+                previous_path: path(&valid.h1),
+                ..genesis_hop
+            },
+            error: HopError::GenesisPath,
+        },
+        Case {
+            name: "malformed previous public values",
+            hop: Hop {
+                previous_public_values: Some(vec![1, 2, 3]),
+                ..valid.clone()
+            },
+            error: HopError::MalformedPublicValues,
+        },
+        Case {
+            name: "path accumulator not of the previous proof",
+            hop: Hop {
+                previous_path: path(&genesis),
+                ..valid.clone()
+            },
+            error: HopError::PathRoot,
+        },
+        Case {
+            name: "mismatched vk digest",
+            hop: with_previous(valid.clone(), proven(&genesis, &valid.h1, old_vk_digest)),
+            error: HopError::Vk,
+        },
+        Case {
+            name: "checkpoints on a non-upgrade hop",
+            hop: Hop {
+                checkpoints: upgrade_hop(vk_digest).checkpoints,
+                ..valid.clone()
+            },
+            error: HopError::Checkpoints,
+        },
+        Case {
+            name: "upgrade without an incoming checkpoint",
+            hop: Hop {
+                checkpoints: Vec::new(),
+                ..upgrade_hop(old_vk_digest)
+            },
+            error: HopError::UpgradeCheckpoints,
+        },
+        Case {
+            name: "upgrade checkpoint from another program",
+            hop: wrong_vk,
+            error: HopError::UpgradeVk,
+        },
+        Case {
+            name: "upgrade checkpoint block hash not h1",
+            hop: wrong_block,
+            error: HopError::UpgradeBlock,
+        },
+        Case {
+            name: "upgrade from a Groth16 checkpoint without a Groth16 proof",
+            hop: missing_groth16,
+            error: HopError::Proof(String::new()),
+        },
+    ]
+}
+
+fn verify_natively(hop: &Hop, vk_digest: [u32; 8]) -> Result<RecursionPublicValues, HopError> {
+    let inputs = HopInputs {
+        is_upgrade: hop.is_upgrade,
+        checkpoints: hop.checkpoints.clone(),
+        genesis_hash: hop.genesis_hash.clone(),
+        h1: hop.h1.clone(),
+        h2: hop.h2.clone(),
+        vk_digest,
+        previous_path: hop.previous_path.clone(),
+        previous: hop
+            .previous_public_values
+            .clone()
+            .map(|public_values| PreviousProof {
+                groth16_proof: Vec::new(),
+                public_values,
+            }),
+    };
+    verify_hop(&inputs, &AcceptCompressed)
+}
+
+#[test]
+fn every_case_fails_natively() {
+    let vk_digest = [1; 8];
+    assert!(verify_natively(&valid_hop(vk_digest), vk_digest).is_ok());
+    assert!(verify_natively(&upgrade_hop([9; 8]), vk_digest).is_ok());
+
+    for case in cases(vk_digest) {
+        match verify_natively(&case.hop, vk_digest) {
+            Ok(_) => panic!("{}: verified", case.name),
+            Err(error) => assert_eq!(
+                std::mem::discriminant(&error),
+                std::mem::discriminant(&case.error),
+                "{}: {}",
+                case.name,
+                error
+            ),
+        }
+    }
+}
+
+#[test]
+fn every_case_fails_in_the_program() {
+    let client = RecursionProver::new(ProverMode::Mock, None).unwrap();
+    let (pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    // The mock client does not verify the proofs the program recurses on, so any other program's
+    // vk stands in for the one upgraded from.
+    let (_, old_vk) = client.setup(ANCESTOR_HEADER_ELF);
+    let execute = |hop: &Hop| {
+        let previous_proof = hop.previous_public_values.as_ref().map(|public_values| {
+            mock_previous_proof(&pk, SP1PublicValues::from(public_values.as_slice()))
+        });
+        HopInput {
+            is_upgrade: hop.is_upgrade,
+            checkpoints: &hop.checkpoints,
+            genesis_hash: &hop.genesis_hash,
+            h1: &hop.h1,
+            h2: &hop.h2,
+            previous_path: &hop.previous_path,
+            previous_proof: previous_proof.as_ref(),
+            previous_vk: Some(&old_vk),
+        }
+        .execute(&client, &vk)
+    };
+
+    let vk_digest = vk.vk.hash_u32();
+    for hop in [valid_hop(vk_digest), upgrade_hop([9; 8])] {
+        let (public_values, _) = execute(&hop).unwrap();
+        assert_eq!(
+            bincode::deserialize::<RecursionPublicValues>(public_values.as_slice()).unwrap(),
+            verify_natively(&hop, vk_digest).unwrap()
+        );
+    }
+
+    for case in cases(vk_digest) {
+        // The program reads a previous proof exactly when h1 is not genesis. So it never sees one
+        // it does not expect, and fails reading its input when one it expects is missing.
+        match case.error {
+            HopError::UnexpectedPreviousProof => continue,
+            HopError::MissingPreviousProof => {
+                assert!(execute(&case.hop).is_err(), "{}: executed", case.name);
+                continue;
+            }
+            _ => {}
+        }
+        // The program panics with the error's message, less any detail of why a proof or
+        // consensus failed.
+        let message = match &case.error {
+            HopError::Consensus(_) => "Verification failed".to_string(),
+            HopError::Proof(_) => "Failed to verify previous proof".to_string(),
+            error => error.to_string(),
+        };
+        match execute(&case.hop) {
+            Ok(_) => panic!("{}: executed", case.name),
+            Err(error) => assert!(error.contains(&message), "{}: {}", case.name, error),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use runner_verify_consensus_recursion::ancestors::check_ancestors;
+use tendermint::block::Header;
+use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+
+/// Headers at heights 1 to `count`, each linked to the one before, newest first.
+fn chain(count: u64) -> Vec<Header> {
+    let mut block = TestgenLightBlock::new_default(1);
+    let mut headers = Vec::new();
+    for _ in 0..count {
+        headers.push(block.generate().unwrap().signed_header.header);
+        block = block.next();
+    }
+    headers.reverse();
+    headers
+}
+
+#[test]
+fn chain_reaches_target() {
+    let headers = chain(5);
+    let target = check_ancestors(&headers[0], &headers[1..]).unwrap();
+    assert_eq!(target.height.value(), 1);
+    assert_eq!(target.hash(), headers[4].hash());
+}
+
+#[test]
+fn no_ancestors_is_the_head() {
+    let headers = chain(2);
+    let target = check_ancestors(&headers[0], &[]).unwrap();
+    assert_eq!(target.hash(), headers[0].hash());
+}
+
+#[test]
+fn changed_ancestor_is_rejected() {
+    let mut headers = chain(4);
+    headers[2].app_hash = vec![0xAA; 32].try_into().unwrap();
+    let err = check_ancestors(&headers[0], &headers[1..]).unwrap_err();
+    assert_eq!(err, "header 2 is not the last block of header 3");
+}
+
+#[test]
+fn skipped_ancestor_is_rejected() {
+    let mut headers = chain(4);
+    headers.remove(1);
+    let err = check_ancestors(&headers[0], &headers[1..]).unwrap_err();
+    assert_eq!(err, "header 2 is not the last block of header 4");
+}
@@ -0,0 +1,73 @@
+use inclusion::{verify_blob_inclusion, InclusionError};
+use runner_verify_consensus_recursion::{
+    blob::{read_blob, read_share_proof},
+    celestia::DataAvailabilityHeader,
+};
+use std::path::{Path, PathBuf};
+
+// A blob of three shares in a 2x2 square, with its share proof and the square's DAH, taken from
+// the celestia-types test suite.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn data_root() -> [u8; 32] {
+    let dah: DataAvailabilityHeader =
+        serde_json::from_str(&std::fs::read_to_string(fixture("dah.json")).unwrap()).unwrap();
+    dah.hash().unwrap()
+}
+
+#[test]
+fn blob_is_included() {
+    // read_blob checks the commitment celestia-node reports against the one computed here.
+    let blob = read_blob(&fixture("blob.json")).unwrap();
+    let proof = read_share_proof(&fixture("share_proof.json"), &blob).unwrap();
+
+    let commitment = verify_blob_inclusion(&blob, &proof, &data_root()).unwrap();
+    assert_eq!(commitment, blob.commitment().unwrap());
+}
+
+#[test]
+fn other_data_root_is_rejected() {
+    let blob = read_blob(&fixture("blob.json")).unwrap();
+    let proof = read_share_proof(&fixture("share_proof.json"), &blob).unwrap();
+
+    let mut root = data_root();
+    root[0] ^= 1;
+    assert_eq!(
+        verify_blob_inclusion(&blob, &proof, &root),
+        Err(InclusionError::InvalidMerkleProof)
+    );
+}
+
+#[test]
+fn other_blob_is_rejected() {
+    let blob = read_blob(&fixture("blob.json")).unwrap();
+    let proof = read_share_proof(&fixture("share_proof.json"), &blob).unwrap();
+
+    let mut other = blob.clone();
+    other.data[100] ^= 1;
+    assert!(read_share_proof(&fixture("share_proof.json"), &other).is_err());
+    assert!(verify_blob_inclusion(&other, &proof, &data_root()).is_err());
+
+    let mut longer = blob;
+    longer.data.push(0);
+    assert!(verify_blob_inclusion(&longer, &proof, &data_root()).is_err());
+}
+
+#[test]
+fn gaps_between_rows_are_rejected() {
+    let blob = read_blob(&fixture("blob.json")).unwrap();
+    let mut proof = read_share_proof(&fixture("share_proof.json"), &blob).unwrap();
+
+    // The blob starts at share 1 of row 0 and fills row 1, so row 0 has to run to its end.
+    proof.share_proofs[0].end = 1;
+    assert_eq!(
+        verify_blob_inclusion(&blob, &proof, &data_root()),
+        Err(InclusionError::InvalidShareProof(
+            "shares are not consecutive"
+        ))
+    );
+}
@@ -0,0 +1,83 @@
+use common::RecursionPublicValues;
+use runner_verify_consensus_recursion::bootstrap::{trusted_state_bundle, TrustedStateBundle};
+use runner_verify_consensus_recursion::verify::read_genesis_hash;
+use scraper::synthetic::{generate_chain, ChainSpec};
+use std::fs;
+use tendermint_light_client_verifier::types::LightBlock;
+
+fn chain() -> Vec<LightBlock> {
+    generate_chain(&ChainSpec {
+        blocks: 10,
+        ..Default::default()
+    })
+}
+
+fn hash(block: &LightBlock) -> Vec<u8> {
+    block.signed_header.header.hash().as_bytes().to_vec()
+}
+
+fn values(genesis: &LightBlock, head: &LightBlock) -> RecursionPublicValues {
+    RecursionPublicValues {
+        checkpoints_hash: [0; 32],
+        genesis_hash: hash(genesis),
+        h2_hash: hash(head),
+        vk_digest: [1; 8],
+        path_root: [2; 32],
+    }
+}
+
+#[test]
+fn genesis_is_a_hash_or_a_header_file() {
+    let blocks = chain();
+    let genesis = hash(&blocks[0]);
+
+    assert_eq!(read_genesis_hash(&hex::encode(&genesis)).unwrap(), genesis);
+    let prefixed = format!("0x{}", hex::encode_upper(&genesis));
+    assert_eq!(read_genesis_hash(&prefixed).unwrap(), genesis);
+
+    let path = std::env::temp_dir().join(format!("genesis-{}.json", std::process::id()));
+    fs::write(&path, serde_json::to_string(&blocks[0]).unwrap()).unwrap();
+    assert_eq!(read_genesis_hash(path.to_str().unwrap()).unwrap(), genesis);
+    fs::remove_file(&path).unwrap();
+
+    assert!(read_genesis_hash("abcd").is_err());
+    assert!(read_genesis_hash("/no/such/block_1.json").is_err());
+}
+
+#[test]
+fn bundle_rejects_another_genesis() {
+    let blocks = chain();
+    let values = values(&blocks[0], &blocks[9]);
+
+    let e = trusted_state_bundle(&values, &hash(&blocks[1]), blocks[9].clone()).unwrap_err();
+    assert!(e.contains("not the trusted genesis"), "{}", e);
+    assert!(trusted_state_bundle(&values, &hash(&blocks[0]), blocks[9].clone()).is_ok());
+}
+
+#[test]
+fn bundle_rejects_another_head() {
+    let blocks = chain();
+    let values = values(&blocks[0], &blocks[9]);
+
+    let e = trusted_state_bundle(&values, &hash(&blocks[0]), blocks[8].clone()).unwrap_err();
+    assert!(e.contains("is not the proven head"), "{}", e);
+}
+
+#[test]
+fn bundle_round_trips() {
+    let blocks = chain();
+    let values = values(&blocks[0], &blocks[9]);
+    let bundle = trusted_state_bundle(&values, &hash(&blocks[0]), blocks[9].clone()).unwrap();
+    assert_eq!(bundle.trusted_height.value(), 10);
+    assert_eq!(bundle.trusted_hash.as_bytes(), hash(&blocks[9]));
+
+    let json = serde_json::to_string_pretty(&bundle).unwrap();
+    let read: TrustedStateBundle = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.trusted_height, bundle.trusted_height);
+    assert_eq!(read.trusted_hash, bundle.trusted_hash);
+    assert_eq!(read.light_block, bundle.light_block);
+
+    // What was read back still checks out as the proven head.
+    let again = trusted_state_bundle(&values, &hash(&blocks[0]), read.light_block).unwrap();
+    assert_eq!(again.trusted_hash, bundle.trusted_hash);
+}
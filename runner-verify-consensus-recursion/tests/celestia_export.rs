@@ -0,0 +1,109 @@
+use runner_verify_consensus_recursion::celestia::{
+    celestia_node_config, celestia_node_flags, DataAvailabilityHeader, ExtendedHeader, TrustedHead,
+};
+use subtle_encoding::base64;
+use tendermint::{block::signed_header::SignedHeader, Hash};
+use tendermint_light_client_verifier::types::{LightBlock, PeerId};
+use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+
+fn light_block(height: u64) -> LightBlock {
+    let block = TestgenLightBlock::new_default(height).generate().unwrap();
+    LightBlock::new(
+        block.signed_header,
+        block.validators,
+        block.next_validators,
+        block.provider,
+    )
+}
+
+fn dah() -> DataAvailabilityHeader {
+    let root = |byte: u8| String::from_utf8(base64::encode([byte; 90])).unwrap();
+    DataAvailabilityHeader {
+        row_roots: vec![root(1), root(2)],
+        column_roots: vec![root(3), root(4)],
+    }
+}
+
+#[test]
+fn celestia_node_config_sets_trusted_hash() {
+    let block = light_block(7);
+    let header = block.signed_header.header();
+
+    let config = celestia_node_config(header);
+    assert!(config.starts_with("[Header]\n"));
+    assert!(config.contains(&format!("TrustedHash = \"{}\"", header.hash())));
+    assert!(config.contains("# Height 7,"));
+
+    assert_eq!(
+        celestia_node_flags(header),
+        format!("--headers.trusted-hash {}", header.hash())
+    );
+}
+
+#[test]
+fn trusted_head_json() {
+    let block = light_block(7);
+    let header = block.signed_header.header();
+
+    let json = serde_json::to_value(TrustedHead::from(header)).unwrap();
+    assert_eq!(json["hash"], header.hash().to_string());
+    assert_eq!(json["height"], 7);
+    assert_eq!(json["time"], header.time.to_rfc3339());
+}
+
+#[test]
+fn extended_header_round_trips() {
+    let block = light_block(7);
+
+    let extended_header = ExtendedHeader::new(&block, None).unwrap();
+    let json = serde_json::to_string(&extended_header).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value.get("dah").is_none());
+    assert_eq!(value["header"]["height"], "7");
+
+    let parsed: ExtendedHeader = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.header.hash(), block.signed_header.header().hash());
+    assert_eq!(parsed.commit.block_id.hash, parsed.header.hash());
+    assert_eq!(parsed.validator_set.hash(), parsed.header.validators_hash);
+}
+
+#[test]
+fn extended_header_checks_dah_against_data_hash() {
+    let mut block = light_block(7);
+    let dah = dah();
+
+    // Default test headers have no data, so there is nothing to match a DAH against.
+    assert!(ExtendedHeader::new(&block, Some(dah.clone())).is_err());
+
+    let mut header = block.signed_header.header().clone();
+    header.data_hash = Some(Hash::Sha256(dah.hash().unwrap()));
+    block.signed_header = SignedHeader::new(header, block.signed_header.commit().clone()).unwrap();
+    let extended_header = ExtendedHeader::new(&block, Some(dah.clone())).unwrap();
+    assert_eq!(extended_header.dah, Some(dah.clone()));
+
+    let mut other_dah = dah;
+    other_dah.row_roots.swap(0, 1);
+    assert!(ExtendedHeader::new(&block, Some(other_dah)).is_err());
+}
+
+/// Height 27 of a celestia-node devnet, in celestia-node's own `ExtendedHeader` JSON, as recorded
+/// in the `celestia-types` crate's test data.
+const RECORDED: &str = include_str!("fixtures/extended_header.json");
+
+#[test]
+fn extended_header_matches_celestia_node() {
+    let recorded: ExtendedHeader = serde_json::from_str(RECORDED).unwrap();
+    assert_eq!(recorded.header.height.value(), 27);
+    assert_eq!(recorded.commit.height.value(), 27);
+    let block = LightBlock::new(
+        SignedHeader::new(recorded.header, recorded.commit).unwrap(),
+        recorded.validator_set.clone(),
+        recorded.validator_set,
+        PeerId::new([0; 20]),
+    );
+
+    let extended_header = ExtendedHeader::new(&block, recorded.dah).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(RECORDED).unwrap();
+    assert_eq!(serde_json::to_value(&extended_header).unwrap(), expected);
+}
@@ -0,0 +1,44 @@
+use common::checkpoint_history_hash;
+use runner_verify_consensus_recursion::checkpoints::{read_checkpoints, replay_checkpoint_history};
+use std::path::Path;
+
+fn checkpoints() -> Vec<common::Groth16VkeyCheckpoint> {
+    read_checkpoints(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/checkpoints.json"))
+        .unwrap()
+}
+
+#[test]
+fn replay_ends_at_the_history_hash() {
+    let checkpoints = checkpoints();
+    let hashes = replay_checkpoint_history(&checkpoints);
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(hashes[0], checkpoint_history_hash(&checkpoints[..1]));
+    assert_eq!(hashes[1], checkpoint_history_hash(&checkpoints));
+}
+
+#[test]
+fn no_upgrades_hash_to_zero() {
+    assert!(replay_checkpoint_history(&[]).is_empty());
+    assert_eq!(checkpoint_history_hash(&[]), [0; 32]);
+}
+
+#[test]
+fn history_hash_covers_every_field() {
+    let hash = checkpoint_history_hash(&checkpoints());
+
+    let mut changed = checkpoints();
+    changed[0].groth16_vk.as_mut().unwrap().push(0);
+    assert_ne!(checkpoint_history_hash(&changed), hash);
+
+    let mut changed = checkpoints();
+    changed[1].groth16_vk = Some(Vec::new());
+    assert_ne!(checkpoint_history_hash(&changed), hash);
+
+    let mut changed = checkpoints();
+    changed[1].program_vk_hash[7] += 1;
+    assert_ne!(checkpoint_history_hash(&changed), hash);
+
+    let mut changed = checkpoints();
+    changed.swap(0, 1);
+    assert_ne!(checkpoint_history_hash(&changed), hash);
+}
@@ -0,0 +1,97 @@
+use common::{TRUSTING_PERIOD_SECS, TRUST_THRESHOLD};
+use ibc_proto::ibc::{
+    core::client::v1::MsgCreateClient,
+    lightclients::tendermint::v1::{ClientState, ConsensusState},
+};
+use prost::Message;
+use runner_verify_consensus_recursion::ibc::{
+    client_state, consensus_state, msg_create_client, revision_number, ClientParams,
+};
+use tendermint::block::Header;
+use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+
+const PARAMS: ClientParams = ClientParams {
+    unbonding_period_secs: 21 * 24 * 60 * 60,
+    max_clock_drift_secs: 10,
+};
+
+fn header(height: u64) -> Header {
+    let block = TestgenLightBlock::new_default(height).generate().unwrap();
+    block.signed_header.header
+}
+
+#[test]
+fn revision_number_follows_chain_id_suffix() {
+    assert_eq!(revision_number("celestia"), 0);
+    assert_eq!(revision_number("mocha-4"), 4);
+    assert_eq!(revision_number("arabica-11"), 11);
+    assert_eq!(revision_number("test-chain-01"), 0);
+    assert_eq!(revision_number("-4"), 0);
+}
+
+#[test]
+fn client_state_uses_proof_policy() {
+    let header = header(12);
+    let state = client_state(&header, &PARAMS).unwrap();
+
+    assert_eq!(state.chain_id, header.chain_id.to_string());
+    let trust_level = state.trust_level.unwrap();
+    assert_eq!(
+        (trust_level.numerator, trust_level.denominator),
+        TRUST_THRESHOLD
+    );
+    assert_eq!(
+        state.trusting_period.unwrap().seconds as u64,
+        TRUSTING_PERIOD_SECS
+    );
+    assert_eq!(state.latest_height.unwrap().revision_height, 12);
+    assert_eq!(state.proof_specs.len(), 2);
+}
+
+#[test]
+fn client_state_rejects_short_unbonding_period() {
+    let params = ClientParams {
+        unbonding_period_secs: TRUSTING_PERIOD_SECS,
+        ..PARAMS
+    };
+    assert!(client_state(&header(3), &params).is_err());
+}
+
+#[test]
+fn msg_create_client_round_trips() {
+    let header = header(5);
+    let client = client_state(&header, &PARAMS).unwrap();
+    let consensus = consensus_state(&header);
+    assert_eq!(
+        consensus.root.as_ref().unwrap().hash,
+        header.app_hash.as_bytes()
+    );
+    assert_eq!(
+        consensus.next_validators_hash,
+        header.next_validators_hash.as_bytes()
+    );
+
+    let msg = msg_create_client(&client, &consensus, "cosmos1signer");
+    let decoded = MsgCreateClient::decode(msg.encode_to_vec().as_slice()).unwrap();
+    assert_eq!(decoded.signer, "cosmos1signer");
+
+    let client_any = decoded.client_state.unwrap();
+    assert_eq!(
+        client_any.type_url,
+        "/ibc.lightclients.tendermint.v1.ClientState"
+    );
+    assert_eq!(
+        ClientState::decode(client_any.value.as_slice()).unwrap(),
+        client
+    );
+
+    let consensus_any = decoded.consensus_state.unwrap();
+    assert_eq!(
+        consensus_any.type_url,
+        "/ibc.lightclients.tendermint.v1.ConsensusState"
+    );
+    assert_eq!(
+        ConsensusState::decode(consensus_any.value.as_slice()).unwrap(),
+        consensus
+    );
+}
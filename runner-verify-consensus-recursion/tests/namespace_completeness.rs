@@ -0,0 +1,111 @@
+use inclusion::{verify_namespace_data, InclusionError, NamespaceDataProof};
+use runner_verify_consensus_recursion::{
+    blob::{parse_namespace, read_namespace_data},
+    celestia::DataAvailabilityHeader,
+};
+use std::path::{Path, PathBuf};
+
+// A 4x4 square built with celestia-types. Namespace 2 spans rows 0 and 1 with two blobs, the
+// second with a signer, and a padding share between them. Namespace 3 falls inside row 1's range
+// without any data.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn dah() -> DataAvailabilityHeader {
+    serde_json::from_str(&std::fs::read_to_string(fixture("namespace_dah.json")).unwrap()).unwrap()
+}
+
+fn proof(name: &str) -> NamespaceDataProof {
+    read_namespace_data(&fixture(name), &dah()).unwrap()
+}
+
+fn commitment(base64: &str) -> [u8; 32] {
+    subtle_encoding::base64::decode(base64)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn all_blobs_of_a_namespace() {
+    let commitments = verify_namespace_data(
+        &proof("namespace_data.json"),
+        &dah().hash().unwrap(),
+        &parse_namespace("02").unwrap(),
+    )
+    .unwrap();
+    // Computed with celestia-types for the same blobs.
+    assert_eq!(
+        commitments,
+        vec![
+            commitment("FIPR6euVs5Q4JhdVuOuqCWEdjVnVJmfSgd6qdF5/KA8="),
+            commitment("47RbUGgXl5GW02pCLWH60UnRWUZqA2mHkr1nSXknJJg="),
+        ]
+    );
+}
+
+#[test]
+fn empty_namespaces() {
+    let data_root = dah().hash().unwrap();
+
+    // Row 1 covers namespace 3, so its absence has to be proven there.
+    let absent = proof("absent_namespace_data.json");
+    assert_eq!(absent.rows.len(), 1);
+    let commitments =
+        verify_namespace_data(&absent, &data_root, &parse_namespace("03").unwrap()).unwrap();
+    assert!(commitments.is_empty());
+
+    // No row covers namespace 5, so no row proofs are needed.
+    let mut beyond = absent;
+    beyond.rows.clear();
+    let commitments =
+        verify_namespace_data(&beyond, &data_root, &parse_namespace("05").unwrap()).unwrap();
+    assert!(commitments.is_empty());
+
+    // But they are for namespace 3.
+    assert_eq!(
+        verify_namespace_data(&beyond, &data_root, &parse_namespace("03").unwrap()),
+        Err(InclusionError::InvalidNamespaceProof("missing a row"))
+    );
+}
+
+#[test]
+fn withheld_data_is_rejected() {
+    let data_root = dah().hash().unwrap();
+    let namespace = parse_namespace("02").unwrap();
+
+    let mut missing_row = proof("namespace_data.json");
+    missing_row.rows.pop();
+    assert!(verify_namespace_data(&missing_row, &data_root, &namespace).is_err());
+
+    let mut missing_share = proof("namespace_data.json");
+    missing_share.rows[1].shares.pop();
+    missing_share.rows[1].proof.end -= 1;
+    assert_eq!(
+        verify_namespace_data(&missing_share, &data_root, &namespace),
+        Err(InclusionError::InvalidNamespaceProof(
+            "NMT proof does not verify"
+        ))
+    );
+
+    // The absence proof of namespace 3 doesn't prove namespace 2 empty.
+    let absent = proof("absent_namespace_data.json");
+    assert!(verify_namespace_data(&absent, &data_root, &namespace).is_err());
+}
+
+#[test]
+fn other_data_root_is_rejected() {
+    let mut root = dah().hash().unwrap();
+    root[0] ^= 1;
+    assert_eq!(
+        verify_namespace_data(
+            &proof("namespace_data.json"),
+            &root,
+            &parse_namespace("02").unwrap()
+        ),
+        Err(InclusionError::InvalidMerkleProof)
+    );
+}
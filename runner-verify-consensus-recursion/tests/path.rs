@@ -0,0 +1,106 @@
+use common::mmr::{PathAccumulator, PathLeaf};
+use common::RecursionPublicValues;
+use consensus::head::linked_header;
+use runner_verify_consensus_recursion::path::{path_leaf, prove_path_header};
+use runner_verify_consensus_recursion::verify::read_header_link;
+use scraper::headers_dir::save_light_block;
+use scraper::synthetic::{generate_chain, ChainSpec};
+use std::path::Path;
+use tendermint::block::Header;
+use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+
+/// Leaves for the heads of a path of `count` hops, at heights 2, 4, 6 and so on.
+fn leaves(count: u64) -> Vec<PathLeaf> {
+    (1..=count)
+        .map(|hop| {
+            let block = TestgenLightBlock::new_default(2 * hop).generate().unwrap();
+            path_leaf(&block.signed_header.header)
+        })
+        .collect()
+}
+
+fn root(leaves: &[PathLeaf]) -> [u8; 32] {
+    let digests: Vec<[u8; 32]> = leaves.iter().map(PathLeaf::digest).collect();
+    PathAccumulator::from_leaves(&digests).root()
+}
+
+#[test]
+fn leaf_describes_the_header() {
+    let block = TestgenLightBlock::new_default(7).generate().unwrap();
+    let header = block.signed_header.header;
+    let leaf = path_leaf(&header);
+    assert_eq!(leaf.hash, header.hash().as_bytes());
+    assert_eq!(leaf.height, 7);
+    assert_eq!(leaf.time_nanos, header.time.unix_timestamp_nanos());
+}
+
+#[test]
+fn every_head_on_the_path_is_proven() {
+    let leaves = leaves(6);
+    let root = root(&leaves);
+    for (index, leaf) in leaves.iter().enumerate() {
+        let inclusion = prove_path_header(&leaves, leaf.height, root).unwrap();
+        assert_eq!(inclusion.proof.index, index as u64);
+        assert_eq!(&inclusion.leaf, leaf);
+        assert!(inclusion.verify());
+    }
+}
+
+#[test]
+fn height_off_the_path_is_rejected() {
+    let leaves = leaves(3);
+    let err = prove_path_header(&leaves, 3, root(&leaves)).unwrap_err();
+    assert_eq!(err, "no header at height 3 in the path");
+}
+
+#[test]
+fn changed_leaf_does_not_verify() {
+    let leaves = leaves(5);
+    let mut inclusion = prove_path_header(&leaves, 4, root(&leaves)).unwrap();
+    inclusion.leaf.time_nanos += 1;
+    assert!(!inclusion.verify());
+}
+
+#[test]
+fn header_link_starts_from_the_head_or_a_path_header() {
+    let dir = std::env::temp_dir().join(format!("header-link-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let blocks = generate_chain(&ChainSpec {
+        blocks: 10,
+        ..Default::default()
+    });
+    for block in &blocks {
+        save_light_block(block, &dir).unwrap();
+    }
+    let headers: Vec<Header> = blocks
+        .iter()
+        .map(|b| b.signed_header.header.clone())
+        .collect();
+    // One hop per block.
+    let values = RecursionPublicValues {
+        checkpoints_hash: [0; 32],
+        genesis_hash: headers[0].hash().as_bytes().to_vec(),
+        h2_hash: headers[9].hash().as_bytes().to_vec(),
+        vk_digest: [1; 8],
+        path_root: root(&headers[1..].iter().map(path_leaf).collect::<Vec<_>>()),
+    };
+    let ancestors = dir.join("ancestors.json");
+    let below_six = vec![headers[4].clone(), headers[3].clone()];
+    std::fs::write(&ancestors, serde_json::to_string(&below_six).unwrap()).unwrap();
+
+    let linked = |ancestors: Option<&Path>, path_height: Option<u64>| {
+        let (link, linked) = read_header_link(&values, Some(&dir), ancestors, path_height)?;
+        linked_header(&headers[9], &values.path_root, &link, &linked)
+            .map(|header| header.height.value())
+            .map_err(|e| e.to_string())
+    };
+    assert_eq!(linked(None, None), Ok(10));
+    assert_eq!(linked(None, Some(6)), Ok(6));
+    assert_eq!(linked(Some(&ancestors), Some(6)), Ok(4));
+    // The ancestors do not start below the head.
+    assert!(linked(Some(&ancestors), None).is_err());
+
+    let err = read_header_link(&values, None, None, Some(6)).unwrap_err();
+    assert_eq!(err, "a path header needs the headers directory");
+}
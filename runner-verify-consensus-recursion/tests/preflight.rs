@@ -0,0 +1,77 @@
+use common::mmr::PathAccumulator;
+use runner_verify_consensus_recursion::{
+    path::path_leaf,
+    preflight::{expected_public_values, proving_cost},
+    prover::panic_message,
+    public_values,
+};
+use tendermint::Time;
+use tendermint_light_client_verifier::types::LightBlock;
+use tendermint_testgen::{Generator, Header, LightBlock as TestgenLightBlock, Validator};
+
+fn light_block(height: u64) -> LightBlock {
+    let validators = [Validator::new("a"), Validator::new("b")];
+    let header = Header::new(&validators)
+        .height(height)
+        .chain_id("test-chain")
+        .time(Time::from_unix_timestamp(height as i64, 0).unwrap());
+    let block = TestgenLightBlock::new_default_with_header(header)
+        .generate()
+        .unwrap();
+    LightBlock::new(
+        block.signed_header,
+        block.validators,
+        block.next_validators,
+        block.provider,
+    )
+}
+
+#[test]
+fn expected_public_values_decode() {
+    let genesis = light_block(1);
+    let h2 = light_block(4);
+    let genesis_hash = genesis.signed_header.header().hash();
+    let mut path = PathAccumulator::default();
+    path.push(path_leaf(h2.signed_header.header()).digest());
+
+    let expected = expected_public_values(&[], genesis_hash.as_bytes(), &h2, [7; 8], &path);
+    let values = public_values::decode(expected.as_slice()).unwrap();
+    assert_eq!(values.checkpoints_hash, [0; 32]);
+    assert_eq!(values.genesis_hash, genesis_hash.as_bytes());
+    assert_eq!(values.h2_hash, h2.signed_header.header().hash().as_bytes());
+    assert_eq!(values.vk_digest, [7; 8]);
+    assert_eq!(values.path_root, path.root());
+}
+
+#[test]
+fn panic_message_has_message_and_location() {
+    let stderr = "thread 'main' panicked at src/main.rs:166:13:\n\
+                  Vkey must match previous proof's vkey, except for upgrades\n\
+                  note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+    assert_eq!(
+        panic_message(stderr).unwrap(),
+        "Vkey must match previous proof's vkey, except for upgrades (at src/main.rs:166:13)"
+    );
+}
+
+#[test]
+fn panic_message_keeps_every_line() {
+    let stderr = "some output\nthread 'main' panicked at src/main.rs:45:55:\n\
+                  couldn't decode h1: Invalid(\"option tag\")\nsecond line\n";
+    assert_eq!(
+        panic_message(stderr).unwrap(),
+        "couldn't decode h1: Invalid(\"option tag\")\nsecond line (at src/main.rs:45:55)"
+    );
+}
+
+#[test]
+fn no_panic_no_message() {
+    assert_eq!(panic_message(""), None);
+    assert_eq!(panic_message("just some output\n"), None);
+}
+
+#[test]
+fn cost_is_priced_per_billion_gas() {
+    assert_eq!(proving_cost(2_000_000_000, 0.5), 1.0);
+    assert_eq!(proving_cost(0, 0.5), 0.0);
+}
@@ -0,0 +1,221 @@
+use common::mmr::PathAccumulator;
+use common::{
+    checkpoint_history_hash, Groth16VkeyCheckpoint, LegacyRecursionPublicValues,
+    RecursionPublicValues,
+};
+use consensus::testing::block;
+use runner_verify_consensus_recursion::{
+    checkpoints::read_checkpoints,
+    hop::{mock_previous_proof, HopInput},
+    path::path_leaf,
+    prover::{ProverMode, RecursionProver},
+    public_values::decode,
+    verify::{check_previous_values, check_upgrade_values, read_verified_proof},
+    ANCESTOR_HEADER_ELF, CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use sp1_sdk::{HashableKey, SP1PublicValues, SP1VerifyingKey};
+use std::fs;
+use std::path::Path;
+use tendermint_light_client_verifier::types::LightBlock;
+
+const GENESIS: [u8; 32] = [1; 32];
+const H1: [u8; 32] = [2; 32];
+
+fn values() -> RecursionPublicValues {
+    RecursionPublicValues {
+        checkpoints_hash: [0; 32],
+        genesis_hash: GENESIS.to_vec(),
+        h2_hash: H1.to_vec(),
+        vk_digest: [3; 8],
+        path_root: [4; 32],
+    }
+}
+
+#[test]
+fn previous_proof_ending_at_h1_is_accepted() {
+    assert_eq!(check_previous_values(&values(), &GENESIS, &H1, &[]), Ok(()));
+}
+
+#[test]
+fn previous_proof_from_another_genesis_is_rejected() {
+    let error = check_previous_values(&values(), &[9; 32], &H1, &[]).unwrap_err();
+    assert!(error.contains("genesis"), "{}", error);
+}
+
+#[test]
+fn previous_proof_ending_elsewhere_is_rejected() {
+    let error = check_previous_values(&values(), &GENESIS, &[9; 32], &[]).unwrap_err();
+    assert!(error.contains("not at h1"), "{}", error);
+}
+
+#[test]
+fn upgrade_history_must_match() {
+    let checkpoints = read_checkpoints(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/checkpoints.json"),
+    )
+    .unwrap();
+    let error = check_previous_values(&values(), &GENESIS, &H1, &checkpoints).unwrap_err();
+    assert!(error.contains("upgrade history"), "{}", error);
+
+    let values = RecursionPublicValues {
+        checkpoints_hash: checkpoint_history_hash(&checkpoints),
+        ..values()
+    };
+    assert_eq!(
+        check_previous_values(&values, &GENESIS, &H1, &checkpoints),
+        Ok(())
+    );
+}
+
+#[test]
+fn upgrade_must_follow_the_last_checkpoint() {
+    let checkpoint = |block_hash: [u8; 32], program_vk_hash: [u32; 8]| Groth16VkeyCheckpoint {
+        block_hash,
+        groth16_vk: None,
+        program_vk_hash,
+    };
+    let upgrade = |checkpoints: &[Groth16VkeyCheckpoint]| {
+        check_upgrade_values(&values(), &GENESIS, &H1, checkpoints)
+    };
+
+    assert_eq!(upgrade(&[checkpoint(H1, [3; 8])]), Ok(()));
+    let error = upgrade(&[]).unwrap_err();
+    assert!(error.contains("needs a checkpoint"), "{}", error);
+    let error = upgrade(&[checkpoint([9; 32], [3; 8])]).unwrap_err();
+    assert!(error.contains("not at h1"), "{}", error);
+    let error = upgrade(&[checkpoint(H1, [9; 8])]).unwrap_err();
+    assert!(error.contains("vk"), "{}", error);
+    // The previous proof committed no history, so an earlier checkpoint is not its.
+    let error = upgrade(&[checkpoint([9; 32], [9; 8]), checkpoint(H1, [3; 8])]).unwrap_err();
+    assert!(error.contains("upgrade history"), "{}", error);
+}
+
+#[test]
+fn baseline_proof_is_read_with_its_history_hash() {
+    let checkpoints = read_checkpoints(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/checkpoints.json"),
+    )
+    .unwrap();
+    let baseline = LegacyRecursionPublicValues {
+        checkpoints: checkpoints.clone(),
+        genesis_hash: GENESIS.to_vec(),
+        h2_hash: H1.to_vec(),
+        vk_digest: [3; 8],
+    };
+    let read = decode(&bincode::serialize(&baseline).unwrap()).unwrap();
+    assert_eq!(read.checkpoints_hash, checkpoint_history_hash(&checkpoints));
+    assert_eq!(read.path_root, PathAccumulator::default().root());
+    assert_eq!(
+        check_previous_values(&read, &GENESIS, &H1, &checkpoints),
+        Ok(())
+    );
+
+    let current = values();
+    assert_eq!(decode(&bincode::serialize(&current).unwrap()), Ok(current));
+    let mut trailing = bincode::serialize(&baseline).unwrap();
+    trailing.push(0);
+    assert!(decode(&trailing).is_err());
+}
+
+#[test]
+fn previous_proof_of_another_vk_is_rejected() {
+    let client = RecursionProver::new(ProverMode::Mock, None).unwrap();
+    let (pk, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    let path = std::env::temp_dir().join(format!("previous-proof-{}.json", std::process::id()));
+    let read = |vk_digest: [u32; 8]| {
+        let values = RecursionPublicValues {
+            vk_digest,
+            ..values()
+        };
+        let public_values = SP1PublicValues::from(bincode::serialize(&values).unwrap().as_slice());
+        let proof = mock_previous_proof(&pk, public_values);
+        fs::write(&path, serde_json::to_string(&proof).unwrap()).unwrap();
+        read_verified_proof(
+            &path,
+            &client,
+            &vk,
+            &sp1_verifier::GROTH16_VK_BYTES,
+            &GENESIS,
+        )
+    };
+
+    // The mock proof verifies, but commits the vk of another program.
+    let error = read([9; 8]).unwrap_err();
+    assert_eq!(error, "committed vk digest does not match the program vk");
+    let (_, values) = read(vk.vk.hash_u32()).unwrap();
+    assert_eq!(values.vk_digest, vk.vk.hash_u32());
+    fs::remove_file(&path).unwrap();
+}
+
+fn hash(block: &LightBlock) -> [u8; 32] {
+    block
+        .signed_header
+        .header()
+        .hash()
+        .as_bytes()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn upgrade_hop_executes_on_a_proof_of_the_older_program() {
+    let client = RecursionProver::new(ProverMode::Mock, None).unwrap();
+    let (_, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+    // Any other program stands in for the older one.
+    let (old_pk, old_vk) = client.setup(ANCESTOR_HEADER_ELF);
+
+    let (genesis, h1, h2) = (block(1, 10), block(3, 30), block(6, 60));
+    let mut path = PathAccumulator::default();
+    path.push(path_leaf(h1.signed_header.header()).digest());
+    let previous = RecursionPublicValues {
+        checkpoints_hash: checkpoint_history_hash(&[]),
+        genesis_hash: hash(&genesis).to_vec(),
+        h2_hash: hash(&h1).to_vec(),
+        vk_digest: old_vk.vk.hash_u32(),
+        path_root: path.root(),
+    };
+    let previous_proof = mock_previous_proof(
+        &old_pk,
+        SP1PublicValues::from(bincode::serialize(&previous).unwrap().as_slice()),
+    );
+    let checkpoints = vec![Groth16VkeyCheckpoint {
+        block_hash: hash(&h1),
+        groth16_vk: None,
+        program_vk_hash: old_vk.vk.hash_u32(),
+    }];
+    let genesis_hash = hash(&genesis);
+    let input = HopInput {
+        is_upgrade: true,
+        checkpoints: &checkpoints,
+        genesis_hash: &genesis_hash,
+        h1: &h1,
+        h2: &h2,
+        previous_path: &path,
+        previous_proof: Some(&previous_proof),
+        previous_vk: None,
+    };
+
+    // The proof is of the older program, so it cannot be registered under the current vk.
+    assert!(input.stdin(&vk).is_err());
+    let stdin = HopInput {
+        previous_vk: Some(&old_vk),
+        ..input
+    }
+    .stdin(&vk)
+    .unwrap();
+    let registered = SP1VerifyingKey {
+        vk: stdin.proofs[0].1.clone(),
+    };
+    assert_eq!(registered.hash_u32(), old_vk.vk.hash_u32());
+
+    let (public_values, _) = client
+        .execute(CONSENSUS_VERIFIER_RECURSION_ELF, &stdin)
+        .unwrap();
+    let values: RecursionPublicValues = bincode::deserialize(public_values.as_slice()).unwrap();
+    assert_eq!(values.vk_digest, vk.vk.hash_u32());
+    assert_eq!(
+        values.checkpoints_hash,
+        checkpoint_history_hash(&checkpoints)
+    );
+    assert_eq!(values.h2_hash, hash(&h2));
+}
@@ -0,0 +1,104 @@
+use runner_verify_consensus_recursion::profile::{CycleProfile, HopProfile, Regression};
+
+fn hop(h1: u64, h2: u64, cycles: u64, spans: &[(&str, u64)]) -> HopProfile {
+    HopProfile {
+        h1,
+        h2,
+        cycles,
+        spans: spans
+            .iter()
+            .map(|(span, cycles)| (span.to_string(), *cycles))
+            .collect(),
+    }
+}
+
+fn baseline() -> CycleProfile {
+    CycleProfile::new(vec![
+        hop(1, 2, 1000, &[("read h1", 100), ("verify", 600)]),
+        hop(2, 5, 2000, &[("read h1", 100), ("verify", 1200)]),
+    ])
+}
+
+#[test]
+fn totals_add_up_over_hops() {
+    let profile = baseline();
+    assert_eq!(profile.cycles, 3000);
+    assert_eq!(profile.spans["read h1"], 200);
+    assert_eq!(profile.spans["verify"], 1800);
+}
+
+#[test]
+fn csv_has_a_row_per_span_and_total() {
+    let profile = CycleProfile::new(vec![hop(1, 2, 1000, &[("a, b", 10), ("verify", 600)])]);
+    assert_eq!(
+        profile.to_csv(),
+        "h1,h2,span,cycles\n\
+         1,2,\"a, b\",10\n\
+         1,2,verify,600\n\
+         1,2,total,1000\n\
+         all,all,\"a, b\",10\n\
+         all,all,verify,600\n\
+         all,all,total,1000\n"
+    );
+}
+
+#[test]
+fn json_round_trips() {
+    let profile = baseline();
+    let json = serde_json::to_string(&profile).unwrap();
+    assert_eq!(
+        serde_json::from_str::<CycleProfile>(&json).unwrap(),
+        profile
+    );
+}
+
+#[test]
+fn growth_within_tolerance_is_not_a_regression() {
+    let profile = CycleProfile::new(vec![
+        hop(1, 2, 1009, &[("read h1", 101), ("verify", 600)]),
+        hop(2, 5, 1500, &[("read h1", 90), ("verify", 900)]),
+    ]);
+    assert!(profile.regressions(&baseline(), 1.0).is_empty());
+}
+
+#[test]
+fn growth_beyond_tolerance_is_a_regression() {
+    let profile = CycleProfile::new(vec![
+        hop(1, 2, 1000, &[("read h1", 100), ("verify", 600)]),
+        hop(2, 5, 2100, &[("read h1", 100), ("verify", 1300)]),
+    ]);
+    assert_eq!(
+        profile.regressions(&baseline(), 1.0),
+        vec![
+            Regression {
+                h1: 2,
+                h2: 5,
+                span: "verify".to_string(),
+                baseline: 1200,
+                cycles: 1300,
+            },
+            Regression {
+                h1: 2,
+                h2: 5,
+                span: "total".to_string(),
+                baseline: 2000,
+                cycles: 2100,
+            },
+        ]
+    );
+    assert!(profile.regressions(&baseline(), 10.0).is_empty());
+}
+
+#[test]
+fn new_spans_and_hops_are_not_regressions() {
+    let profile = CycleProfile::new(vec![
+        hop(
+            1,
+            2,
+            1000,
+            &[("read h1", 100), ("verify", 600), ("new span", 50)],
+        ),
+        hop(5, 9, 9000, &[("verify", 9000)]),
+    ]);
+    assert!(profile.regressions(&baseline(), 1.0).is_empty());
+}
@@ -0,0 +1,397 @@
+//! The proof API over a store of a synthetic chain's headers, with Groth16 proofs that carry
+//! public values but no real proof, which a stand-in verifier accepts.
+
+use common::{Groth16VkeyCheckpoint, RecursionPublicValues};
+use consensus::encoding::decode_light_block;
+use reqwest::{header, StatusCode};
+use runner_verify_consensus_recursion::checkpoints::replay_checkpoint_history;
+use runner_verify_consensus_recursion::proof_api::{negotiate, router, Format};
+use runner_verify_consensus_recursion::proof_store::{
+    scan, ProofStore, StoreConfig, StoreVerifier,
+};
+use scraper::headers_dir::save_light_block;
+use scraper::synthetic::{generate_chain, ChainSpec};
+use serde_json::json;
+use sp1_sdk::{SP1Proof, SP1ProofWithPublicValues, SP1PublicValues, SP1_CIRCUIT_VERSION};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tendermint_light_client_verifier::types::LightBlock;
+
+const BINARY: &str = "application/octet-stream";
+
+/// A store with the headers of a synthetic chain and an upgrade history of one checkpoint.
+fn store(name: &str) -> (PathBuf, Vec<LightBlock>) {
+    let dir = std::env::temp_dir().join(format!("proof-store-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("proofs")).unwrap();
+    fs::create_dir_all(dir.join("headers")).unwrap();
+    let blocks = generate_chain(&ChainSpec {
+        blocks: 20,
+        ..Default::default()
+    });
+    for block in &blocks {
+        save_light_block(block, &dir.join("headers")).unwrap();
+    }
+    let checkpoints = vec![Groth16VkeyCheckpoint {
+        block_hash: hash(&blocks[4]).try_into().unwrap(),
+        groth16_vk: None,
+        program_vk_hash: [2; 8],
+    }];
+    fs::write(
+        dir.join("checkpoints.json"),
+        serde_json::to_string(&checkpoints).unwrap(),
+    )
+    .unwrap();
+    (dir, blocks)
+}
+
+fn hash(block: &LightBlock) -> Vec<u8> {
+    block.signed_header.header.hash().as_bytes().to_vec()
+}
+
+fn values(blocks: &[LightBlock], height: u64, checkpoints_hash: [u8; 32]) -> RecursionPublicValues {
+    RecursionPublicValues {
+        checkpoints_hash,
+        genesis_hash: hash(&blocks[0]),
+        h2_hash: hash(&blocks[height as usize - 1]),
+        vk_digest: [1; 8],
+        path_root: [height as u8; 32],
+    }
+}
+
+fn public_values(blocks: &[LightBlock], height: u64, checkpoints_hash: [u8; 32]) -> Vec<u8> {
+    bincode::serialize(&values(blocks, height, checkpoints_hash)).unwrap()
+}
+
+/// Accepts every proof of vk `[1; 8]` but those whose encoded proof is `invalid`.
+struct AcceptValid;
+
+impl StoreVerifier for AcceptValid {
+    fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<[u32; 8], String> {
+        match &proof.proof {
+            SP1Proof::Groth16(groth16) if groth16.encoded_proof == "invalid" => {
+                Err("invalid proof".to_string())
+            }
+            _ => Ok([1; 8]),
+        }
+    }
+}
+
+fn config(blocks: &[LightBlock]) -> StoreConfig {
+    StoreConfig {
+        genesis_hash: hash(&blocks[0]),
+        verifier: Box::new(AcceptValid),
+    }
+}
+
+fn groth16_proof(encoded_proof: &str, public_values: Vec<u8>) -> SP1ProofWithPublicValues {
+    let groth16 = json!({
+        "Groth16": {
+            "public_inputs": ["", ""],
+            "encoded_proof": encoded_proof,
+            "raw_proof": "",
+            "groth16_vkey_hash": vec![0; 32],
+        }
+    });
+    SP1ProofWithPublicValues {
+        proof: serde_json::from_value(groth16).unwrap(),
+        public_values: SP1PublicValues::from(&public_values),
+        sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        tee_proof: None,
+    }
+}
+
+fn write(dir: &Path, name: &str, proof: &SP1ProofWithPublicValues) {
+    fs::write(
+        dir.join("proofs").join(name),
+        serde_json::to_string(proof).unwrap(),
+    )
+    .unwrap();
+}
+
+/// Writes a proof of `public_values` to the store as `name`.
+fn write_proof(dir: &Path, name: &str, public_values: Vec<u8>) -> SP1ProofWithPublicValues {
+    let proof = groth16_proof("", public_values);
+    write(dir, name, &proof);
+    proof
+}
+
+async fn serve(store: Arc<ProofStore>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let router = router(store);
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    url
+}
+
+#[test]
+fn accept_header_chooses_the_format() {
+    assert_eq!(negotiate(None), Some(Format::Json));
+    assert_eq!(negotiate(Some("*/*")), Some(Format::Json));
+    assert_eq!(negotiate(Some(BINARY)), Some(Format::Binary));
+    assert_eq!(
+        negotiate(Some("application/json;q=0.5, application/octet-stream")),
+        Some(Format::Binary)
+    );
+    assert_eq!(
+        negotiate(Some("application/octet-stream;q=0, */*")),
+        Some(Format::Json)
+    );
+    assert_eq!(negotiate(Some("text/html")), None);
+}
+
+#[test]
+fn scan_leaves_out_proofs_it_cannot_serve() {
+    let (dir, blocks) = store("scan");
+    let history = replay_checkpoint_history(
+        &serde_json::from_str::<Vec<_>>(&fs::read_to_string(dir.join("checkpoints.json")).unwrap())
+            .unwrap(),
+    );
+    write_proof(&dir, "a.json", public_values(&blocks, 10, [0; 32]));
+    write_proof(&dir, "b.json", public_values(&blocks, 12, history[0]));
+    // The same head again, a history the store does not have, and a head it has no header of.
+    write_proof(&dir, "c.json", public_values(&blocks, 12, [0; 32]));
+    write_proof(&dir, "d.json", public_values(&blocks, 14, [9; 32]));
+    fs::remove_file(dir.join("headers").join("block_16.json")).unwrap();
+    write_proof(&dir, "e.json", public_values(&blocks, 16, [0; 32]));
+    fs::write(dir.join("proofs").join("f.json"), "{").unwrap();
+
+    // Another head at a height already served.
+    write_proof(&dir, "g.json", public_values(&blocks, 18, [0; 32]));
+    let other = generate_chain(&ChainSpec {
+        blocks: 18,
+        validators: 5,
+        ..Default::default()
+    });
+    fs::write(
+        dir.join("headers").join("block_99.json"),
+        serde_json::to_string(&other[17]).unwrap(),
+    )
+    .unwrap();
+    let mut conflicting = values(&blocks, 18, [0; 32]);
+    conflicting.h2_hash = hash(&other[17]);
+    write_proof(&dir, "h.json", bincode::serialize(&conflicting).unwrap());
+
+    // Another genesis, another vk, another proof type, and a proof that does not verify.
+    let mut other_genesis = values(&blocks, 19, [0; 32]);
+    other_genesis.genesis_hash = hash(&blocks[1]);
+    write_proof(&dir, "i.json", bincode::serialize(&other_genesis).unwrap());
+    let mut other_vk = values(&blocks, 19, [0; 32]);
+    other_vk.vk_digest = [2; 8];
+    write_proof(&dir, "j.json", bincode::serialize(&other_vk).unwrap());
+    let mut core = groth16_proof("", public_values(&blocks, 19, [0; 32]));
+    core.proof = SP1Proof::Core(Vec::new());
+    write(&dir, "k.json", &core);
+    write(
+        &dir,
+        "l.json",
+        &groth16_proof("invalid", public_values(&blocks, 19, [0; 32])),
+    );
+
+    let index = scan(&dir, &config(&blocks)).unwrap();
+    assert_eq!(index.proofs.len(), 3);
+    assert_eq!(index.latest, Some(hash(&blocks[17])));
+    assert_eq!(index.checkpoints.len(), 1);
+    let left_out = [
+        "c.json", "d.json", "e.json", "f.json", "h.json", "i.json", "j.json", "k.json", "l.json",
+    ];
+    assert_eq!(index.errors.len(), left_out.len(), "{:?}", index.errors);
+    for (file, error) in left_out.iter().zip(&index.errors) {
+        assert!(error.contains(file), "{}", error);
+    }
+    assert!(index.errors[4].contains("conflicts"), "{}", index.errors[4]);
+}
+
+#[tokio::test]
+async fn serves_proofs_by_head_and_latest() {
+    let (dir, blocks) = store("serve");
+    write_proof(&dir, "10.json", public_values(&blocks, 10, [0; 32]));
+    let latest = write_proof(&dir, "15.json", public_values(&blocks, 15, [0; 32]));
+    let url = serve(Arc::new(ProofStore::open(dir, config(&blocks)).unwrap())).await;
+    let client = reqwest::Client::new();
+
+    let response = reqwest::get(format!("{}/proofs/latest", url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let proof: SP1ProofWithPublicValues = response.json().await.unwrap();
+    assert_eq!(proof.public_values.to_vec(), latest.public_values.to_vec());
+
+    let by_hash = format!("{}/proofs/0x{}", url, hex::encode_upper(hash(&blocks[14])));
+    let response = client
+        .get(&by_hash)
+        .header(header::ACCEPT, BINARY)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], BINARY);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let proof: SP1ProofWithPublicValues =
+        bincode::deserialize(&response.bytes().await.unwrap()).unwrap();
+    assert_eq!(proof.public_values.to_vec(), latest.public_values.to_vec());
+
+    let older = format!("{}/proofs/{}", url, hex::encode(hash(&blocks[9])));
+    let values: serde_json::Value = reqwest::get(format!("{}/public_values", older))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(values["h2_hash"], hex::encode(hash(&blocks[9])));
+    assert_eq!(values["genesis_hash"], hex::encode(hash(&blocks[0])));
+    assert_eq!(values["path_root"], hex::encode([10; 32]));
+
+    let raw = client
+        .get(format!("{}/public_values", older))
+        .header(header::ACCEPT, BINARY)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    assert_eq!(raw.to_vec(), public_values(&blocks, 10, [0; 32]));
+
+    let head: LightBlock = reqwest::get(format!("{}/head", older))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(hash(&head), hash(&blocks[9]));
+    let encoded = client
+        .get(format!("{}/head", older))
+        .header(header::ACCEPT, BINARY)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    assert_eq!(
+        hash(&decode_light_block(&encoded).unwrap()),
+        hash(&blocks[9])
+    );
+
+    let checkpoints: Vec<Groth16VkeyCheckpoint> = reqwest::get(format!("{}/checkpoints", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(checkpoints[0].block_hash.to_vec(), hash(&blocks[4]));
+}
+
+#[tokio::test]
+async fn etags_and_errors() {
+    let (dir, blocks) = store("etags");
+    let url = serve(Arc::new(
+        ProofStore::open(dir.clone(), config(&blocks)).unwrap(),
+    ))
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = reqwest::get(format!("{}/proofs/latest", url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["error"].is_string());
+
+    write_proof(&dir, "10.json", public_values(&blocks, 10, [0; 32]));
+    let url = serve(Arc::new(ProofStore::open(dir, config(&blocks)).unwrap())).await;
+    let proof = format!("{}/proofs/{}", url, hex::encode(hash(&blocks[9])));
+
+    let response = reqwest::get(&proof).await.unwrap();
+    assert_eq!(response.headers()[header::VARY], "Accept");
+    let etag = response.headers()[header::ETAG].clone();
+    let response = client
+        .get(&proof)
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.bytes().await.unwrap().is_empty());
+
+    // The binary representation is tagged apart from the JSON one.
+    let response = client
+        .get(&proof)
+        .header(header::ACCEPT, BINARY)
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .get(&proof)
+        .header(header::ACCEPT, "text/html")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+    let unknown = format!("{}/proofs/{}", url, hex::encode(hash(&blocks[10])));
+    let response = reqwest::get(unknown).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = reqwest::get(format!("{}/proofs/xyz", url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn refresh_picks_up_new_proofs() {
+    let (dir, blocks) = store("refresh");
+    write_proof(&dir, "10.json", public_values(&blocks, 10, [0; 32]));
+    let store = Arc::new(ProofStore::open(dir.clone(), config(&blocks)).unwrap());
+    let url = serve(store.clone()).await;
+    assert!(!store.refresh().unwrap());
+
+    write_proof(&dir, "18.json", public_values(&blocks, 18, [0; 32]));
+    assert!(store.refresh().unwrap());
+    let values: serde_json::Value = reqwest::get(format!("{}/proofs/latest/public_values", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(values["h2_hash"], hex::encode(hash(&blocks[17])));
+}
+
+#[tokio::test]
+async fn proof_of_a_head_can_be_replaced() {
+    let (dir, blocks) = store("replace");
+    write_proof(&dir, "10.json", public_values(&blocks, 10, [0; 32]));
+    let store = Arc::new(ProofStore::open(dir.clone(), config(&blocks)).unwrap());
+    let url = serve(store.clone()).await;
+    let client = reqwest::Client::new();
+    let public_values_url = format!(
+        "{}/proofs/{}/public_values",
+        url,
+        hex::encode(hash(&blocks[9]))
+    );
+
+    let response = reqwest::get(&public_values_url).await.unwrap();
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let etag = response.headers()[header::ETAG].clone();
+
+    // A proof of the same head over another path, under a name that sorts first.
+    let mut other_path = values(&blocks, 10, [0; 32]);
+    other_path.path_root = [99; 32];
+    write_proof(&dir, "09.json", bincode::serialize(&other_path).unwrap());
+    assert!(store.refresh().unwrap());
+
+    let response = client
+        .get(&public_values_url)
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let values: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(values["path_root"], hex::encode([99; 32]));
+}
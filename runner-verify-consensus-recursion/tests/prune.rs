@@ -0,0 +1,103 @@
+use runner_verify_consensus_recursion::prune::prune_signatures;
+use std::time::Duration;
+use tendermint::Time;
+use tendermint_light_client_verifier::{
+    options::Options, types::LightBlock, ProdVerifier, Verdict, Verifier,
+};
+use tendermint_testgen::{Generator, Header, LightBlock as TestgenLightBlock, Validator};
+
+fn validators(powers: &[(&str, u64)]) -> Vec<Validator> {
+    powers
+        .iter()
+        .map(|(id, power)| Validator::new(id).voting_power(*power))
+        .collect()
+}
+
+fn light_block(height: u64, validators: &[Validator], next_validators: &[Validator]) -> LightBlock {
+    let header = Header::new(validators)
+        .height(height)
+        .chain_id("test-chain")
+        .next_validators(next_validators)
+        .time(Time::from_unix_timestamp(height as i64, 0).unwrap());
+    let block = TestgenLightBlock::new_default_with_header(header)
+        .next_validators(next_validators)
+        .generate()
+        .unwrap();
+    LightBlock::new(
+        block.signed_header,
+        block.validators,
+        block.next_validators,
+        block.provider,
+    )
+}
+
+fn verify(untrusted: &LightBlock, trusted: &LightBlock) -> Verdict {
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(common::TRUSTING_PERIOD_SECS),
+        clock_drift: Default::default(),
+    };
+    let now = (untrusted.time() + Duration::from_secs(20)).unwrap();
+    ProdVerifier::default().verify_update_header(
+        untrusted.as_untrusted_state(),
+        trusted.as_trusted_state(),
+        &options,
+        now,
+    )
+}
+
+fn signed(block: &LightBlock) -> usize {
+    block
+        .signed_header
+        .commit
+        .signatures
+        .iter()
+        .filter(|signature| signature.is_commit())
+        .count()
+}
+
+#[test]
+fn adjacent_hop_keeps_two_thirds_of_the_power() {
+    let vals = validators(&[("a", 40), ("b", 30), ("c", 20), ("d", 10)]);
+    let trusted = light_block(1, &vals, &vals);
+    let mut untrusted = light_block(2, &vals, &vals);
+
+    assert_eq!(prune_signatures(&mut untrusted, &trusted), 2);
+    assert_eq!(signed(&untrusted), 2);
+    assert_eq!(untrusted.signed_header.commit.signatures.len(), 4);
+    assert_eq!(verify(&untrusted, &trusted), Verdict::Success);
+}
+
+#[test]
+fn skipping_hop_also_keeps_the_trust_threshold() {
+    let vals = validators(&[("a", 40), ("b", 30), ("c", 20), ("d", 10)]);
+    let trusted_next = validators(&[("c", 20), ("d", 10)]);
+    let trusted = light_block(1, &trusted_next, &trusted_next);
+    let mut untrusted = light_block(5, &vals, &vals);
+
+    // a and b carry 2/3 of the new set, but none of the trusted one.
+    assert_eq!(prune_signatures(&mut untrusted, &trusted), 3);
+    assert_eq!(verify(&untrusted, &trusted), Verdict::Success);
+}
+
+#[test]
+fn equal_powers_keep_just_over_two_thirds() {
+    let vals = validators(&[("a", 1), ("b", 1), ("c", 1), ("d", 1), ("e", 1), ("f", 1)]);
+    let trusted = light_block(1, &vals, &vals);
+    let mut untrusted = light_block(2, &vals, &vals);
+
+    assert_eq!(prune_signatures(&mut untrusted, &trusted), 5);
+    assert_eq!(verify(&untrusted, &trusted), Verdict::Success);
+}
+
+#[test]
+fn pruning_does_not_change_the_header() {
+    let vals = validators(&[("a", 40), ("b", 30), ("c", 20), ("d", 10)]);
+    let trusted = light_block(1, &vals, &vals);
+    let mut untrusted = light_block(2, &vals, &vals);
+    let hash = untrusted.signed_header.header().hash();
+
+    prune_signatures(&mut untrusted, &trusted);
+    assert_eq!(untrusted.signed_header.header().hash(), hash);
+    assert_eq!(prune_signatures(&mut untrusted, &trusted), 2);
+}
@@ -0,0 +1,99 @@
+use runner_verify_consensus_recursion::state::{read_abci_query, AbciQueryJson};
+use state_proof::StateProofError;
+use std::path::{Path, PathBuf};
+
+// abci_query responses for bank balances at height 2000000. The proofs come from an IAVL bank
+// store and a rootmulti store built to match the app hash below.
+const APP_HASH: &str = "7FBDD3B09BEDC1A96AA41214F84A92B3BEDB2FC36D4768BC04227D2E365847AC";
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn app_hash() -> Vec<u8> {
+    hex::decode(APP_HASH).unwrap()
+}
+
+#[test]
+fn balance_is_proven() {
+    let (proof, height) = read_abci_query(&fixture("abci_query_balance.json")).unwrap();
+    assert_eq!(height, 2_000_000);
+    assert_eq!(proof.store, "bank");
+    // Balances prefix, address length, address, denom.
+    assert_eq!(
+        hex::encode(&proof.key),
+        "0214606060606060606060606060606060606060606075746961"
+    );
+    // A Coin of 3000017utia.
+    assert_eq!(
+        proof.value.as_deref(),
+        Some(
+            hex::decode("0a0475746961120733303030303137")
+                .unwrap()
+                .as_slice()
+        )
+    );
+    proof.verify(&app_hash()).unwrap();
+}
+
+#[test]
+fn absent_key_is_proven() {
+    let (proof, _) = read_abci_query(&fixture("abci_query_absent.json")).unwrap();
+    assert_eq!(proof.value, None);
+    proof.verify(&app_hash()).unwrap();
+
+    let mut present = proof;
+    present.value = Some(b"1".to_vec());
+    assert_eq!(
+        present.verify(&app_hash()),
+        Err(StateProofError::InvalidStoreProof)
+    );
+}
+
+#[test]
+fn other_state_is_rejected() {
+    let (proof, _) = read_abci_query(&fixture("abci_query_balance.json")).unwrap();
+
+    let mut other_value = proof.clone();
+    other_value.value.as_mut().unwrap()[10] ^= 1;
+    assert_eq!(
+        other_value.verify(&app_hash()),
+        Err(StateProofError::InvalidStoreProof)
+    );
+
+    let mut other_key = proof.clone();
+    other_key.key[5] ^= 1;
+    assert_eq!(
+        other_key.verify(&app_hash()),
+        Err(StateProofError::InvalidStoreProof)
+    );
+
+    let mut other_store = proof.clone();
+    other_store.store = "acc".to_string();
+    assert_eq!(
+        other_store.verify(&app_hash()),
+        Err(StateProofError::InvalidMultistoreProof)
+    );
+
+    let mut other_app_hash = app_hash();
+    other_app_hash[0] ^= 1;
+    assert_eq!(
+        proof.verify(&other_app_hash),
+        Err(StateProofError::InvalidMultistoreProof)
+    );
+}
+
+#[test]
+fn failed_queries_are_rejected() {
+    let content = std::fs::read_to_string(fixture("abci_query_balance.json")).unwrap();
+    let mut json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    json["result"]["response"]["code"] = 18.into();
+    json["result"]["response"]["log"] = "invalid request".into();
+    let query: AbciQueryJson = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        query.result.response.to_state_proof().unwrap_err(),
+        "query failed with code 18: invalid request"
+    );
+}
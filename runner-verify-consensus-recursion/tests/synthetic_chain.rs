@@ -0,0 +1,97 @@
+//! End to end over a synthetic chain, offline: the scraper's path search finds the path, and every
+//! hop of it passes the recursion rules, each recursing on the public values of the one before,
+//! both natively and in the program with the mock prover.
+
+use common::mmr::PathAccumulator;
+use common::RecursionPublicValues;
+use consensus::recursion::{verify_hop, HopInputs, PreviousProof};
+use consensus::testing::AcceptCompressed;
+use runner_verify_consensus_recursion::{
+    hop::execute_path,
+    path::path_leaf,
+    prover::{ProverMode, RecursionProver},
+    prune::prune_signatures,
+    CONSENSUS_VERIFIER_RECURSION_ELF,
+};
+use scraper::path_search::{find_path, verifier_options};
+use scraper::synthetic::{ChainSpec, SyntheticChain};
+use sp1_sdk::HashableKey;
+use tendermint_light_client_verifier::{types::LightBlock, ProdVerifier};
+
+async fn scraped_path() -> Vec<LightBlock> {
+    let spec = ChainSpec {
+        blocks: 60,
+        churn: 2,
+        power_skew: 5,
+        ..Default::default()
+    };
+    let chain = SyntheticChain::new(&spec);
+    let path = find_path(
+        &chain,
+        &ProdVerifier::default(),
+        &verifier_options(),
+        1,
+        spec.blocks,
+        |_| {},
+    )
+    .await
+    .unwrap();
+    assert!(path.len() > 2, "churn should force intermediate hops");
+    path
+}
+
+#[tokio::test]
+async fn scraped_path_recurses() {
+    let path = scraped_path().await;
+    let genesis_hash = path[0].signed_header.header().hash().as_bytes().to_vec();
+    let vk_digest = [1; 8];
+    let mut previous_path = PathAccumulator::default();
+    let mut previous: Option<RecursionPublicValues> = None;
+    for pair in path.windows(2) {
+        let mut h2 = pair[1].clone();
+        prune_signatures(&mut h2, &pair[0]);
+        let inputs = HopInputs {
+            is_upgrade: false,
+            checkpoints: Vec::new(),
+            genesis_hash: genesis_hash.clone(),
+            h1: pair[0].clone(),
+            h2,
+            vk_digest,
+            previous_path: previous_path.clone(),
+            previous: previous.map(|values| PreviousProof {
+                groth16_proof: Vec::new(),
+                public_values: bincode::serialize(&values).unwrap(),
+            }),
+        };
+        let values = verify_hop(&inputs, &AcceptCompressed)
+            .unwrap_or_else(|e| panic!("hop {} -> {}: {}", pair[0].height(), pair[1].height(), e));
+
+        previous_path.push(path_leaf(inputs.h2.signed_header.header()).digest());
+        assert_eq!(values.path_root, previous_path.root());
+        previous = Some(values);
+    }
+
+    let head = path.last().unwrap().signed_header.header().hash();
+    assert_eq!(previous.unwrap().h2_hash, head.as_bytes());
+}
+
+#[tokio::test]
+async fn scraped_path_executes() {
+    let path = scraped_path().await;
+    let client = RecursionProver::new(ProverMode::Mock, None).unwrap();
+    let (_, vk) = client.setup(CONSENSUS_VERIFIER_RECURSION_ELF);
+
+    let mut last = None;
+    execute_path(&client, &[], path.clone(), false, |hop| {
+        let (public_values, _) = hop
+            .result
+            .unwrap_or_else(|e| panic!("hop {} -> {}: {}", hop.h1.height(), hop.h2.height(), e));
+        let values: RecursionPublicValues = bincode::deserialize(public_values.as_slice()).unwrap();
+        assert_eq!(values.path_root, hop.path.root());
+        assert_eq!(values.vk_digest, vk.vk.hash_u32());
+        last = Some(values);
+    });
+
+    let head = path.last().unwrap().signed_header.header().hash();
+    assert_eq!(last.unwrap().h2_hash, head.as_bytes());
+}
@@ -0,0 +1,207 @@
+use crate::headers_dir::{light_block_path, list_heights, read_light_block};
+use crate::rpc_response::{
+    commit_result, envelope, int_param, json_response, status_result, validators_result, Params,
+    RpcError, MAX_PER_PAGE,
+};
+use axum::{
+    extract::{Query, State},
+    http::Uri,
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tendermint::{Hash, Time};
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// Serves a headers directory read-only, with the parts of the Tendermint RPC a light client
+/// needs: `/status`, `/commit` and `/validators`, plus `/path` to list what is archived. Every
+/// request lists the directory afresh, so headers the scraper adds are served as they land.
+pub fn router(dir: PathBuf) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/commit", get(commit))
+        .route("/validators", get(validators))
+        .route("/path", get(path))
+        .fallback(not_found)
+        .with_state(Arc::new(Archive {
+            dir,
+            path: RwLock::default(),
+        }))
+}
+
+/// An archived header as `/path` lists it, with the size and modification time of the file it
+/// was read from.
+#[derive(Clone)]
+struct PathEntry {
+    height: u64,
+    hash: Hash,
+    time: Time,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// A headers directory, with the path read from it so far.
+struct Archive {
+    dir: PathBuf,
+    path: RwLock<Arc<Vec<PathEntry>>>,
+}
+
+impl Archive {
+    /// Every archived header, in order, of which there is at least one. Only the files that
+    /// changed since the last call are read again.
+    fn path(&self) -> Result<Arc<Vec<PathEntry>>, RpcError> {
+        let cached = self.path.read().unwrap().clone();
+        let heights = list_heights(&self.dir).map_err(|e| RpcError::internal(e.to_string()))?;
+        let mut changed = heights.len() != cached.len();
+        let mut path = Vec::with_capacity(heights.len());
+        for height in heights {
+            let metadata = fs::metadata(light_block_path(&self.dir, height)).ok();
+            let size = metadata.as_ref().map_or(0, |metadata| metadata.len());
+            let modified = metadata.and_then(|metadata| metadata.modified().ok());
+            let entry = match cached.binary_search_by_key(&height, |entry| entry.height) {
+                Ok(i) if cached[i].size == size && cached[i].modified == modified => {
+                    cached[i].clone()
+                }
+                _ => {
+                    changed = true;
+                    let header = block(&self.dir, height)?.signed_header.header;
+                    PathEntry {
+                        height,
+                        hash: header.hash(),
+                        time: header.time,
+                        size,
+                        modified,
+                    }
+                }
+            };
+            path.push(entry);
+        }
+        let path = if changed {
+            let path = Arc::new(path);
+            *self.path.write().unwrap() = path.clone();
+            path
+        } else {
+            cached
+        };
+        if path.is_empty() {
+            return Err(RpcError::internal("no headers archived".to_string()));
+        }
+        Ok(path)
+    }
+
+    /// The archived heights, in order.
+    fn heights(&self) -> Result<Vec<u64>, RpcError> {
+        Ok(self.path()?.iter().map(|entry| entry.height).collect())
+    }
+}
+
+type ArchiveState = State<Arc<Archive>>;
+
+fn respond(result: Result<Value, RpcError>) -> Response {
+    let (status, body) = envelope(result);
+    json_response(status, body.to_string().into_bytes())
+}
+
+/// Answers a request off the async runtime, since it reads the directory.
+async fn respond_blocking(
+    archive: Arc<Archive>,
+    answer: impl FnOnce(&Archive) -> Result<Value, RpcError> + Send + 'static,
+) -> Response {
+    let result = tokio::task::spawn_blocking(move || answer(&archive))
+        .await
+        .unwrap_or_else(|e| Err(RpcError::internal(e.to_string())));
+    respond(result)
+}
+
+fn block(dir: &Path, height: u64) -> Result<LightBlock, RpcError> {
+    if !light_block_path(dir, height).exists() {
+        return Err(RpcError::internal(format!(
+            "height {} is not archived",
+            height
+        )));
+    }
+    read_light_block(dir, height).map_err(|e| RpcError::internal(e.to_string()))
+}
+
+async fn status(State(archive): ArchiveState) -> Response {
+    respond_blocking(archive, status_at).await
+}
+
+async fn commit(State(archive): ArchiveState, Query(params): Query<Params>) -> Response {
+    respond_blocking(archive, move |archive| commit_at(archive, &params)).await
+}
+
+async fn validators(State(archive): ArchiveState, Query(params): Query<Params>) -> Response {
+    respond_blocking(archive, move |archive| validators_at(archive, &params)).await
+}
+
+async fn path(State(archive): ArchiveState) -> Response {
+    respond_blocking(archive, path_of).await
+}
+
+fn status_at(archive: &Archive) -> Result<Value, RpcError> {
+    let heights = archive.heights()?;
+    let latest = block(&archive.dir, *heights.last().unwrap())?;
+    Ok(status_result(&latest, heights[0], "header-archive"))
+}
+
+/// The requested height, or the latest archived.
+fn height(archive: &Archive, params: &Params) -> Result<u64, RpcError> {
+    match int_param(params, "height")? {
+        Some(height) => Ok(height),
+        None => Ok(*archive.heights()?.last().unwrap()),
+    }
+}
+
+fn commit_at(archive: &Archive, params: &Params) -> Result<Value, RpcError> {
+    let height = height(archive, params)?;
+    Ok(commit_result(&block(&archive.dir, height)?.signed_header))
+}
+
+/// The validators at a height are those of its block, or the next validators of the block
+/// before, which is how the head's next validators are served.
+fn validators_at(archive: &Archive, params: &Params) -> Result<Value, RpcError> {
+    let dir = &archive.dir;
+    let height = height(archive, params)?;
+    let validators = if light_block_path(dir, height).exists() {
+        block(dir, height)?.validators
+    } else {
+        match height.checked_sub(1) {
+            Some(previous) if light_block_path(dir, previous).exists() => {
+                block(dir, previous)?.next_validators
+            }
+            _ => {
+                return Err(RpcError::internal(format!(
+                    "validators at height {} are not archived",
+                    height
+                )))
+            }
+        }
+    };
+    validators_result(height, &validators, params, MAX_PER_PAGE)
+}
+
+/// Every archived header, in order, with its hash and time.
+fn path_of(archive: &Archive) -> Result<Value, RpcError> {
+    let blocks: Vec<Value> = archive
+        .path()?
+        .iter()
+        .map(|entry| {
+            json!({
+                "height": entry.height.to_string(),
+                "hash": entry.hash,
+                "time": entry.time,
+            })
+        })
+        .collect();
+    Ok(json!({ "total": blocks.len().to_string(), "blocks": blocks }))
+}
+
+async fn not_found(uri: Uri) -> Response {
+    respond(Err(RpcError::method_not_found(uri.path())))
+}
@@ -0,0 +1,46 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+use scraper::archive::router;
+use scraper::headers_dir::list_heights;
+
+/// Serve a directory of scraped headers read-only, with enough of the Tendermint RPC interface
+/// for light clients and `TendermintRPCClient` to fetch them
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the directory of block_<height>.json files, as written by the scraper
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:26657")]
+    listen: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let heights = list_heights(&args.headers_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Error: failed to listen on {}: {}", args.listen, e);
+            std::process::exit(1);
+        });
+    println!(
+        "Serving {} headers from {:?} at http://{}",
+        heights.len(),
+        args.headers_dir,
+        args.listen
+    );
+    if let Err(e) = axum::serve(listener, router(args.headers_dir)).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
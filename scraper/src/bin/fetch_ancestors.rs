@@ -0,0 +1,117 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+
+use tendermint::block::Header;
+
+use scraper::headers_dir::read_light_block;
+use scraper::tm_rpc_utils::TendermintRPCClient;
+
+/// Collect the headers from a head back to a target height, for proving the target by hash chaining
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Height of the head to start from (usually the head proven by a recursion proof)
+    #[arg(long, required_unless_present = "path_height")]
+    head_height: Option<u64>,
+
+    /// Height of a header verified on the way to the proven head to start from instead, for
+    /// `prove_ancestor_header --path-height`
+    #[arg(long, conflicts_with = "head_height")]
+    path_height: Option<u64>,
+
+    /// Height of the header to reach
+    #[arg(short, long)]
+    target_height: u64,
+
+    /// Tendermint RPC URL to fetch headers from
+    #[arg(short, long, value_name = "URL", required_unless_present = "archive")]
+    rpc_url: Option<String>,
+
+    /// Directory of block_<height>.json light blocks to read headers from instead
+    #[arg(short, long, value_name = "PATH", conflicts_with = "rpc_url")]
+    archive: Option<PathBuf>,
+
+    /// Path to write the headers to, as a JSON array from the start's parent down to the target
+    #[arg(short, long, value_name = "PATH")]
+    output: PathBuf,
+}
+
+enum Source {
+    Rpc(TendermintRPCClient),
+    Archive(PathBuf),
+}
+
+impl Source {
+    async fn header(&self, height: u64) -> Result<Header, String> {
+        match self {
+            Source::Rpc(client) => client
+                .fetch_commit(height)
+                .await
+                .map(|commit| commit.result.signed_header.header)
+                .map_err(|e| format!("failed to fetch header {}: {}", height, e)),
+            Source::Archive(dir) => {
+                read_light_block(dir, height).map(|block| block.signed_header.header)
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let start_height = match (args.head_height, args.path_height) {
+        (Some(height), _) | (None, Some(height)) => height,
+        (None, None) => unreachable!("clap requires one of --head-height or --path-height"),
+    };
+    if args.target_height > start_height {
+        eprintln!("Error: target height must not be above the start");
+        std::process::exit(1);
+    }
+    let source = match (args.rpc_url, args.archive) {
+        (Some(url), _) => Source::Rpc(TendermintRPCClient::new(url)),
+        (None, Some(dir)) => Source::Archive(dir),
+        (None, None) => unreachable!("clap requires one of --rpc-url or --archive"),
+    };
+
+    // Check every link as it is fetched, so that a bad source fails fast.
+    let mut child = source.header(start_height).await.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let mut ancestors = Vec::new();
+    for height in (args.target_height..start_height).rev() {
+        let parent = source.header(height).await.unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        if child.last_block_id.map(|id| id.hash) != Some(parent.hash()) {
+            eprintln!(
+                "Error: header {} is not the last block of header {}",
+                height, child.height
+            );
+            std::process::exit(1);
+        }
+        ancestors.push(parent.clone());
+        child = parent;
+
+        if ancestors.len() % 1000 == 0 {
+            println!(
+                "Fetched {} headers, down to height {}",
+                ancestors.len(),
+                height
+            );
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&ancestors).expect("Failed to serialize headers");
+    fs::write(&args.output, json).expect("Failed to write headers to file");
+    println!(
+        "Saved {} headers from {} down to {} to {:?}",
+        ancestors.len(),
+        start_height,
+        args.target_height,
+        args.output
+    );
+}
@@ -0,0 +1,106 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+
+use tendermint_light_client_verifier::ProdVerifier;
+
+use scraper::headers_dir::save_light_block;
+use scraper::path_search::{find_path, verifier_options};
+use scraper::synthetic::{ChainSpec, SyntheticChain};
+
+/// Generate a deterministic synthetic chain and write it as a headers directory, for testing the
+/// scraper and the prover offline
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to write the block_<height>.json files to
+    #[arg(short, long, value_name = "PATH")]
+    output_path: PathBuf,
+
+    /// Only write the minimum verification path from genesis to the last block, as the scraper
+    /// would, instead of every block
+    #[arg(long, default_value_t = false)]
+    path: bool,
+
+    /// Chain ID of every header
+    #[arg(long, default_value = "synthetic-chain")]
+    chain_id: String,
+
+    /// Number of blocks, from height 1
+    #[arg(short = 'n', long, default_value_t = 100)]
+    blocks: u64,
+
+    /// Number of validators in every set
+    #[arg(short, long, default_value_t = 4)]
+    validators: usize,
+
+    /// Validators replaced at each rotation, oldest first
+    #[arg(long, default_value_t = 0)]
+    churn: usize,
+
+    /// Blocks between rotations
+    #[arg(long, default_value_t = 10)]
+    churn_interval: u64,
+
+    /// The oldest validator's voting power as a multiple of the newest's
+    #[arg(long, default_value_t = 1)]
+    power_skew: u64,
+
+    /// Unix time of the first block
+    #[arg(long, default_value_t = 1_700_000_000)]
+    genesis_time: i64,
+
+    /// Seconds between blocks
+    #[arg(long, default_value_t = 6)]
+    block_time: u64,
+
+    /// Seed of the validator keys
+    #[arg(long, default_value_t = 0)]
+    seed: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if args.blocks == 0 || args.validators == 0 {
+        eprintln!("Error: need at least one block and one validator");
+        std::process::exit(1);
+    }
+
+    let spec = ChainSpec {
+        chain_id: args.chain_id,
+        blocks: args.blocks,
+        validators: args.validators,
+        churn: args.churn,
+        churn_interval: args.churn_interval,
+        power_skew: args.power_skew,
+        genesis_time: args.genesis_time,
+        block_time_secs: args.block_time,
+        seed: args.seed,
+    };
+    println!("Generating {} blocks...", spec.blocks);
+    let chain = SyntheticChain::new(&spec);
+
+    fs::create_dir_all(&args.output_path).expect("Failed to create output directory");
+    let blocks = if args.path {
+        find_path(
+            &chain,
+            &ProdVerifier::default(),
+            &verifier_options(),
+            1,
+            spec.blocks,
+            |_| {},
+        )
+        .await
+        .expect("synthetic blocks are all present")
+    } else {
+        chain.blocks
+    };
+    for block in &blocks {
+        save_light_block(block, &args.output_path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+    }
+    println!("Wrote {} blocks to {:?}", blocks.len(), args.output_path);
+}
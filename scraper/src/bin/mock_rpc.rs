@@ -0,0 +1,99 @@
+use clap::Parser;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use scraper::headers_dir::{list_heights, read_light_block};
+use scraper::mock_rpc::{Faults, MockRpc};
+use scraper::rpc_response::MAX_PER_PAGE;
+
+/// Serve a headers directory as a stand-in for a node's Tendermint RPC, optionally misbehaving,
+/// to test RPC clients offline
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the directory of block_<height>.json files to serve, such as a fixture or the
+    /// output of generate_chain
+    #[arg(short = 'd', long, value_name = "PATH")]
+    headers_dir: PathBuf,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:26657")]
+    listen: String,
+
+    /// Largest page of validators to return
+    #[arg(long, default_value_t = MAX_PER_PAGE)]
+    max_per_page: usize,
+
+    /// Delay before every response, in milliseconds
+    #[arg(long, default_value_t = 0)]
+    latency_ms: u64,
+
+    /// HTTP status to fail every --error-every'th request with, such as 503 or 429
+    #[arg(long, requires = "error_every")]
+    error_status: Option<u16>,
+
+    /// Fail every Nth request with --error-status
+    #[arg(long, default_value_t = 0)]
+    error_every: u64,
+
+    /// Answer requests for heights below this as pruned
+    #[arg(long, default_value_t = 0)]
+    pruned_below: u64,
+
+    /// Cut the body of every Nth response in half
+    #[arg(long, default_value_t = 0)]
+    truncate_every: u64,
+
+    /// Height whose header /commit alters so that it no longer matches its commit, repeatable
+    #[arg(long = "lying-height", value_name = "HEIGHT")]
+    lying_heights: Vec<u64>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let heights = list_heights(&args.headers_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    if heights.is_empty() {
+        eprintln!("Error: no headers in {:?}", args.headers_dir);
+        std::process::exit(1);
+    }
+    let blocks = heights.iter().map(|height| {
+        read_light_block(&args.headers_dir, *height).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    let faults = Faults {
+        latency: Duration::from_millis(args.latency_ms),
+        error_status: args.error_status,
+        error_every: args.error_every,
+        pruned_below: args.pruned_below,
+        truncate_every: args.truncate_every,
+        lying_heights: args.lying_heights.into_iter().collect::<BTreeSet<_>>(),
+    };
+    let rpc = Arc::new(MockRpc::new(blocks, faults).max_per_page(args.max_per_page));
+
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Error: failed to listen on {}: {}", args.listen, e);
+            std::process::exit(1);
+        });
+    println!(
+        "Serving {} blocks from {:?} at http://{}",
+        heights.len(),
+        args.headers_dir,
+        args.listen
+    );
+    if let Err(e) = axum::serve(listener, rpc.router()).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
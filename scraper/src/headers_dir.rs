@@ -0,0 +1,78 @@
+use crate::provider::LightBlockProvider;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// The path of the light block at `height` in a headers directory.
+pub fn light_block_path(dir: &Path, height: u64) -> PathBuf {
+    dir.join(format!("block_{}.json", height))
+}
+
+/// Writes a light block to a headers directory as `block_<height>.json`.
+pub fn save_light_block(block: &LightBlock, dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let path = light_block_path(dir, block.height().value());
+    fs::write(&path, serde_json::to_string_pretty(block)?)?;
+    Ok(path)
+}
+
+/// Lists the `block_<height>.json` files of a headers directory, sorted by height.
+pub fn list_headers_dir(dir: &Path) -> Result<Vec<(u64, PathBuf)>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("failed to read headers directory {:?}: {}", dir, e))?;
+
+    let mut blocks: Vec<(u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let height = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix("block_")?
+                .strip_suffix(".json")?
+                .parse::<u64>()
+                .ok()?;
+            Some((height, path))
+        })
+        .collect();
+
+    blocks.sort_by_key(|(height, _)| *height);
+    Ok(blocks)
+}
+
+/// The heights of the light blocks in a headers directory, in order.
+pub fn list_heights(dir: &Path) -> Result<Vec<u64>, String> {
+    Ok(list_headers_dir(dir)?
+        .into_iter()
+        .map(|(height, _)| height)
+        .collect())
+}
+
+/// Reads a `LightBlock` JSON file, as written by [`save_light_block`].
+pub fn read_light_block_file(path: &Path) -> Result<LightBlock, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("failed to deserialize {:?}: {}", path, e))
+}
+
+/// Reads the light block at `height` from a headers directory.
+pub fn read_light_block(dir: &Path, height: u64) -> Result<LightBlock, String> {
+    read_light_block_file(&light_block_path(dir, height))
+}
+
+/// The light blocks of a headers directory, as written by the scraper.
+pub struct HeadersDir {
+    pub dir: PathBuf,
+}
+
+impl LightBlockProvider for HeadersDir {
+    async fn latest_height(&self) -> Result<u64, Box<dyn Error>> {
+        list_heights(&self.dir)?
+            .last()
+            .copied()
+            .ok_or_else(|| format!("no headers in {:?}", self.dir).into())
+    }
+
+    async fn light_block(&self, height: u64) -> Result<LightBlock, Box<dyn Error>> {
+        Ok(read_light_block(&self.dir, height)?)
+    }
+}
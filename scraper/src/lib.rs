@@ -1,2 +1,12 @@
 pub mod tm_rpc_utils;
-pub mod tm_rpc_types;
\ No newline at end of file
+pub mod tm_rpc_types;
+pub mod provider;
+pub mod headers_dir;
+pub mod path_search;
+pub mod synthetic;
+#[cfg(any(feature = "archive", feature = "mock-rpc"))]
+pub mod rpc_response;
+#[cfg(feature = "mock-rpc")]
+pub mod mock_rpc;
+#[cfg(feature = "archive")]
+pub mod archive;
@@ -0,0 +1,281 @@
+use crate::rpc_response::{
+    commit_result, envelope, int_param, json_response, status_result, validators_result, Params,
+    RpcError, MAX_PER_PAGE,
+};
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tendermint::{block::Commit, evidence, AppHash, Block};
+use tendermint_light_client_verifier::types::LightBlock;
+use tokio::net::TcpListener;
+
+/// Misbehaviour to inject into responses. Requests are counted from 1 across every endpoint, so
+/// faults land on the same requests every run.
+#[derive(Clone, Debug, Default)]
+pub struct Faults {
+    /// Delay before every response.
+    pub latency: Duration,
+    /// Status to fail every `error_every`th request with, such as 503, or 429 with a
+    /// `Retry-After` header.
+    pub error_status: Option<u16>,
+    pub error_every: u64,
+    /// Heights below this are pruned, as on a node that does not keep them.
+    pub pruned_below: u64,
+    /// Cut the body of every `truncate_every`th response in half.
+    pub truncate_every: u64,
+    /// Heights whose header `/commit` alters, so that it no longer matches its commit.
+    pub lying_heights: BTreeSet<u64>,
+}
+
+/// A stand-in for the Tendermint RPC of a node, serving `/status`, `/commit`, `/validators`,
+/// `/block_by_hash` and `/genesis` from a set of light blocks.
+pub struct MockRpc {
+    blocks: BTreeMap<u64, LightBlock>,
+    faults: Faults,
+    max_per_page: usize,
+    requests: AtomicU64,
+}
+
+impl MockRpc {
+    /// Serves `blocks`, which need not be contiguous: the validators at a height missing from
+    /// them are the next validators of the block before.
+    pub fn new(blocks: impl IntoIterator<Item = LightBlock>, faults: Faults) -> Self {
+        MockRpc {
+            blocks: blocks
+                .into_iter()
+                .map(|block| (block.height().value(), block))
+                .collect(),
+            faults,
+            max_per_page: MAX_PER_PAGE,
+            requests: AtomicU64::new(0),
+        }
+    }
+
+    /// Caps `per_page` lower than CometBFT does, so small validator sets span several pages.
+    pub fn max_per_page(mut self, max_per_page: usize) -> Self {
+        self.max_per_page = max_per_page.max(1);
+        self
+    }
+
+    /// The number of requests served so far.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// A router serving the RPC, to mount or serve.
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new().fallback(handle).with_state(self)
+    }
+
+    /// Serves the RPC on a local port in the background, returning its URL.
+    pub async fn spawn(self: Arc<Self>) -> std::io::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let router = self.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        Ok(url)
+    }
+
+    fn latest_height(&self) -> Result<u64, RpcError> {
+        self.blocks
+            .keys()
+            .next_back()
+            .copied()
+            .ok_or_else(|| RpcError::internal("no blocks".to_string()))
+    }
+
+    /// The height of a request, the latest by default, checked against pruning. Validators are
+    /// known one height ahead of the latest block.
+    fn height(&self, params: &Params, ahead: u64) -> Result<u64, RpcError> {
+        let latest = self.latest_height()?;
+        let height = int_param(params, "height")?.unwrap_or(latest);
+        if height > latest + ahead {
+            return Err(RpcError::internal(format!(
+                "height {} must be less than or equal to the current blockchain height {}",
+                height,
+                latest + ahead
+            )));
+        }
+        if height < self.faults.pruned_below {
+            return Err(RpcError::internal(format!(
+                "height {} is not available, lowest height is {}",
+                height, self.faults.pruned_below
+            )));
+        }
+        Ok(height)
+    }
+
+    fn block(&self, height: u64) -> Result<&LightBlock, RpcError> {
+        self.blocks
+            .get(&height)
+            .ok_or_else(|| RpcError::internal(format!("no block at height {}", height)))
+    }
+
+    fn status(&self) -> Result<Value, RpcError> {
+        let latest = self.block(self.latest_height()?)?;
+        let earliest = *self.blocks.keys().next().unwrap();
+        Ok(status_result(
+            latest,
+            earliest.max(self.faults.pruned_below),
+            "mock-rpc",
+        ))
+    }
+
+    fn commit(&self, params: &Params) -> Result<Value, RpcError> {
+        let height = self.height(params, 0)?;
+        let mut signed_header = self.block(height)?.signed_header.clone();
+        if self.faults.lying_heights.contains(&height) {
+            signed_header.header.app_hash = AppHash::try_from(b"lying".to_vec()).unwrap();
+        }
+        Ok(commit_result(&signed_header))
+    }
+
+    fn validators(&self, params: &Params) -> Result<Value, RpcError> {
+        let height = self.height(params, 1)?;
+        let validators = match self.blocks.get(&height) {
+            Some(block) => &block.validators,
+            None => match height.checked_sub(1).and_then(|h| self.blocks.get(&h)) {
+                Some(block) => &block.next_validators,
+                None => return Err(RpcError::internal(format!("no validators at {}", height))),
+            },
+        };
+        validators_result(height, validators, params, self.max_per_page)
+    }
+
+    fn block_by_hash(&self, params: &Params) -> Result<Value, RpcError> {
+        let hash = params
+            .get("hash")
+            .map(|hash| {
+                hash.trim_matches('"')
+                    .trim_start_matches("0x")
+                    .to_uppercase()
+            })
+            .ok_or_else(|| RpcError::invalid_params("missing hash".to_string()))?;
+        let Some(block) = self
+            .blocks
+            .values()
+            .find(|block| block.signed_header.header.hash().to_string() == hash)
+        else {
+            // CometBFT answers an unknown hash with an empty result.
+            return Ok(json!({ "block_id": null, "block": null }));
+        };
+        let header = &block.signed_header.header;
+        if header.height.value() < self.faults.pruned_below {
+            return Err(RpcError::internal(format!(
+                "height {} is not available, lowest height is {}",
+                header.height, self.faults.pruned_below
+            )));
+        }
+
+        // The commit of the block before, or a stand-in for it when that block is not served.
+        let last_commit = match header.height.value() {
+            1 => None,
+            height => Some(match self.blocks.get(&(height - 1)) {
+                Some(previous) => previous.signed_header.commit.clone(),
+                None => Commit {
+                    height: (height - 1).try_into().unwrap(),
+                    ..block.signed_header.commit.clone()
+                },
+            }),
+        };
+        let full_block = Block::new(
+            header.clone(),
+            Vec::new(),
+            evidence::List::default(),
+            last_commit,
+        )
+        .map_err(|e| RpcError::internal(e.to_string()))?;
+        Ok(json!({
+            "block_id": block.signed_header.commit.block_id,
+            "block": full_block,
+        }))
+    }
+
+    fn genesis(&self) -> Result<Value, RpcError> {
+        let (height, first) = self
+            .blocks
+            .iter()
+            .next()
+            .ok_or_else(|| RpcError::internal("no blocks".to_string()))?;
+        let validators: Vec<Value> = first
+            .validators
+            .validators()
+            .iter()
+            .map(|validator| {
+                json!({
+                    "address": validator.address,
+                    "pub_key": validator.pub_key,
+                    "power": validator.power().to_string(),
+                    "name": "",
+                })
+            })
+            .collect();
+        Ok(json!({
+            "genesis": {
+                "genesis_time": first.time(),
+                "chain_id": first.signed_header.header.chain_id,
+                "initial_height": height.to_string(),
+                "consensus_params": {
+                    "block": { "max_bytes": "22020096", "max_gas": "-1" },
+                    "evidence": {
+                        "max_age_num_blocks": "100000",
+                        "max_age_duration": "172800000000000",
+                        "max_bytes": "1048576",
+                    },
+                    "validator": { "pub_key_types": ["ed25519"] },
+                    "version": {},
+                },
+                "validators": validators,
+                "app_hash": "",
+                "app_state": {},
+            },
+        }))
+    }
+}
+
+async fn handle(
+    State(rpc): State<Arc<MockRpc>>,
+    uri: Uri,
+    Query(params): Query<Params>,
+) -> Response {
+    let request = rpc.requests.fetch_add(1, Ordering::SeqCst) + 1;
+    let faults = &rpc.faults;
+    tokio::time::sleep(faults.latency).await;
+
+    if let Some(status) = faults.error_status {
+        if faults.error_every > 0 && request % faults.error_every == 0 {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE);
+            let mut response = (status, status.to_string()).into_response();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, header::HeaderValue::from_static("1"));
+            }
+            return response;
+        }
+    }
+
+    let result = match uri.path() {
+        "/status" => rpc.status(),
+        "/commit" => rpc.commit(&params),
+        "/validators" => rpc.validators(&params),
+        "/block_by_hash" => rpc.block_by_hash(&params),
+        "/genesis" => rpc.genesis(),
+        path => Err(RpcError::method_not_found(path)),
+    };
+    let (status, body) = envelope(result);
+
+    let mut body = body.to_string().into_bytes();
+    if faults.truncate_every > 0 && request % faults.truncate_every == 0 {
+        body.truncate(body.len() / 2);
+    }
+    json_response(status, body)
+}
@@ -0,0 +1,186 @@
+use crate::provider::LightBlockProvider;
+use std::error::Error;
+use std::time::Duration;
+use tendermint_light_client_verifier::{
+    options::Options, types::LightBlock, ProdVerifier, Verdict, Verifier,
+};
+
+/// The light client options the scraper searches paths with.
+pub fn verifier_options() -> Options {
+    Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(common::TRUSTING_PERIOD_SECS),
+        clock_drift: Default::default(),
+    }
+}
+
+/// Finds a minimum verification path from `start` to `end`, calling `on_block` with each block
+/// of the path as it is found, genesis first.
+pub async fn find_path<P: LightBlockProvider>(
+    provider: &P,
+    verifier: &ProdVerifier,
+    options: &Options,
+    start: u64,
+    end: u64,
+    mut on_block: impl FnMut(&LightBlock),
+) -> Result<Vec<LightBlock>, Box<dyn Error>> {
+    let genesis_block = provider.light_block(start).await?;
+    on_block(&genesis_block);
+
+    let mut verified_blocks = vec![genesis_block];
+    let mut current_height = start;
+
+    while current_height < end {
+        let target_height = find_next_verifiable_block(
+            provider,
+            verifier,
+            options,
+            verified_blocks.last().unwrap(),
+            current_height + 1,
+            end,
+        )
+        .await;
+
+        if let Some(next_height) = target_height {
+            println!(
+                "Successfully verified jump from {} to {}",
+                current_height, next_height
+            );
+            current_height = next_height;
+        } else {
+            println!(
+                "Failed to find verifiable path from {}. Trying next block...",
+                current_height
+            );
+            current_height += 1;
+        }
+        let next_block = provider.light_block(current_height).await?;
+        on_block(&next_block);
+        verified_blocks.push(next_block);
+    }
+
+    Ok(verified_blocks)
+}
+
+/// Uses binary search to find the furthest block that can be verified from the trusted block
+pub async fn find_next_verifiable_block<P: LightBlockProvider>(
+    provider: &P,
+    verifier: &ProdVerifier,
+    options: &Options,
+    trusted_block: &LightBlock,
+    start_height: u64,
+    end_height: u64,
+) -> Option<u64> {
+    if start_height > end_height {
+        return None;
+    }
+
+    // First, try to verify directly to the end
+    println!(
+        "Attempting to verify from {} to {}...",
+        trusted_block.height().value(),
+        end_height
+    );
+
+    let target_block = match provider.light_block(end_height).await {
+        Ok(block) => block,
+        Err(e) => {
+            println!("✗ Error fetching block at height {}: {}", end_height, e);
+            return None;
+        }
+    };
+
+    match try_verify(verifier, options, trusted_block, &target_block) {
+        Ok(true) => {
+            println!("✓ Successfully verified jump to {}", end_height);
+            return Some(end_height);
+        }
+        Ok(false) => {
+            println!("✗ Failed to verify jump to {}", end_height);
+        }
+        Err(e) => {
+            println!("✗ Error verifying jump to {}: {}", end_height, e);
+        }
+    }
+
+    // If we can't verify to the end, do binary search
+    let mut left = start_height;
+    let mut right = end_height;
+    let mut best_verifiable: Option<u64> = None;
+
+    while left <= right {
+        let mid = left + (right - left) / 2;
+
+        println!(
+            "Binary search: trying height {} (range: {} to {})",
+            mid, left, right
+        );
+
+        let target_block = match provider.light_block(mid).await {
+            Ok(block) => block,
+            Err(e) => {
+                println!("✗ Error fetching block at height {}: {}", mid, e);
+                if mid == 0 {
+                    break;
+                }
+                right = mid - 1;
+                continue;
+            }
+        };
+
+        match try_verify(verifier, options, trusted_block, &target_block) {
+            Ok(true) => {
+                println!("✓ Successfully verified jump to {}", mid);
+                best_verifiable = Some(mid);
+                // Try to find a further block
+                left = mid + 1;
+            }
+            Ok(false) => {
+                println!("✗ Failed to verify jump to {}", mid);
+                // Try a closer block
+                if mid == 0 {
+                    break;
+                }
+                right = mid - 1;
+            }
+            Err(e) => {
+                println!("✗ ERROR!!! verifying jump to {}: {}", mid, e);
+                if mid == 0 {
+                    break;
+                }
+                right = mid - 1;
+            }
+        }
+    }
+
+    best_verifiable
+}
+
+/// Attempts to verify a target block against a trusted block
+pub fn try_verify(
+    verifier: &ProdVerifier,
+    options: &Options,
+    trusted_block: &LightBlock,
+    target_block: &LightBlock,
+) -> Result<bool, Box<dyn Error>> {
+    // Get verification time (target block time + some buffer)
+    let verify_time = (target_block.time() + Duration::from_secs(20))
+        .map_err(|e| format!("Failed to calculate verify time: {:?}", e))?;
+
+    // Attempt verification
+    let verdict = verifier.verify_update_header(
+        target_block.as_untrusted_state(),
+        trusted_block.as_trusted_state(),
+        options,
+        verify_time,
+    );
+
+    match verdict {
+        Verdict::Success => Ok(true),
+        Verdict::NotEnoughTrust(_) => Ok(false),
+        Verdict::Invalid(e) => {
+            println!("  Invalid verdict: {:?}", e);
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::tm_rpc_utils::TendermintRPCClient;
+use std::error::Error;
+use std::future::Future;
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// A source of the light blocks of a chain, so that path search runs the same over an RPC node,
+/// a headers directory or a synthetic chain.
+pub trait LightBlockProvider {
+    /// The height of the latest block.
+    fn latest_height(&self) -> impl Future<Output = Result<u64, Box<dyn Error>>>;
+
+    /// The light block at `height`.
+    fn light_block(&self, height: u64) -> impl Future<Output = Result<LightBlock, Box<dyn Error>>>;
+}
+
+/// Light blocks of a Tendermint RPC node, fetched as the given peer.
+pub struct RpcProvider {
+    pub client: TendermintRPCClient,
+    pub peer_id: [u8; 20],
+}
+
+impl RpcProvider {
+    /// Fetches the node's peer ID once, for every light block after.
+    pub async fn connect(client: TendermintRPCClient) -> Result<Self, Box<dyn Error>> {
+        let peer_id = client.fetch_peer_id().await?;
+        Ok(RpcProvider { client, peer_id })
+    }
+}
+
+impl LightBlockProvider for RpcProvider {
+    async fn latest_height(&self) -> Result<u64, Box<dyn Error>> {
+        let latest_commit = self.client.fetch_latest_commit().await?;
+        Ok(latest_commit.result.signed_header.header.height.value())
+    }
+
+    async fn light_block(&self, height: u64) -> Result<LightBlock, Box<dyn Error>> {
+        self.client.fetch_light_block(height, self.peer_id).await
+    }
+}
@@ -0,0 +1,169 @@
+//! Responses in the shape of CometBFT's RPC, for the servers that stand in for it.
+
+use axum::{
+    body::Body,
+    http::{header, StatusCode},
+    response::Response,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tendermint::block::signed_header::SignedHeader;
+use tendermint_light_client_verifier::types::{LightBlock, ValidatorSet};
+
+/// CometBFT's cap on `per_page`.
+pub const MAX_PER_PAGE: usize = 100;
+
+/// CometBFT's default `per_page`.
+const DEFAULT_PER_PAGE: usize = 30;
+
+/// JSON-RPC error codes, as CometBFT returns them.
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// The query parameters of a request.
+pub type Params = HashMap<String, String>;
+
+/// An error response, as CometBFT sends for a request it cannot serve.
+pub struct RpcError {
+    code: i64,
+    data: String,
+}
+
+impl RpcError {
+    pub fn internal(data: String) -> Self {
+        RpcError {
+            code: INTERNAL_ERROR,
+            data,
+        }
+    }
+
+    pub fn invalid_params(data: String) -> Self {
+        RpcError {
+            code: INVALID_PARAMS,
+            data,
+        }
+    }
+
+    pub fn method_not_found(path: &str) -> Self {
+        RpcError {
+            code: METHOD_NOT_FOUND,
+            data: format!("unknown method {}", path),
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self.code {
+            METHOD_NOT_FOUND => "Method not found",
+            INVALID_PARAMS => "Invalid params",
+            _ => "Internal error",
+        }
+    }
+}
+
+/// An integer parameter, which CometBFT also accepts quoted.
+pub fn int_param(params: &Params, name: &str) -> Result<Option<u64>, RpcError> {
+    params
+        .get(name)
+        .map(|value| {
+            value
+                .trim_matches('"')
+                .parse::<u64>()
+                .map_err(|e| RpcError::invalid_params(format!("{}: {}", name, e)))
+        })
+        .transpose()
+}
+
+/// The JSON-RPC envelope of a result or error, and the status CometBFT sends it with.
+pub fn envelope(result: Result<Value, RpcError>) -> (StatusCode, Value) {
+    match result {
+        Ok(result) => (
+            StatusCode::OK,
+            json!({ "jsonrpc": "2.0", "id": -1, "result": result }),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            json!({
+                "jsonrpc": "2.0",
+                "id": -1,
+                "error": { "code": e.code, "message": e.message(), "data": e.data },
+            }),
+        ),
+    }
+}
+
+/// A JSON response with `body`.
+pub fn json_response(status: StatusCode, body: Vec<u8>) -> Response {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// The result of `/status` for a node whose blocks run from `earliest` to `latest`.
+pub fn status_result(latest: &LightBlock, earliest: u64, moniker: &str) -> Value {
+    json!({
+        "node_info": {
+            "id": latest.provider.to_string(),
+            "network": latest.signed_header.header.chain_id,
+            "moniker": moniker,
+        },
+        "sync_info": {
+            "latest_block_hash": latest.signed_header.header.hash(),
+            "latest_block_height": latest.height().value().to_string(),
+            "latest_block_time": latest.time(),
+            "earliest_block_height": earliest.to_string(),
+            "catching_up": false,
+        },
+    })
+}
+
+/// The result of `/commit`.
+pub fn commit_result(signed_header: &SignedHeader) -> Value {
+    json!({
+        "signed_header": signed_header,
+        "canonical": true,
+    })
+}
+
+/// The result of `/validators` at `height`, one page of `validators` as the request's `page`
+/// and `per_page` ask.
+pub fn validators_result(
+    height: u64,
+    validators: &ValidatorSet,
+    params: &Params,
+    max_per_page: usize,
+) -> Result<Value, RpcError> {
+    let validators = validators.validators();
+    let per_page = int_param(params, "per_page")?
+        .map_or(DEFAULT_PER_PAGE, |per_page| per_page as usize)
+        .clamp(1, max_per_page);
+    let pages = validators.len().div_ceil(per_page).max(1);
+    let page = int_param(params, "page")?.map_or(1, |page| page as usize);
+    if page < 1 || page > pages {
+        return Err(RpcError::invalid_params(format!(
+            "page should be within [1, {}] range, given {}",
+            pages, page
+        )));
+    }
+    let page: Vec<Value> = validators
+        .iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .map(|validator| {
+            json!({
+                "address": validator.address,
+                "pub_key": validator.pub_key,
+                "voting_power": validator.power().to_string(),
+                "proposer_priority": validator.proposer_priority.value().to_string(),
+            })
+        })
+        .collect();
+    Ok(json!({
+        "block_height": height.to_string(),
+        "validators": page,
+        "count": page.len().to_string(),
+        "total": validators.len().to_string(),
+    }))
+}
@@ -0,0 +1,227 @@
+use crate::provider::LightBlockProvider;
+use std::error::Error;
+use tendermint::Time;
+use tendermint_light_client_verifier::types::LightBlock;
+use tendermint_testgen::{Commit, Generator, Header, LightBlock as TestgenLightBlock, Validator};
+
+/// Voting power of the smallest validator.
+const BASE_POWER: u64 = 10;
+
+/// The shape of a synthetic chain. The same spec always generates the same chain, with commits
+/// signed by deterministic ed25519 keys.
+#[derive(Clone, Debug)]
+pub struct ChainSpec {
+    pub chain_id: String,
+    /// Number of blocks, from height 1.
+    pub blocks: u64,
+    /// Number of validators in every set.
+    pub validators: usize,
+    /// Validators replaced at each rotation, oldest first.
+    pub churn: usize,
+    /// Blocks between rotations.
+    pub churn_interval: u64,
+    /// The oldest validator's voting power as a multiple of the newest's, in between linearly.
+    pub power_skew: u64,
+    /// Unix time of the first block.
+    pub genesis_time: i64,
+    /// Seconds between blocks.
+    pub block_time_secs: u64,
+    /// Tells apart the validator keys of chains that are otherwise the same.
+    pub seed: u32,
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        ChainSpec {
+            chain_id: "synthetic-chain".to_string(),
+            blocks: 100,
+            validators: 4,
+            churn: 0,
+            churn_interval: 10,
+            power_skew: 1,
+            genesis_time: 1_700_000_000,
+            block_time_secs: 6,
+            seed: 0,
+        }
+    }
+}
+
+impl ChainSpec {
+    /// The validator set that signs the block at `height`, oldest first.
+    pub fn validator_set(&self, height: u64) -> Vec<Validator> {
+        let rotations = (height - 1) / self.churn_interval.max(1);
+        let first = rotations as usize * self.churn;
+        let skew = self.power_skew.max(1);
+        let last = self.validators.saturating_sub(1).max(1) as u64;
+        (0..self.validators)
+            .map(|position| {
+                let younger = last.saturating_sub(position as u64);
+                let power = BASE_POWER + BASE_POWER * (skew - 1) * younger / last;
+                Validator::new(&format!("{:x}-{}", self.seed, first + position)).voting_power(power)
+            })
+            .collect()
+    }
+
+    /// The time of the block at `height`.
+    pub fn time(&self, height: u64) -> Time {
+        let offset = (height - 1) * self.block_time_secs;
+        Time::from_unix_timestamp(self.genesis_time + offset as i64, 0).expect("time in range")
+    }
+}
+
+/// Generates every block of the chain, each linked to the one before.
+pub fn generate_chain(spec: &ChainSpec) -> Vec<LightBlock> {
+    let mut blocks = Vec::new();
+    let mut last_block_id_hash = None;
+    for height in 1..=spec.blocks {
+        let validators = spec.validator_set(height);
+        let next_validators = spec.validator_set(height + 1);
+        let mut header = Header::new(&validators)
+            .next_validators(&next_validators)
+            .chain_id(&spec.chain_id)
+            .height(height)
+            .time(spec.time(height));
+        if let Some(hash) = last_block_id_hash {
+            header = header.last_block_id_hash(hash);
+        }
+        let block = TestgenLightBlock::new(header.clone(), Commit::new(header, 1))
+            .validators(&validators)
+            .next_validators(&next_validators)
+            .generate()
+            .expect("synthetic blocks are well formed");
+        last_block_id_hash = Some(block.signed_header.header.hash());
+        blocks.push(LightBlock::new(
+            block.signed_header,
+            block.validators,
+            block.next_validators,
+            block.provider,
+        ));
+    }
+    blocks
+}
+
+/// A synthetic chain held in memory.
+pub struct SyntheticChain {
+    pub blocks: Vec<LightBlock>,
+}
+
+impl SyntheticChain {
+    pub fn new(spec: &ChainSpec) -> Self {
+        SyntheticChain {
+            blocks: generate_chain(spec),
+        }
+    }
+}
+
+impl LightBlockProvider for SyntheticChain {
+    async fn latest_height(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.blocks.len() as u64)
+    }
+
+    async fn light_block(&self, height: u64) -> Result<LightBlock, Box<dyn Error>> {
+        height
+            .checked_sub(1)
+            .and_then(|index| self.blocks.get(index as usize))
+            .cloned()
+            .ok_or_else(|| format!("no block at height {}", height).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_search::{find_path, try_verify, verifier_options};
+    use tendermint_light_client_verifier::ProdVerifier;
+
+    fn churning() -> ChainSpec {
+        ChainSpec {
+            blocks: 60,
+            churn: 2,
+            power_skew: 5,
+            ..Default::default()
+        }
+    }
+
+    fn hashes(blocks: &[LightBlock]) -> Vec<tendermint::Hash> {
+        blocks
+            .iter()
+            .map(|b| b.signed_header.header.hash())
+            .collect()
+    }
+
+    #[test]
+    fn same_spec_same_chain() {
+        let spec = churning();
+        assert_eq!(
+            hashes(&generate_chain(&spec)),
+            hashes(&generate_chain(&spec))
+        );
+        let other = ChainSpec { seed: 1, ..spec };
+        assert_ne!(
+            hashes(&generate_chain(&churning())),
+            hashes(&generate_chain(&other))
+        );
+    }
+
+    #[test]
+    fn every_block_verifies_from_the_one_before() {
+        let spec = churning();
+        let blocks = generate_chain(&spec);
+        assert_eq!(blocks.len(), 60);
+        assert_eq!(blocks[59].time(), spec.time(60));
+        let verifier = ProdVerifier::default();
+        for pair in blocks.windows(2) {
+            assert!(try_verify(&verifier, &verifier_options(), &pair[0], &pair[1]).unwrap());
+            assert_eq!(
+                pair[1].signed_header.header.last_block_id.unwrap().hash,
+                pair[0].signed_header.header.hash()
+            );
+        }
+    }
+
+    #[test]
+    fn sets_churn_and_skew() {
+        let spec = churning();
+        let first = spec.validator_set(1);
+        let rotated = spec.validator_set(11);
+        assert_eq!(first[2..], rotated[..2]);
+        assert!(!rotated.contains(&first[0]));
+        assert_eq!(first[0].voting_power, Some(50));
+        assert_eq!(first[3].voting_power, Some(10));
+    }
+
+    #[tokio::test]
+    async fn path_search_skips_a_static_set() {
+        let chain = SyntheticChain::new(&ChainSpec::default());
+        let path = find_path(
+            &chain,
+            &ProdVerifier::default(),
+            &verifier_options(),
+            1,
+            100,
+            |_| {},
+        )
+        .await
+        .unwrap();
+        let heights: Vec<u64> = path.iter().map(|b| b.height().value()).collect();
+        assert_eq!(heights, vec![1, 100]);
+    }
+
+    #[tokio::test]
+    async fn path_search_steps_through_churn() {
+        let chain = SyntheticChain::new(&churning());
+        let verifier = ProdVerifier::default();
+        let mut found = 0;
+        let path = find_path(&chain, &verifier, &verifier_options(), 1, 60, |_| {
+            found += 1
+        })
+        .await
+        .unwrap();
+        assert_eq!(found, path.len());
+        assert!(path.len() > 2);
+        assert_eq!(path.last().unwrap().height().value(), 60);
+        for pair in path.windows(2) {
+            assert!(try_verify(&verifier, &verifier_options(), &pair[0], &pair[1]).unwrap());
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use scraper::archive::router;
+use scraper::headers_dir::save_light_block;
+use scraper::synthetic::{generate_chain, ChainSpec};
+use scraper::tm_rpc_utils::TendermintRPCClient;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tendermint_light_client_verifier::types::LightBlock;
+
+/// A headers directory of a synthetic chain's blocks at `heights`.
+fn archive(name: &str, heights: &[u64]) -> (PathBuf, Vec<LightBlock>) {
+    let dir = std::env::temp_dir().join(format!("archive-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let blocks = generate_chain(&ChainSpec {
+        blocks: 40,
+        validators: 3,
+        churn: 1,
+        ..Default::default()
+    });
+    for height in heights {
+        save_light_block(&blocks[*height as usize - 1], &dir).unwrap();
+    }
+    (dir, blocks)
+}
+
+async fn serve(dir: &Path) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let router = router(dir.to_path_buf());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    url
+}
+
+#[tokio::test]
+async fn client_fetches_archived_light_blocks() {
+    let (dir, blocks) = archive("fetch", &[1, 15, 30]);
+    let client = TendermintRPCClient::new(serve(&dir).await);
+
+    let peer_id = client.fetch_peer_id().await.unwrap();
+    assert_eq!(client.get_latest_block_height().await, 30);
+    for height in [1, 15, 30] {
+        let fetched = client.fetch_light_block(height, peer_id).await.unwrap();
+        let expected = &blocks[height as usize - 1];
+        assert_eq!(
+            fetched.signed_header.header.hash(),
+            expected.signed_header.header.hash()
+        );
+        assert_eq!(fetched.validators.hash(), expected.validators.hash());
+        assert_eq!(
+            fetched.next_validators.hash(),
+            expected.next_validators.hash()
+        );
+    }
+    assert!(client.fetch_light_block(20, peer_id).await.is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn path_lists_every_archived_header() {
+    let (dir, blocks) = archive("path", &[1, 15, 30]);
+    let url = serve(&dir).await;
+
+    let path: serde_json::Value = reqwest::get(format!("{}/path", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let listed = path["result"]["blocks"].as_array().unwrap();
+    assert_eq!(path["result"]["total"], "3");
+    assert_eq!(listed[1]["height"], "15");
+    assert_eq!(
+        listed[1]["hash"],
+        blocks[14].signed_header.header.hash().to_string()
+    );
+
+    // Headers added after the server starts are served too.
+    save_light_block(&blocks[39], &dir).unwrap();
+    let client = TendermintRPCClient::new(url);
+    assert_eq!(client.get_latest_block_height().await, 40);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn path_follows_rewritten_headers() {
+    let (dir, blocks) = archive("rewritten", &[1, 15]);
+    let url = serve(&dir).await;
+    let hash_at_15 = || async {
+        let path: serde_json::Value = reqwest::get(format!("{}/path", url))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        path["result"]["blocks"][1]["hash"].clone()
+    };
+    assert_eq!(
+        hash_at_15().await,
+        blocks[14].signed_header.header.hash().to_string()
+    );
+
+    // Another chain's header at the same height replaces the one read before.
+    let other = generate_chain(&ChainSpec {
+        blocks: 15,
+        validators: 4,
+        ..Default::default()
+    });
+    save_light_block(&other[14], &dir).unwrap();
+    assert_eq!(
+        hash_at_15().await,
+        other[14].signed_header.header.hash().to_string()
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn archive_is_read_only() {
+    let (dir, _) = archive("read-only", &[1]);
+    let url = serve(&dir).await;
+
+    let client = reqwest::Client::new();
+    let response = client.post(format!("{}/commit", url)).send().await.unwrap();
+    assert_eq!(response.status(), 405);
+
+    let response = reqwest::get(format!("{}/broadcast_tx_sync", url))
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["message"], "Method not found");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,212 @@
+use scraper::mock_rpc::{Faults, MockRpc};
+use scraper::path_search::{find_path, try_verify, verifier_options};
+use scraper::provider::RpcProvider;
+use scraper::synthetic::{generate_chain, ChainSpec, SyntheticChain};
+use scraper::tm_rpc_utils::TendermintRPCClient;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tendermint_light_client_verifier::{types::LightBlock, ProdVerifier};
+
+fn spec() -> ChainSpec {
+    ChainSpec {
+        blocks: 30,
+        validators: 5,
+        churn: 2,
+        power_skew: 3,
+        ..Default::default()
+    }
+}
+
+async fn serve(blocks: Vec<LightBlock>, faults: Faults) -> (Arc<MockRpc>, String) {
+    let rpc = Arc::new(MockRpc::new(blocks, faults).max_per_page(2));
+    let url = rpc.clone().spawn().await.unwrap();
+    (rpc, url)
+}
+
+fn assert_same_block(fetched: &LightBlock, expected: &LightBlock) {
+    assert_eq!(
+        fetched.signed_header.header.hash(),
+        expected.signed_header.header.hash()
+    );
+    assert_eq!(
+        fetched.signed_header.commit.block_id,
+        expected.signed_header.commit.block_id
+    );
+    assert_eq!(fetched.validators.hash(), expected.validators.hash());
+    assert_eq!(
+        fetched.next_validators.hash(),
+        expected.next_validators.hash()
+    );
+    assert_eq!(fetched.provider, expected.provider);
+}
+
+#[tokio::test]
+async fn light_blocks_round_trip_across_validator_pages() {
+    let blocks = generate_chain(&spec());
+    let (_, url) = serve(blocks.clone(), Faults::default()).await;
+    let client = TendermintRPCClient::new(url);
+
+    let peer_id = client.fetch_peer_id().await.unwrap();
+    assert_eq!(client.get_latest_block_height().await, 30);
+    for height in [1, 10, 11, 30] {
+        let fetched = client.fetch_light_block(height, peer_id).await.unwrap();
+        assert_same_block(&fetched, &blocks[height as usize - 1]);
+    }
+
+    let hash = blocks[11].signed_header.header.hash();
+    assert_eq!(client.get_block_height_from_hash(hash.as_bytes()).await, 12);
+}
+
+#[tokio::test]
+async fn genesis_describes_the_first_block() {
+    let blocks = generate_chain(&spec());
+    let (_, url) = serve(blocks, Faults::default()).await;
+    let genesis: serde_json::Value = reqwest::get(format!("{}/genesis", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let genesis = &genesis["result"]["genesis"];
+    assert_eq!(genesis["chain_id"], "synthetic-chain");
+    assert_eq!(genesis["initial_height"], "1");
+    assert_eq!(genesis["validators"].as_array().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn genesis_of_no_blocks_is_an_error() {
+    let (_, url) = serve(Vec::new(), Faults::default()).await;
+    let genesis: serde_json::Value = reqwest::get(format!("{}/genesis", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(genesis["error"]["data"], "no blocks");
+}
+
+#[tokio::test]
+async fn path_search_over_rpc_matches_path_search_in_memory() {
+    let chain = SyntheticChain::new(&spec());
+    let (_, url) = serve(chain.blocks.clone(), Faults::default()).await;
+    let provider = RpcProvider::connect(TendermintRPCClient::new(url))
+        .await
+        .unwrap();
+
+    let verifier = ProdVerifier::default();
+    let heights = |path: Vec<LightBlock>| -> Vec<u64> {
+        path.iter().map(|block| block.height().value()).collect()
+    };
+    let over_rpc = find_path(&provider, &verifier, &verifier_options(), 1, 30, |_| {})
+        .await
+        .unwrap();
+    let in_memory = find_path(&chain, &verifier, &verifier_options(), 1, 30, |_| {})
+        .await
+        .unwrap();
+    assert!(over_rpc.len() > 2);
+    assert_eq!(heights(over_rpc), heights(in_memory));
+}
+
+#[tokio::test]
+async fn pruned_heights_are_errors() {
+    let faults = Faults {
+        pruned_below: 10,
+        ..Default::default()
+    };
+    let (_, url) = serve(generate_chain(&spec()), faults).await;
+    let client = TendermintRPCClient::new(url.clone());
+    let peer_id = client.fetch_peer_id().await.unwrap();
+
+    assert!(client.fetch_light_block(5, peer_id).await.is_err());
+    assert!(client.fetch_light_block(10, peer_id).await.is_ok());
+
+    let response = reqwest::get(format!("{}/commit?height=5", url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 500);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body["error"]["data"],
+        "height 5 is not available, lowest height is 10"
+    );
+}
+
+#[tokio::test]
+async fn every_nth_request_is_rate_limited() {
+    let faults = Faults {
+        error_status: Some(429),
+        error_every: 2,
+        ..Default::default()
+    };
+    let (rpc, url) = serve(generate_chain(&spec()), faults).await;
+    let client = TendermintRPCClient::new(url.clone());
+
+    assert!(client.fetch_commit(3).await.is_ok());
+    assert!(client.fetch_commit(3).await.is_err());
+    assert!(client.fetch_commit(3).await.is_ok());
+
+    let response = reqwest::get(format!("{}/status", url)).await.unwrap();
+    assert_eq!(rpc.requests(), 4);
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers()["retry-after"], "1");
+}
+
+#[tokio::test]
+async fn server_errors_fail_requests() {
+    let faults = Faults {
+        error_status: Some(503),
+        error_every: 1,
+        ..Default::default()
+    };
+    let (_, url) = serve(generate_chain(&spec()), faults).await;
+    let response = reqwest::get(format!("{}/status", url)).await.unwrap();
+    assert_eq!(response.status(), 503);
+    assert!(TendermintRPCClient::new(url).fetch_peer_id().await.is_err());
+}
+
+#[tokio::test]
+async fn truncated_json_is_an_error() {
+    let faults = Faults {
+        truncate_every: 2,
+        ..Default::default()
+    };
+    let (_, url) = serve(generate_chain(&spec()), faults).await;
+    let client = TendermintRPCClient::new(url);
+
+    assert!(client.fetch_latest_commit().await.is_ok());
+    assert!(client.fetch_latest_commit().await.is_err());
+    assert!(client.fetch_latest_commit().await.is_ok());
+}
+
+#[tokio::test]
+async fn lying_header_fails_verification() {
+    let faults = Faults {
+        lying_heights: [20].into(),
+        ..Default::default()
+    };
+    let (_, url) = serve(generate_chain(&spec()), faults).await;
+    let client = TendermintRPCClient::new(url);
+    let peer_id = client.fetch_peer_id().await.unwrap();
+
+    let verifier = ProdVerifier::default();
+    let trusted = client.fetch_light_block(19, peer_id).await.unwrap();
+    let honest = client.fetch_light_block(21, peer_id).await.unwrap();
+    let lying = client.fetch_light_block(20, peer_id).await.unwrap();
+    assert!(try_verify(&verifier, &verifier_options(), &trusted, &honest).unwrap());
+    assert!(!try_verify(&verifier, &verifier_options(), &trusted, &lying).unwrap());
+}
+
+#[tokio::test]
+async fn latency_delays_every_response() {
+    let faults = Faults {
+        latency: Duration::from_millis(200),
+        ..Default::default()
+    };
+    let (_, url) = serve(generate_chain(&spec()), faults).await;
+    let client = TendermintRPCClient::new(url);
+
+    let start = Instant::now();
+    client.fetch_peer_id().await.unwrap();
+    client.fetch_commit(1).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
@@ -0,0 +1,110 @@
+//! Checks Celestia state against a block's `app_hash`, with the ICS23 proofs `abci_query` returns:
+//! one of the key in its store's IAVL tree, and one of the store's root in the multistore.
+
+use core::fmt;
+
+use ics23::{
+    calculate_existence_root, commitment_proof::Proof, iavl_spec, tendermint_spec,
+    verify_membership, verify_non_membership, CommitmentProof, HostFunctionsManager,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// A key's value in one store, or its absence, with the proofs `abci_query` returns for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateProof {
+    /// Name of the store, e.g. `bank`.
+    pub store: String,
+    pub key: Vec<u8>,
+    /// `None` proves the key absent.
+    pub value: Option<Vec<u8>>,
+    /// Protobuf encoded `CommitmentProof` of the key in the store's IAVL tree.
+    pub store_proof: Vec<u8>,
+    /// Protobuf encoded `CommitmentProof` of the store's root in the multistore, whose root is the
+    /// `app_hash`.
+    pub multistore_proof: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StateProofError {
+    MalformedProof(&'static str),
+    InvalidStoreProof,
+    InvalidMultistoreProof,
+}
+
+impl fmt::Display for StateProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateProofError::MalformedProof(reason) => write!(f, "malformed proof: {}", reason),
+            StateProofError::InvalidStoreProof => write!(f, "store proof does not verify"),
+            StateProofError::InvalidMultistoreProof => {
+                write!(f, "multistore proof does not verify")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateProofError {}
+
+impl StateProof {
+    /// Checks the key's value, or its absence, against the `app_hash` of a header.
+    pub fn verify(&self, app_hash: &[u8]) -> Result<(), StateProofError> {
+        let store_proof = decode(&self.store_proof)?;
+        let store_root = store_root(&store_proof)?;
+        let valid = match &self.value {
+            Some(value) => verify_membership::<HostFunctionsManager>(
+                &store_proof,
+                &iavl_spec(),
+                &store_root,
+                &self.key,
+                value,
+            ),
+            None => verify_non_membership::<HostFunctionsManager>(
+                &store_proof,
+                &iavl_spec(),
+                &store_root,
+                &self.key,
+            ),
+        };
+        if !valid {
+            return Err(StateProofError::InvalidStoreProof);
+        }
+
+        let multistore_proof = decode(&self.multistore_proof)?;
+        if !verify_membership::<HostFunctionsManager>(
+            &multistore_proof,
+            &tendermint_spec(),
+            &app_hash.to_vec(),
+            self.store.as_bytes(),
+            &store_root,
+        ) {
+            return Err(StateProofError::InvalidMultistoreProof);
+        }
+        Ok(())
+    }
+}
+
+fn decode(proof: &[u8]) -> Result<CommitmentProof, StateProofError> {
+    CommitmentProof::decode(proof)
+        .map_err(|_| StateProofError::MalformedProof("not a CommitmentProof"))
+}
+
+/// The root the store proof leads to. An absence proof leads there through either neighbour;
+/// `verify_non_membership` checks that both agree.
+fn store_root(proof: &CommitmentProof) -> Result<Vec<u8>, StateProofError> {
+    let exist = match &proof.proof {
+        Some(Proof::Exist(exist)) => exist,
+        Some(Proof::Nonexist(nonexist)) => {
+            nonexist.left.as_ref().or(nonexist.right.as_ref()).ok_or(
+                StateProofError::MalformedProof("absence proof without neighbours"),
+            )?
+        }
+        _ => {
+            return Err(StateProofError::MalformedProof(
+                "expected an existence or absence proof",
+            ))
+        }
+    };
+    calculate_existence_root::<HostFunctionsManager>(exist)
+        .map_err(|_| StateProofError::InvalidStoreProof)
+}
@@ -0,0 +1,123 @@
+//! WebAssembly bindings around the `verifier` crate, for light nodes running in the browser.
+
+use common::Groth16VkeyCheckpoint;
+use tendermint::block::Header;
+use verifier::{verify_groth16_proof, TrustedSetup};
+use wasm_bindgen::prelude::*;
+
+/// Verifies recursion proofs for one chain.
+#[wasm_bindgen]
+pub struct ProofVerifier {
+    genesis_hash: [u8; 32],
+    program_vks: Vec<[u32; 8]>,
+    groth16_vks: Vec<Vec<u8>>,
+    checkpoints: Vec<Groth16VkeyCheckpoint>,
+    /// The Groth16 vk proofs are wrapped under, if not the one of this SP1 version.
+    wrapper_vk: Option<Vec<u8>>,
+}
+
+/// The head proven by a valid proof.
+#[wasm_bindgen(getter_with_clone)]
+pub struct ProvenHead {
+    /// Upper-case hex hash of the head header.
+    pub hash: String,
+    pub height: u64,
+    /// RFC 3339 timestamp of the head header.
+    pub time: String,
+    /// Upper-case hex root of every head verified on the way to this one.
+    #[wasm_bindgen(js_name = pathRoot)]
+    pub path_root: String,
+}
+
+#[wasm_bindgen]
+impl ProofVerifier {
+    /// Creates a verifier for the chain starting at `genesis_hash` (32 bytes).
+    #[wasm_bindgen(constructor)]
+    pub fn new(genesis_hash: &[u8]) -> Result<ProofVerifier, JsError> {
+        let genesis_hash = genesis_hash
+            .try_into()
+            .map_err(|_| JsError::new("genesis hash must be 32 bytes"))?;
+        Ok(ProofVerifier {
+            genesis_hash,
+            program_vks: Vec::new(),
+            groth16_vks: Vec::new(),
+            checkpoints: Vec::new(),
+            wrapper_vk: None,
+        })
+    }
+
+    /// Trusts a program version, given as the eight words of its vk digest.
+    #[wasm_bindgen(js_name = trustProgramVk)]
+    pub fn trust_program_vk(&mut self, vk_digest: &[u32]) -> Result<(), JsError> {
+        let vk_digest = vk_digest
+            .try_into()
+            .map_err(|_| JsError::new("vk digest must be 8 words"))?;
+        self.program_vks.push(vk_digest);
+        Ok(())
+    }
+
+    /// Trusts a groth16 vk used to cross an upgrade.
+    #[wasm_bindgen(js_name = trustGroth16Vk)]
+    pub fn trust_groth16_vk(&mut self, groth16_vk: &[u8]) {
+        self.groth16_vks.push(groth16_vk.to_vec());
+    }
+
+    /// Sets the Groth16 vk that proofs are wrapped under, for proofs wrapped by another SP1 version
+    /// than the one this was built with.
+    #[wasm_bindgen(js_name = setWrapperVk)]
+    pub fn set_wrapper_vk(&mut self, groth16_vk: &[u8]) {
+        self.wrapper_vk = Some(groth16_vk.to_vec());
+    }
+
+    /// Sets the chain's checkpoint history, as the JSON array `create_checkpoint` writes. Proofs
+    /// only commit its hash. Chains that never upgraded have none.
+    #[wasm_bindgen(js_name = setCheckpoints)]
+    pub fn set_checkpoints(&mut self, checkpoints_json: &str) -> Result<(), JsError> {
+        self.checkpoints = serde_json::from_str(checkpoints_json)
+            .map_err(|e| JsError::new(&format!("invalid checkpoints: {}", e)))?;
+        Ok(())
+    }
+
+    /// Verifies a groth16 proof (`SP1ProofWithPublicValues::bytes`) and its public values, and
+    /// returns the head it proves.
+    ///
+    /// Height and time are not part of the public values, so the caller also passes the head
+    /// header as CometBFT JSON. Its hash must match the proven head hash.
+    pub fn verify(
+        &self,
+        proof: &[u8],
+        public_values: &[u8],
+        head_header_json: &str,
+    ) -> Result<ProvenHead, JsError> {
+        let groth16_vks: Vec<&[u8]> = self.groth16_vks.iter().map(Vec::as_slice).collect();
+        let trusted = TrustedSetup {
+            genesis_hash: self.genesis_hash,
+            program_vks: &self.program_vks,
+            groth16_vks: &groth16_vks,
+        };
+
+        let head = verify_groth16_proof(
+            proof,
+            public_values,
+            self.wrapper_vk
+                .as_deref()
+                .unwrap_or(&sp1_verifier::GROTH16_VK_BYTES),
+            &self.checkpoints,
+            &trusted,
+        )
+        .map_err(|e| JsError::new(&e.to_string()))?;
+
+        let header: Header = serde_json::from_str(head_header_json)
+            .map_err(|e| JsError::new(&format!("invalid head header: {}", e)))?;
+        if header.hash().as_bytes() != head.head_hash {
+            return Err(JsError::new("head header does not match the proven head hash"));
+        }
+
+        Ok(ProvenHead {
+            hash: hex::encode_upper(head.head_hash),
+            height: header.height.value(),
+            time: header.time.to_rfc3339(),
+            path_root: hex::encode_upper(head.path_root),
+        })
+    }
+}
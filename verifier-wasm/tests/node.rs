@@ -0,0 +1,125 @@
+//! Run with `wasm-pack test --node verifier-wasm` (or `cargo test --target wasm32-unknown-unknown`
+//! with `wasm-bindgen-test-runner` installed).
+#![cfg(target_arch = "wasm32")]
+
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint, RecursionPublicValues};
+use verifier_wasm::ProofVerifier;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+const GENESIS: [u8; 32] = [1; 32];
+const PROGRAM_VK: [u32; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+// A header whose hash is irrelevant: every case below fails before the header is looked at.
+const HEADER: &str = "{}";
+
+/// A Groth16 proof of recursion public values under a test Groth16 vk, with the head header it
+/// proves. Real recursion proofs need the prover; see the verifier crate's fixtures.
+const RECURSION: &str = include_str!("../../verifier/tests/fixtures/recursion_groth16.json");
+
+const CHECKPOINTS: &str = r#"[{"block_hash":[2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2],"groth16_vk":null,"program_vk_hash":[1,2,3,4,5,6,7,8]}]"#;
+
+fn public_values(genesis_hash: [u8; 32], vk_digest: [u32; 8]) -> Vec<u8> {
+    bincode::serialize(&RecursionPublicValues {
+        checkpoints_hash: checkpoint_history_hash(&[Groth16VkeyCheckpoint {
+            block_hash: [2; 32],
+            groth16_vk: None,
+            program_vk_hash: PROGRAM_VK,
+        }]),
+        genesis_hash: genesis_hash.to_vec(),
+        h2_hash: vec![3; 32],
+        vk_digest,
+        path_root: [4; 32],
+    })
+    .unwrap()
+}
+
+fn trusting_verifier() -> ProofVerifier {
+    let mut verifier = ProofVerifier::new(&GENESIS).unwrap();
+    verifier.trust_program_vk(&PROGRAM_VK).unwrap();
+    verifier.set_checkpoints(CHECKPOINTS).unwrap();
+    verifier
+}
+
+fn error_message<T>(result: Result<T, wasm_bindgen::JsError>) -> String {
+    let error: JsValue = result.err().expect("expected an error").into();
+    String::from(js_sys::Error::from(error).message())
+}
+
+#[wasm_bindgen_test]
+fn returns_the_proven_head() {
+    let fixture: serde_json::Value = serde_json::from_str(RECURSION).unwrap();
+    let bytes = |field: &str| hex::decode(fixture[field].as_str().unwrap()).unwrap();
+    let program_vk: Vec<u32> = serde_json::from_value(fixture["program_vk"].clone()).unwrap();
+    let header = fixture["head_header"].to_string();
+
+    let mut verifier = ProofVerifier::new(&bytes("genesis_hash")).unwrap();
+    verifier.trust_program_vk(&program_vk).unwrap();
+    verifier.set_wrapper_vk(&bytes("groth16_vk"));
+    let head = verifier
+        .verify(&bytes("proof"), &bytes("public_values"), &header)
+        .unwrap();
+    let expected: tendermint::block::Header =
+        serde_json::from_value(fixture["head_header"].clone()).unwrap();
+    assert_eq!(head.hash, expected.hash().to_string());
+    assert_eq!(head.height, 42);
+    assert_eq!(head.time, "2023-11-14T22:17:32Z");
+    assert_eq!(head.path_root, hex::encode_upper([7; 32]));
+
+    // The same proof under this SP1 version's vk does not verify.
+    let mut verifier = ProofVerifier::new(&bytes("genesis_hash")).unwrap();
+    verifier.trust_program_vk(&program_vk).unwrap();
+    let message = error_message(verifier.verify(&bytes("proof"), &bytes("public_values"), &header));
+    assert!(
+        message.starts_with("groth16 verification failed"),
+        "{}",
+        message
+    );
+}
+
+#[wasm_bindgen_test]
+fn rejects_short_genesis_hash() {
+    assert!(ProofVerifier::new(&[0; 31]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn rejects_short_vk_digest() {
+    let mut verifier = ProofVerifier::new(&GENESIS).unwrap();
+    assert!(verifier.trust_program_vk(&[1, 2, 3]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn rejects_malformed_public_values() {
+    let message = error_message(trusting_verifier().verify(&[], &[0; 7], HEADER));
+    assert_eq!(message, "malformed public values");
+}
+
+#[wasm_bindgen_test]
+fn rejects_other_genesis() {
+    let values = public_values([9; 32], PROGRAM_VK);
+    let message = error_message(trusting_verifier().verify(&[], &values, HEADER));
+    assert_eq!(message, "genesis hash does not match");
+}
+
+#[wasm_bindgen_test]
+fn rejects_untrusted_program_vk() {
+    let values = public_values(GENESIS, [8; 8]);
+    let message = error_message(trusting_verifier().verify(&[], &values, HEADER));
+    assert!(message.contains("is not trusted"), "{}", message);
+}
+
+#[wasm_bindgen_test]
+fn rejects_other_checkpoint_history() {
+    let mut verifier = trusting_verifier();
+    verifier.set_checkpoints("[]").unwrap();
+    let values = public_values(GENESIS, PROGRAM_VK);
+    let message = error_message(verifier.verify(&[], &values, HEADER));
+    assert_eq!(message, "checkpoint history does not match the proof");
+}
+
+#[wasm_bindgen_test]
+fn rejects_invalid_proof() {
+    let values = public_values(GENESIS, PROGRAM_VK);
+    let message = error_message(trusting_verifier().verify(&[0; 260], &values, HEADER));
+    assert!(message.starts_with("groth16 verification failed"), "{}", message);
+}
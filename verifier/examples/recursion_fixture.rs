@@ -0,0 +1,101 @@
+//! Regenerates `tests/fixtures/recursion_groth16.json`:
+//!
+//! ```sh
+//! cargo run -p verifier --example recursion_fixture > verifier/tests/fixtures/recursion_groth16.json
+//! ```
+//!
+//! Real recursion proofs need the prover, so this makes a Groth16 proof of recursion public values
+//! under a test Groth16 vk whose trapdoor is known, in the byte formats sp1-verifier reads. The
+//! program vk is the one in `fibonacci_groth16.json`.
+use bn::{AffineG1, AffineG2, Fr, Group, G1, G2};
+use common::RecursionPublicValues;
+use sha2::{Digest, Sha256};
+use sp1_verifier::converter::{
+    compress_g1_point_to_x, compress_g2_point_to_x, g1_point_to_uncompressed_bytes,
+    g2_point_to_uncompressed_bytes,
+};
+use sp1_verifier::{decode_sp1_vkey_hash, hash_public_inputs, Groth16Verifier};
+use tendermint::Time;
+use tendermint_testgen::{Generator, Header, LightBlock, Validator};
+
+const PROGRAM_VK: [u32; 8] = [
+    1929654368, 520035219, 151015640, 232503417, 866605631, 1630354369, 60754710, 397020881,
+];
+
+fn fr(s: &str) -> Fr {
+    Fr::from_str(s).unwrap()
+}
+
+fn g1(x: Fr) -> AffineG1 {
+    AffineG1::from_jacobian(G1::one() * x).unwrap()
+}
+
+fn g2(x: Fr) -> AffineG2 {
+    AffineG2::from_jacobian(G2::one() * x).unwrap()
+}
+
+fn main() {
+    let validators = [
+        Validator::new("a").voting_power(40),
+        Validator::new("b").voting_power(30),
+        Validator::new("c").voting_power(30),
+    ];
+    let block = |height: u64, time: i64| {
+        let header = Header::new(&validators)
+            .height(height)
+            .chain_id("test-chain")
+            .time(Time::from_unix_timestamp(time, 0).unwrap());
+        LightBlock::new_default_with_header(header).generate().unwrap()
+    };
+    let genesis = block(1, 1_700_000_000);
+    let head = block(42, 1_700_000_252);
+
+    let values = RecursionPublicValues {
+        checkpoints_hash: [0; 32],
+        genesis_hash: genesis.signed_header.header.hash().as_bytes().to_vec(),
+        h2_hash: head.signed_header.header.hash().as_bytes().to_vec(),
+        vk_digest: PROGRAM_VK,
+        path_root: [7; 32],
+    };
+    let public_values = bincode::serialize(&values).unwrap();
+    let vkey_hash = verifier::program_vk_hash_hex(&PROGRAM_VK);
+
+    // The trapdoor: the vk is these scalars times the generators.
+    let (alpha, beta, gamma, delta) = (fr("1111"), fr("2222"), fr("3333"), fr("4444"));
+    let k = [fr("5555"), fr("6666"), fr("7777")];
+    let mut vk = Vec::new();
+    vk.extend(compress_g1_point_to_x(&g1(alpha)).unwrap());
+    vk.extend(compress_g1_point_to_x(&g1(beta)).unwrap());
+    vk.extend(compress_g2_point_to_x(&g2(beta)).unwrap());
+    vk.extend(compress_g2_point_to_x(&g2(gamma)).unwrap());
+    vk.extend(compress_g1_point_to_x(&g1(delta)).unwrap());
+    vk.extend(compress_g2_point_to_x(&g2(delta)).unwrap());
+    vk.extend(3u32.to_be_bytes());
+    for ki in k {
+        vk.extend(compress_g1_point_to_x(&g1(ki)).unwrap());
+    }
+
+    // Pick A and B, then solve the pairing equation for C.
+    let x1 = Fr::from_slice(&decode_sp1_vkey_hash(&vkey_hash).unwrap()).unwrap();
+    let x2 = Fr::from_slice(&hash_public_inputs(&public_values)).unwrap();
+    let l = k[0] + x1 * k[1] + x2 * k[2];
+    let (a, b) = (fr("8888"), fr("9999"));
+    let c = (a * b - alpha * beta - l * gamma) * delta.inverse().unwrap();
+
+    let mut proof = Sha256::digest(&vk)[..4].to_vec();
+    proof.extend(g1_point_to_uncompressed_bytes(&g1(a)).unwrap());
+    proof.extend(g2_point_to_uncompressed_bytes(&g2(b)).unwrap());
+    proof.extend(g1_point_to_uncompressed_bytes(&g1(c)).unwrap());
+
+    Groth16Verifier::verify(&proof, &public_values, &vkey_hash, &vk).expect("the proof verifies");
+
+    let fixture = serde_json::json!({
+        "proof": hex::encode(&proof),
+        "public_values": hex::encode(&public_values),
+        "groth16_vk": hex::encode(&vk),
+        "program_vk": PROGRAM_VK,
+        "genesis_hash": hex::encode(&values.genesis_hash),
+        "head_header": head.signed_header.header,
+    });
+    println!("{}", serde_json::to_string_pretty(&fixture).unwrap());
+}
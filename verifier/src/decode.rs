@@ -0,0 +1,81 @@
+//! A decoder for the bincode encoding of [`RecursionPublicValues`].
+//!
+//! bincode itself needs std, so this reads the handful of types the program commits by hand:
+//! little-endian fixed-width integers, fixed-size arrays as they are and `u64` length prefixes for
+//! vectors.
+
+use alloc::vec::Vec;
+use common::RecursionPublicValues;
+
+use crate::VerifyError;
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VerifyError> {
+        if self.data.len() < len {
+            return Err(VerifyError::MalformedPublicValues);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, VerifyError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_len(&mut self) -> Result<usize, VerifyError> {
+        let bytes = self.take(8)?;
+        let len = u64::from_le_bytes(bytes.try_into().unwrap());
+        // Every element takes at least one byte, so anything longer than the rest of the input is
+        // malformed. Checking here avoids huge allocations on hostile input.
+        if len > self.data.len() as u64 {
+            return Err(VerifyError::MalformedPublicValues);
+        }
+        Ok(len as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, VerifyError> {
+        let len = self.read_len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn read_digest(&mut self) -> Result<[u32; 8], VerifyError> {
+        let mut digest = [0u32; 8];
+        for word in digest.iter_mut() {
+            *word = self.read_u32()?;
+        }
+        Ok(digest)
+    }
+
+    fn read_hash(&mut self) -> Result<[u8; 32], VerifyError> {
+        Ok(self.take(32)?.try_into().unwrap())
+    }
+}
+
+/// Decodes the public values committed by the recursion program.
+pub fn decode_public_values(data: &[u8]) -> Result<RecursionPublicValues, VerifyError> {
+    let mut reader = Reader { data };
+
+    let checkpoints_hash = reader.read_hash()?;
+    let genesis_hash = reader.read_bytes()?;
+    let h2_hash = reader.read_bytes()?;
+    let vk_digest = reader.read_digest()?;
+    let path_root = reader.read_hash()?;
+
+    if !reader.data.is_empty() {
+        return Err(VerifyError::MalformedPublicValues);
+    }
+
+    Ok(RecursionPublicValues {
+        checkpoints_hash,
+        genesis_hash,
+        h2_hash,
+        vk_digest,
+        path_root,
+    })
+}
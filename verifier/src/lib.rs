@@ -0,0 +1,382 @@
+//! Verifies Groth16-wrapped recursion proofs without the SP1 SDK, so that rollups and bridges can
+//! compile the check into their own runtimes.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use common::{checkpoint_history_hash, Groth16VkeyCheckpoint};
+use core::fmt;
+use sp1_verifier::{Groth16Error, Groth16Verifier};
+
+mod decode;
+pub use decode::decode_public_values;
+
+/// What the caller trusts before looking at a proof.
+pub struct TrustedSetup<'a> {
+    /// Hash of the chain's genesis header.
+    pub genesis_hash: [u8; 32],
+    /// Digests of every program version the caller accepts, current and past.
+    pub program_vks: &'a [[u32; 8]],
+    /// Groth16 vks the caller accepts for upgrades that verified the previous proof with groth16.
+    pub groth16_vks: &'a [&'a [u8]],
+}
+
+/// The head of the chain, as attested by a valid proof.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifiedHead {
+    pub genesis_hash: [u8; 32],
+    /// Hash of the last header verified by the recursion (h2 of the final hop).
+    pub head_hash: [u8; 32],
+    /// Digest of the program that produced the proof.
+    pub program_vk: [u32; 8],
+    /// Root of every head verified along the way, for checking `common::mmr::PathProof`s against.
+    pub path_root: [u8; 32],
+}
+
+#[derive(Debug)]
+pub enum VerifyError {
+    MalformedPublicValues,
+    GenesisHashMismatch,
+    /// The checkpoint history given does not hash to the one the proof commits.
+    CheckpointHistoryMismatch,
+    UntrustedProgramVk([u32; 8]),
+    /// A checkpoint at this position in the history is not acceptable.
+    InvalidCheckpoint(usize, &'static str),
+    Groth16(Groth16Error),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::MalformedPublicValues => write!(f, "malformed public values"),
+            VerifyError::GenesisHashMismatch => write!(f, "genesis hash does not match"),
+            VerifyError::CheckpointHistoryMismatch => {
+                write!(f, "checkpoint history does not match the proof")
+            }
+            VerifyError::UntrustedProgramVk(digest) => {
+                write!(f, "program vk {:?} is not trusted", digest)
+            }
+            VerifyError::InvalidCheckpoint(index, reason) => {
+                write!(f, "checkpoint {}: {}", index, reason)
+            }
+            VerifyError::Groth16(e) => write!(f, "groth16 verification failed: {}", e),
+        }
+    }
+}
+
+/// Verifies a Groth16-wrapped recursion proof and the history it commits to.
+///
+/// `proof` is the encoded proof as returned by `SP1ProofWithPublicValues::bytes`, and
+/// `groth16_vk` the Groth16 vk of the SP1 version that wrapped it. The proof only commits the
+/// hash of its checkpoint history, so the history itself is passed in as `checkpoints`.
+pub fn verify_groth16_proof(
+    proof: &[u8],
+    public_values: &[u8],
+    groth16_vk: &[u8],
+    checkpoints: &[Groth16VkeyCheckpoint],
+    trusted: &TrustedSetup,
+) -> Result<VerifiedHead, VerifyError> {
+    let values = decode_public_values(public_values)?;
+
+    if values.genesis_hash.as_slice() != trusted.genesis_hash {
+        return Err(VerifyError::GenesisHashMismatch);
+    }
+    let head_hash: [u8; 32] = values
+        .h2_hash
+        .as_slice()
+        .try_into()
+        .map_err(|_| VerifyError::MalformedPublicValues)?;
+
+    if !trusted.program_vks.contains(&values.vk_digest) {
+        return Err(VerifyError::UntrustedProgramVk(values.vk_digest));
+    }
+
+    if checkpoint_history_hash(checkpoints) != values.checkpoints_hash {
+        return Err(VerifyError::CheckpointHistoryMismatch);
+    }
+    check_checkpoints(checkpoints, trusted)?;
+
+    Groth16Verifier::verify(
+        proof,
+        public_values,
+        &program_vk_hash_hex(&values.vk_digest),
+        groth16_vk,
+    )
+    .map_err(VerifyError::Groth16)?;
+
+    Ok(VerifiedHead {
+        genesis_hash: trusted.genesis_hash,
+        head_hash,
+        program_vk: values.vk_digest,
+        path_root: values.path_root,
+    })
+}
+
+/// Walks the upgrade history. Every program that ever extended the chain must be trusted, and so
+/// must every groth16 vk used to cross an upgrade.
+fn check_checkpoints(
+    checkpoints: &[Groth16VkeyCheckpoint],
+    trusted: &TrustedSetup,
+) -> Result<(), VerifyError> {
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        if !trusted.program_vks.contains(&checkpoint.program_vk_hash) {
+            return Err(VerifyError::InvalidCheckpoint(i, "program vk is not trusted"));
+        }
+        if let Some(groth16_vk) = &checkpoint.groth16_vk {
+            if !trusted.groth16_vks.contains(&groth16_vk.as_slice()) {
+                return Err(VerifyError::InvalidCheckpoint(i, "groth16 vk is not trusted"));
+            }
+        }
+        // The genesis hop has no previous proof, so it can never be an upgrade.
+        if checkpoint.block_hash == trusted.genesis_hash {
+            return Err(VerifyError::InvalidCheckpoint(i, "upgrade at genesis"));
+        }
+        // Each upgrade happens on a later hop than the one before it.
+        if checkpoints[..i]
+            .iter()
+            .any(|earlier| earlier.block_hash == checkpoint.block_hash)
+        {
+            return Err(VerifyError::InvalidCheckpoint(i, "duplicate upgrade block"));
+        }
+    }
+    Ok(())
+}
+
+/// The program vk hash in the `0x`-prefixed bn254 form expected by [`Groth16Verifier`].
+///
+/// This is what `HashableKey::bytes32` returns on the host: the eight BabyBear words packed 31 bits
+/// at a time into one big-endian integer. They fit in 248 bits, so no field reduction happens.
+pub fn program_vk_hash_hex(vk_digest: &[u32; 8]) -> String {
+    let mut packed = [0u8; 32];
+    for word in vk_digest {
+        // packed = packed * 2^31 + word
+        let mut carry = *word as u64;
+        for byte in packed.iter_mut().rev() {
+            let value = ((*byte as u64) << 31) + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+    }
+
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::from("0x");
+    for byte in packed {
+        hex.push(HEX[(byte >> 4) as usize] as char);
+        hex.push(HEX[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use common::RecursionPublicValues;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use serde_json::Value;
+    use sp1_sdk::HashableKey;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// A real SP1 (v3.0.0) Groth16 proof of the fibonacci example, with the Groth16 vk it was
+    /// wrapped under, from sp1-verifier's test binaries.
+    const FIBONACCI: &str = include_str!("../tests/fixtures/fibonacci_groth16.json");
+
+    /// A Groth16 proof of recursion public values under a test Groth16 vk, whose trapdoor is
+    /// known: real recursion proofs need the prover. Its program vk is the fibonacci one.
+    /// `examples/recursion_fixture.rs` regenerates it.
+    const RECURSION: &str = include_str!("../tests/fixtures/recursion_groth16.json");
+
+    const GENESIS: [u8; 32] = [1; 32];
+    const PROGRAM_VK: [u32; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    /// A vk digest, hashed by the SDK's own `HashableKey` methods.
+    struct Digest([u32; 8]);
+
+    impl HashableKey for Digest {
+        fn hash_babybear(&self) -> [BabyBear; 8] {
+            self.0.map(BabyBear::from_canonical_u32)
+        }
+
+        fn hash_u32(&self) -> [u32; 8] {
+            self.0
+        }
+    }
+
+    fn fixture(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn bytes(fixture: &Value, field: &str) -> Vec<u8> {
+        hex::decode(fixture[field].as_str().unwrap()).unwrap()
+    }
+
+    fn program_vk(fixture: &Value) -> [u32; 8] {
+        serde_json::from_value(fixture["program_vk"].clone()).unwrap()
+    }
+
+    fn checkpoint(block_hash: [u8; 32], groth16_vk: Option<Vec<u8>>) -> Groth16VkeyCheckpoint {
+        Groth16VkeyCheckpoint {
+            block_hash,
+            groth16_vk,
+            program_vk_hash: PROGRAM_VK,
+        }
+    }
+
+    fn public_values(checkpoints: &[Groth16VkeyCheckpoint]) -> Vec<u8> {
+        bincode::serialize(&RecursionPublicValues {
+            checkpoints_hash: checkpoint_history_hash(checkpoints),
+            genesis_hash: GENESIS.to_vec(),
+            h2_hash: vec![3; 32],
+            vk_digest: PROGRAM_VK,
+            path_root: [4; 32],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_what_the_program_commits() {
+        let encoded = public_values(&[checkpoint([2; 32], Some(vec![5; 3]))]);
+        let decoded = decode_public_values(&encoded).unwrap();
+        assert_eq!(
+            decoded,
+            bincode::deserialize::<RecursionPublicValues>(&encoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_or_extended_public_values() {
+        let encoded = public_values(&[]);
+        for len in 0..encoded.len() {
+            assert!(
+                matches!(
+                    decode_public_values(&encoded[..len]),
+                    Err(VerifyError::MalformedPublicValues)
+                ),
+                "decoded {} of {} bytes",
+                len,
+                encoded.len()
+            );
+        }
+        let mut extended = encoded;
+        extended.push(0);
+        assert!(decode_public_values(&extended).is_err());
+    }
+
+    #[test]
+    fn program_vk_hash_hex_is_bytes32() {
+        let fibonacci = fixture(FIBONACCI);
+        let vk = program_vk(&fibonacci);
+        assert_eq!(program_vk_hash_hex(&vk), Digest(vk).bytes32());
+        assert_eq!(
+            program_vk_hash_hex(&vk),
+            fibonacci["program_vk_hash"].as_str().unwrap()
+        );
+        // The largest BabyBear digest still packs without reduction.
+        let max = [2013265920; 8];
+        assert_eq!(program_vk_hash_hex(&max), Digest(max).bytes32());
+    }
+
+    #[test]
+    fn program_vk_hash_hex_verifies_a_real_proof() {
+        let fibonacci = fixture(FIBONACCI);
+        let proof = bytes(&fibonacci, "proof");
+        let public_values = bytes(&fibonacci, "public_values");
+        let groth16_vk = bytes(&fibonacci, "groth16_vk");
+        let vk_hash = program_vk_hash_hex(&program_vk(&fibonacci));
+        Groth16Verifier::verify(&proof, &public_values, &vk_hash, &groth16_vk).unwrap();
+
+        let mut tampered = public_values;
+        tampered[0] ^= 1;
+        assert!(Groth16Verifier::verify(&proof, &tampered, &vk_hash, &groth16_vk).is_err());
+    }
+
+    #[test]
+    fn verifies_a_recursion_proof() {
+        let recursion = fixture(RECURSION);
+        let genesis_hash: [u8; 32] = bytes(&recursion, "genesis_hash").try_into().unwrap();
+        let program_vks = [program_vk(&recursion)];
+        let trusted = TrustedSetup {
+            genesis_hash,
+            program_vks: &program_vks,
+            groth16_vks: &[],
+        };
+        let head = verify_groth16_proof(
+            &bytes(&recursion, "proof"),
+            &bytes(&recursion, "public_values"),
+            &bytes(&recursion, "groth16_vk"),
+            &[],
+            &trusted,
+        )
+        .unwrap();
+        assert_eq!(head.genesis_hash, genesis_hash);
+        assert_eq!(head.program_vk, program_vks[0]);
+        assert_eq!(head.path_root, [7; 32]);
+    }
+
+    #[test]
+    fn rejects_another_genesis() {
+        let trusted = TrustedSetup {
+            genesis_hash: [9; 32],
+            program_vks: &[PROGRAM_VK],
+            groth16_vks: &[],
+        };
+        let result = verify_groth16_proof(&[], &public_values(&[]), &[], &[], &trusted);
+        assert!(matches!(result, Err(VerifyError::GenesisHashMismatch)));
+    }
+
+    #[test]
+    fn rejects_another_history() {
+        let trusted = TrustedSetup {
+            genesis_hash: GENESIS,
+            program_vks: &[PROGRAM_VK],
+            groth16_vks: &[],
+        };
+        let committed = [checkpoint([2; 32], None)];
+        let values = public_values(&committed);
+        for history in [&[][..], &[checkpoint([5; 32], None)]] {
+            let result = verify_groth16_proof(&[], &values, &[], history, &trusted);
+            assert!(matches!(
+                result,
+                Err(VerifyError::CheckpointHistoryMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn checks_every_checkpoint() {
+        let groth16_vk: &[u8] = &[5; 3];
+        let trusted = TrustedSetup {
+            genesis_hash: GENESIS,
+            program_vks: &[PROGRAM_VK],
+            groth16_vks: &[groth16_vk],
+        };
+        let valid = checkpoint([2; 32], Some(groth16_vk.to_vec()));
+        assert!(check_checkpoints(&[valid.clone(), checkpoint([3; 32], None)], &trusted).is_ok());
+
+        let untrusted_program = Groth16VkeyCheckpoint {
+            program_vk_hash: [9; 8],
+            ..checkpoint([3; 32], None)
+        };
+        let cases = [
+            (untrusted_program, "program vk is not trusted"),
+            (
+                checkpoint([3; 32], Some(vec![6; 3])),
+                "groth16 vk is not trusted",
+            ),
+            (checkpoint(GENESIS, None), "upgrade at genesis"),
+            (checkpoint([2; 32], None), "duplicate upgrade block"),
+        ];
+        for (invalid, reason) in cases {
+            let result = check_checkpoints(&[valid.clone(), invalid], &trusted);
+            assert!(
+                matches!(result, Err(VerifyError::InvalidCheckpoint(1, r)) if r == reason),
+                "{}",
+                reason
+            );
+        }
+    }
+}